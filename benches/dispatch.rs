@@ -0,0 +1,79 @@
+use clap::ValueEnum;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use rr::clock::ManualClock;
+use rr::executor::MockExecutor;
+use rr::scheduler::{self, Policy};
+use rr::simulator::Simulator;
+use rr::task::Task;
+use std::hint::black_box;
+use std::num::NonZeroUsize;
+use std::sync::mpsc;
+use std::time::Duration;
+
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+const POLICIES: [Policy; 2] = [Policy::Priority, Policy::Random];
+const SLOTS: usize = 256;
+
+fn synthetic_tasks(n: usize) -> Vec<Task> {
+    (0..n)
+        .map(|i| Task::new(format!("task-{}", i), None, (i % 8) as u8))
+        .collect()
+}
+
+fn policy_name(policy: Policy) -> String {
+    policy.to_possible_value().unwrap().get_name().to_string()
+}
+
+fn dispatch_decisions(c: &mut Criterion) {
+    rr::output::set_quiet(true);
+    let mut group = c.benchmark_group("rank");
+
+    for policy in POLICIES {
+        for n in SIZES {
+            let tasks = synthetic_tasks(n);
+            let ready: Vec<&Task> = tasks.iter().collect();
+            let mut scheduler = scheduler::build(policy, Some(1));
+
+            group.throughput(Throughput::Elements(n as u64));
+            group.bench_with_input(
+                BenchmarkId::new(policy_name(policy), n),
+                &ready,
+                |b, ready| b.iter(|| black_box(scheduler.rank(black_box(ready)))),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn simulated_runs(c: &mut Criterion) {
+    rr::output::set_quiet(true);
+    let mut group = c.benchmark_group("run");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(20));
+
+    for policy in POLICIES {
+        for n in SIZES {
+            group.throughput(Throughput::Elements(n as u64));
+            group.bench_function(BenchmarkId::new(policy_name(policy), n), |b| {
+                b.iter_batched(
+                    || {
+                        let (_control_tx, control_rx) = mpsc::channel();
+                        Simulator::new(synthetic_tasks(n), control_rx)
+                            .executor(MockExecutor::new())
+                            .clock(ManualClock::new())
+                            .scheduler(scheduler::build(policy, Some(1)))
+                            .concurrency(NonZeroUsize::new(SLOTS).unwrap())
+                    },
+                    |mut simulator| simulator.run(),
+                    BatchSize::PerIteration,
+                );
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, dispatch_decisions, simulated_runs);
+criterion_main!(benches);
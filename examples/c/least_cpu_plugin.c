@@ -0,0 +1,21 @@
+#include <stdlib.h>
+
+#include "psched_plugin.h"
+
+static const psched_task_t *ranked;
+
+static int by_cpu_time(const void *a, const void *b) {
+    uint64_t left = ranked[*(const size_t *)a].cpu_time_ms;
+    uint64_t right = ranked[*(const size_t *)b].cpu_time_ms;
+    return (left > right) - (left < right);
+}
+
+const char *psched_policy_name(void) {
+    return "least-cpu";
+}
+
+int psched_policy_rank(const psched_task_t *tasks, size_t len, size_t *order) {
+    ranked = tasks;
+    qsort(order, len, sizeof *order, by_cpu_time);
+    return 0;
+}
@@ -0,0 +1,30 @@
+#include <stdio.h>
+
+#include "psched.h"
+
+static const char *kinds[] = {"admitted", "dispatched", "preempted", "terminated"};
+
+int main(void) {
+    psched_t *psched = psched_create(1, 1);
+
+    psched_script(psched, "editor", 3, 0);
+    psched_script(psched, "compiler", 2, 1);
+    psched_add_task(psched, "editor", 2);
+    psched_add_task(psched, "compiler", 1);
+
+    while (psched_step(psched) == 1) {
+    }
+
+    psched_event_t event;
+    while (psched_poll_event(psched, &event) == 1) {
+        printf("%3llu  task %llu  %s", (unsigned long long)event.quantum,
+               (unsigned long long)event.task, kinds[event.kind]);
+        if (event.kind == PSCHED_TERMINATED) {
+            printf(" (exit %d)", event.exit_code);
+        }
+        printf("\n");
+    }
+
+    psched_destroy(psched);
+    return 0;
+}
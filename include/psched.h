@@ -0,0 +1,43 @@
+#ifndef PSCHED_H
+#define PSCHED_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define PSCHED_ADMITTED 0
+#define PSCHED_DISPATCHED 1
+#define PSCHED_PREEMPTED 2
+#define PSCHED_TERMINATED 3
+#define PSCHED_SUSPENDED 4
+#define PSCHED_RESUMED 5
+#define PSCHED_THROTTLED 6
+#define PSCHED_UNTHROTTLED 7
+
+typedef struct Psched psched_t;
+
+typedef struct {
+    uint64_t quantum;
+    uint64_t task;
+    uint32_t kind;
+    uint8_t backfilled;
+    int32_t exit_code;
+} psched_event_t;
+
+psched_t *psched_create(size_t concurrency, int simulated);
+void psched_destroy(psched_t *psched);
+
+int psched_script(psched_t *psched, const char *path, uint32_t quanta, int exit_code);
+int64_t psched_add_task(psched_t *psched, const char *path, uint8_t priority);
+
+int psched_step(psched_t *psched);
+int psched_poll_event(psched_t *psched, psched_event_t *event);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
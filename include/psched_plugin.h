@@ -0,0 +1,29 @@
+#ifndef PSCHED_PLUGIN_H
+#define PSCHED_PLUGIN_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct {
+    uint8_t priority;
+    uint8_t effective_priority;
+    size_t width;
+    uint64_t cpu_time_ms;
+    int64_t remaining_estimate_ms;
+} psched_task_t;
+
+/* Implemented by the plugin. */
+const char *psched_policy_name(void);
+
+/* Write a permutation of 0..len into order, best candidate first. Return 0 on success. */
+int psched_policy_rank(const psched_task_t *tasks, size_t len, size_t *order);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,226 @@
+use crate::registry::Registry;
+use crate::task::Task;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use ulid::Ulid;
+
+pub fn conflicts(a: &Task, b: &Task) -> bool {
+    let apart = |task: &Task, other: &Task| {
+        task.get_spec()
+            .apart_from
+            .iter()
+            .any(|reference| other.is_called(reference))
+    };
+    a.get_id() != b.get_id() && (apart(a, b) || apart(b, a))
+}
+
+#[derive(Default)]
+struct Stats {
+    label: String,
+    same_core: u64,
+    other_core: u64,
+    held_back: u64,
+    together: u64,
+    migrations: u64,
+}
+
+#[derive(Default)]
+pub struct Affinity {
+    active: bool,
+    tracking: bool,
+    cores: HashMap<Ulid, usize>,
+    stats: BTreeMap<Ulid, Stats>,
+    placements: u64,
+    migrations: u64,
+    cost: Option<Duration>,
+}
+
+impl Affinity {
+    pub fn admitted(&mut self, task: &Task) {
+        let spec = task.get_spec();
+        if spec.near.is_some() || !spec.apart_from.is_empty() {
+            self.active = true;
+            self.stats.entry(task.get_id()).or_default().label = task.label();
+        }
+    }
+
+    pub fn track_cores(&mut self) {
+        self.tracking = true;
+    }
+
+    pub fn migration_cost(&mut self, cost: Duration) {
+        self.tracking = true;
+        self.cost = Some(cost);
+    }
+
+    pub fn penalty(&self) -> Duration {
+        self.cost.unwrap_or_default() * self.migrations as u32
+    }
+
+    pub fn core(&self, id: Ulid) -> Option<usize> {
+        self.cores.get(&id).copied()
+    }
+
+    pub fn task_migrations(&self, id: Ulid) -> u64 {
+        self.stats.get(&id).map_or(0, |stats| stats.migrations)
+    }
+
+    pub fn migrations(&self) -> (u64, u64) {
+        (self.migrations, self.placements)
+    }
+
+    pub fn held_back(&mut self, task: &Task) {
+        let stats = self.stats.entry(task.get_id()).or_default();
+        stats.label = task.label();
+        stats.held_back += 1;
+    }
+
+    pub fn violations(&self) -> (u64, u64) {
+        self.stats
+            .values()
+            .fold((0, 0), |(together, other_core), stats| {
+                (together + stats.together, other_core + stats.other_core)
+            })
+    }
+
+    pub fn place(&mut self, tasks: &Registry, running: &[Ulid], cores: usize) {
+        if !self.active && !self.tracking {
+            return;
+        }
+
+        let mut free = vec![true; cores];
+        let mut placed: HashMap<Ulid, usize> = HashMap::new();
+        let mut order = running.to_vec();
+        order.sort_by_key(|&id| tasks[id].get_spec().near.is_some());
+
+        for id in order {
+            let task = &tasks[id];
+            let target = task.get_spec().near.as_deref().and_then(|near| {
+                tasks
+                    .iter()
+                    .find(|other| other.get_id() != id && other.is_called(near))
+            });
+            let wanted = target
+                .and_then(|target| {
+                    let target = target.get_id();
+                    placed.get(&target).or(self.cores.get(&target))
+                })
+                .copied();
+            let preferred = wanted.or(self.cores.get(&id).copied());
+
+            let core = preferred
+                .filter(|&core| free.get(core).copied().unwrap_or(false))
+                .or_else(|| free.iter().position(|&slot| slot))
+                .unwrap_or(0);
+            let mut width = task.get_spec().width;
+            for slot in (core..cores).chain(0..core) {
+                if width > 0 && free[slot] {
+                    free[slot] = false;
+                    width -= 1;
+                }
+            }
+            placed.insert(id, core);
+            if let Some(&last) = self.cores.get(&id) {
+                self.placements += 1;
+                if last != core {
+                    self.migrations += 1;
+                    let stats = self.stats.entry(id).or_default();
+                    stats.label = task.label();
+                    stats.migrations += 1;
+                }
+            }
+
+            let (Some(target), Some(wanted)) = (target, wanted) else {
+                continue;
+            };
+            let stats = self.stats.entry(id).or_default();
+            if core == wanted {
+                stats.same_core += 1;
+            } else {
+                stats.other_core += 1;
+                crate::say!(
+                    "Affinity: PID: {} runs on core {}, not on core {} near PID: {}",
+                    task.label(),
+                    core,
+                    wanted,
+                    target.label()
+                );
+            }
+        }
+
+        for (i, &a) in running.iter().enumerate() {
+            for &b in &running[i + 1..] {
+                if !conflicts(&tasks[a], &tasks[b]) {
+                    continue;
+                }
+                crate::say!(
+                    "Affinity: PID: {} and PID: {} run in the same quantum",
+                    tasks[a].label(),
+                    tasks[b].label()
+                );
+                self.stats.entry(a).or_default().together += 1;
+            }
+        }
+        self.cores.extend(placed);
+    }
+
+    pub fn print_report(&self, tasks: &Registry) {
+        if !self.active && !self.tracking {
+            return;
+        }
+
+        crate::report!(
+            "------------------------------------------\n\
+             AFFINITY REPORT"
+        );
+        for (&id, stats) in &self.stats {
+            let spec = tasks[id].get_spec();
+            crate::report!("PID:            {}", stats.label);
+            if let Some(near) = &spec.near {
+                crate::report!(
+                    "Near:           {} (same core in {} of {} quanta)",
+                    near,
+                    stats.same_core,
+                    stats.same_core + stats.other_core
+                );
+            }
+            if !spec.apart_from.is_empty() || stats.held_back > 0 {
+                crate::report!(
+                    "Apart From:     {} (held back {} quanta)",
+                    if spec.apart_from.is_empty() {
+                        "-".to_string()
+                    } else {
+                        spec.apart_from.join(", ")
+                    },
+                    stats.held_back
+                );
+            }
+            if let (Some(cost), true) = (self.cost, stats.migrations > 0) {
+                crate::report!(
+                    "Migrations:     {} ({} seconds of migration cost)",
+                    stats.migrations,
+                    (cost * stats.migrations as u32).as_secs_f64()
+                );
+            }
+        }
+        if self.tracking {
+            crate::report!(
+                "Migrations:     {} of {} resumed quanta moved to another core",
+                self.migrations,
+                self.placements
+            );
+        }
+        if self.cost.is_some() {
+            crate::report!("Migration Cost: {} seconds", self.penalty().as_secs_f64());
+        }
+        let (together, other_core) = self.violations();
+        if self.active {
+            crate::report!(
+                "Violations:     {} quanta together despite --apart-from, {} quanta off the preferred core",
+                together,
+                other_core
+            );
+        }
+        crate::report!("------------------------------------------");
+    }
+}
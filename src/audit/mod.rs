@@ -0,0 +1,186 @@
+use crate::control::{local_owner, quote_words};
+use crate::task::Task;
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::str::FromStr;
+
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+const SYSLOG_SOCKET: &str = "/dev/log";
+const LOG_DAEMON_INFO: u8 = 3 << 3 | 6;
+const IDENTIFIER: &str = "psched";
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Sink {
+    Journald,
+    Syslog,
+}
+
+impl FromStr for Sink {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "journald" => Ok(Sink::Journald),
+            "syslog" => Ok(Sink::Syslog),
+            _ => Err(format!(
+                "Unknown audit sink: {} (expected journald or syslog)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Action {
+    Submit,
+    Dispatch,
+    Kill,
+    Terminate,
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Submit => write!(f, "submit"),
+            Action::Dispatch => write!(f, "dispatch"),
+            Action::Kill => write!(f, "kill"),
+            Action::Terminate => write!(f, "terminate"),
+        }
+    }
+}
+
+pub struct Audit {
+    sink: Sink,
+    socket: UnixDatagram,
+    local_user: String,
+}
+
+fn journal_field(out: &mut Vec<u8>, key: &str, value: &str) {
+    out.extend_from_slice(key.as_bytes());
+    out.push(b'\n');
+    out.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    out.extend_from_slice(value.as_bytes());
+    out.push(b'\n');
+}
+
+impl Audit {
+    pub fn open(sink: Sink) -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(match sink {
+            Sink::Journald => JOURNAL_SOCKET,
+            Sink::Syslog => SYSLOG_SOCKET,
+        })?;
+
+        Ok(Self {
+            sink,
+            socket,
+            local_user: local_owner(),
+        })
+    }
+
+    fn owner<'a>(&'a self, task: &'a Task) -> &'a str {
+        task.get_spec().owner.as_deref().unwrap_or(&self.local_user)
+    }
+
+    fn message(&self, action: Action, task: &Task) -> String {
+        let mut command = vec![task.get_path_to_binary().display().to_string()];
+        command.extend(task.get_spec().args.iter().flatten().cloned());
+
+        let mut message = format!(
+            "{} task={} owner={} command={}",
+            action,
+            task.get_id(),
+            self.owner(task),
+            quote_words(&command)
+        );
+        if let (Action::Terminate, Some(exit_code)) = (action, task.exit_code) {
+            message.push_str(&format!(" exit_code={}", exit_code));
+        }
+        message
+    }
+
+    fn datagram(&self, action: Action, task: &Task) -> Vec<u8> {
+        let message = self.message(action, task);
+
+        match self.sink {
+            Sink::Syslog => format!(
+                "<{}>{}[{}]: {}",
+                LOG_DAEMON_INFO,
+                IDENTIFIER,
+                std::process::id(),
+                message
+            )
+            .into_bytes(),
+            Sink::Journald => {
+                let mut out = Vec::new();
+                journal_field(&mut out, "MESSAGE", &message);
+                journal_field(&mut out, "PRIORITY", "6");
+                journal_field(&mut out, "SYSLOG_IDENTIFIER", IDENTIFIER);
+                journal_field(&mut out, "PSCHED_ACTION", &action.to_string());
+                journal_field(&mut out, "PSCHED_TASK", &task.get_id().to_string());
+                journal_field(&mut out, "PSCHED_OWNER", self.owner(task));
+                out
+            }
+        }
+    }
+
+    pub fn record(&self, action: Action, task: &Task) {
+        if let Err(err) = self.socket.send(&self.datagram(action, task)) {
+            eprintln!("Failed to write audit record: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audit(sink: Sink) -> (Audit, UnixDatagram) {
+        let (socket, peer) = UnixDatagram::pair().unwrap();
+        let audit = Audit {
+            sink,
+            socket,
+            local_user: "operator".to_string(),
+        };
+        (audit, peer)
+    }
+
+    fn received(peer: &UnixDatagram) -> Vec<u8> {
+        let mut buf = [0; 1024];
+        let len = peer.recv(&mut buf).unwrap();
+        buf[..len].to_vec()
+    }
+
+    #[test]
+    fn syslog_lines_name_the_task_and_its_owner() {
+        let (audit, peer) = audit(Sink::Syslog);
+        let mut task = Task::new("/bin/echo", Some(vec!["hi there".to_string()]), 2);
+        task.exit_code = Some(crate::task::ExitCode::Success);
+
+        audit.record(Action::Terminate, &task);
+        let line = String::from_utf8(received(&peer)).unwrap();
+
+        assert!(line.starts_with("<30>psched["));
+        assert!(line.ends_with(&format!(
+            "]: terminate task={} owner=operator command=/bin/echo 'hi there' exit_code=0 (SUCCESS)",
+            task.get_id()
+        )));
+    }
+
+    #[test]
+    fn journal_fields_are_length_prefixed() {
+        let (audit, peer) = audit(Sink::Journald);
+        let task = Task::from_spec(crate::task::Spec {
+            owner: Some("alice".to_string()),
+            ..crate::task::Spec::new("/bin/ls", None, 1)
+        });
+
+        audit.record(Action::Dispatch, &task);
+        let datagram = received(&peer);
+
+        let mut expected = Vec::new();
+        journal_field(&mut expected, "PSCHED_OWNER", "alice");
+        assert!(datagram.ends_with(&expected));
+        assert!(datagram.starts_with(b"MESSAGE\n"));
+    }
+}
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Slot {
+    pub width: usize,
+    pub remaining: Option<Duration>,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Start {
+    pub index: usize,
+    pub backfilled: bool,
+}
+
+fn reservation(running: &[Slot], width: usize, free: usize) -> Option<(Duration, usize)> {
+    let mut running = running.to_vec();
+    running.sort_by_key(|slot| slot.remaining.unwrap_or(Duration::MAX));
+
+    let mut available = free;
+    for slot in running {
+        let remaining = slot.remaining?;
+        available += slot.width;
+        if available >= width {
+            return Some((remaining, available - width));
+        }
+    }
+
+    None
+}
+
+pub fn plan(running: &[Slot], queue: &[Slot], mut free: usize) -> Vec<Start> {
+    let mut running = running.to_vec();
+    let mut starts = Vec::new();
+
+    let mut position = 0;
+    while position < queue.len() && queue[position].width <= free {
+        free -= queue[position].width;
+        running.push(queue[position]);
+        starts.push(Start {
+            index: position,
+            backfilled: false,
+        });
+        position += 1;
+    }
+
+    let Some(head) = queue.get(position) else {
+        return starts;
+    };
+
+    let Some((shadow, mut extra)) = reservation(&running, head.width, free) else {
+        return starts;
+    };
+
+    for (index, candidate) in queue.iter().enumerate().skip(position + 1) {
+        if candidate.width > free {
+            continue;
+        }
+
+        let ends_before_shadow = candidate.remaining.is_some_and(|r| r <= shadow);
+        if ends_before_shadow || candidate.width <= extra {
+            free -= candidate.width;
+            if !ends_before_shadow {
+                extra -= candidate.width;
+            }
+            starts.push(Start {
+                index,
+                backfilled: true,
+            });
+        }
+    }
+
+    starts
+}
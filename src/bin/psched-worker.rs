@@ -0,0 +1,39 @@
+use clap::Parser;
+use rr::burst::Program;
+use rr::worker::Worker;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(
+    name = "psched-worker",
+    about = "Burn CPU, sleep or do file I/O following a burst program"
+)]
+struct Args {
+    #[arg(long, default_value_t = 0)]
+    exit: u8,
+
+    #[arg(long, env = "PSCHED_WORKER_DIR", value_name = "DIR")]
+    scratch: Option<PathBuf>,
+
+    #[arg(required = true, value_name = "PROGRAM", allow_hyphen_values = true)]
+    program: Vec<String>,
+}
+
+fn main() {
+    let args = Args::parse();
+    let program: Program = match args.program.join(" ").parse() {
+        Ok(program) => program,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+    let mut worker = Worker::new(args.scratch.unwrap_or_else(std::env::temp_dir));
+    if let Err(err) = worker.run(&program) {
+        eprintln!("{}", err);
+        drop(worker);
+        std::process::exit(1);
+    }
+    drop(worker);
+    std::process::exit(args.exit.into());
+}
@@ -0,0 +1,276 @@
+use crate::clock;
+use std::collections::HashSet;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Phase {
+    Cpu(Duration),
+    Io { device: String, duration: Duration },
+    Sleep(Duration),
+    Acquire(String),
+    Release(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    pub phases: Vec<Phase>,
+}
+
+fn phase(words: &[&str]) -> Result<Phase, String> {
+    let duration = |value: &str| clock::parse_duration(value);
+    match words {
+        ["cpu", value] => Ok(Phase::Cpu(duration(value)?)),
+        ["io", value] => Ok(Phase::Io {
+            device: "io".to_string(),
+            duration: duration(value)?,
+        }),
+        ["io", device, value] => Ok(Phase::Io {
+            device: device.to_string(),
+            duration: duration(value)?,
+        }),
+        ["sleep", value] => Ok(Phase::Sleep(duration(value)?)),
+        ["acquire", lock] => Ok(Phase::Acquire(lock.to_string())),
+        ["release", lock] => Ok(Phase::Release(lock.to_string())),
+        [] => Err("empty phase".to_string()),
+        _ => Err(format!(
+            "Unknown phase: {} (expected cpu DURATION, io [DEVICE] DURATION, sleep DURATION, acquire LOCK or release LOCK)",
+            words.join(" ")
+        )),
+    }
+}
+
+impl std::str::FromStr for Program {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut phases = Vec::new();
+        let mut held = HashSet::new();
+        for part in s.split(';') {
+            let words: Vec<&str> = part.split_whitespace().collect();
+            if words.is_empty() && !phases.is_empty() {
+                continue;
+            }
+            let phase = phase(&words)?;
+            match &phase {
+                Phase::Acquire(lock) if !held.insert(lock.clone()) => {
+                    return Err(format!("{} is acquired twice", lock));
+                }
+                Phase::Release(lock) if !held.remove(lock) => {
+                    return Err(format!("{} is released without being acquired", lock));
+                }
+                _ => {}
+            }
+            phases.push(phase);
+        }
+        Ok(Self { phases })
+    }
+}
+
+impl std::fmt::Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let nanos = |duration: &Duration| duration.as_nanos();
+        match self {
+            Phase::Cpu(duration) => write!(f, "cpu {}ns", nanos(duration)),
+            Phase::Io { device, duration } => write!(f, "io {} {}ns", device, nanos(duration)),
+            Phase::Sleep(duration) => write!(f, "sleep {}ns", nanos(duration)),
+            Phase::Acquire(lock) => write!(f, "acquire {}", lock),
+            Phase::Release(lock) => write!(f, "release {}", lock),
+        }
+    }
+}
+
+impl std::fmt::Display for Program {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let phases: Vec<String> = self.phases.iter().map(Phase::to_string).collect();
+        f.write_str(&phases.join("; "))
+    }
+}
+
+impl Program {
+    pub fn cpu(duration: Duration) -> Self {
+        Self {
+            phases: vec![Phase::Cpu(duration)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::executor::{Executor, MockExecutor};
+    use crate::simulator::Simulator;
+    use crate::task::{self, ExitCode, Spec, Task};
+    use crate::trace::Kind;
+    use std::num::NonZeroUsize;
+    use std::sync::mpsc;
+    use ulid::Ulid;
+
+    const QUANTUM: Duration = Duration::from_millis(10);
+
+    fn run(programs: &[(&str, &str, u8)], concurrency: usize) -> Simulator {
+        let mut simulator = simulator(programs, concurrency);
+        simulator.run();
+        simulator
+    }
+
+    fn simulator(programs: &[(&str, &str, u8)], concurrency: usize) -> Simulator {
+        let mut executor = MockExecutor::new();
+        let mut tasks = Vec::new();
+        for &(name, program, priority) in programs {
+            executor = executor.program(name, &program.parse().unwrap(), QUANTUM);
+            tasks.push(Task::new(name, None, priority));
+        }
+        crate::output::set_quiet(true);
+        let (_control_tx, control_rx) = mpsc::channel();
+        Simulator::new(tasks, control_rx)
+            .quantum(QUANTUM)
+            .concurrency(NonZeroUsize::new(concurrency).unwrap())
+            .clock(ManualClock::new())
+            .executor(executor)
+            .record_trace()
+    }
+
+    fn events(simulator: &Simulator, wanted: fn(&Kind) -> bool) -> Vec<(u64, String)> {
+        simulator
+            .events()
+            .iter()
+            .filter(|event| wanted(&event.kind))
+            .map(|event| (event.quantum, simulator.tasks()[event.task].name()))
+            .collect()
+    }
+
+    fn exit_code(simulator: &Simulator, name: &str) -> Option<ExitCode> {
+        let task = simulator.tasks().iter().find(|t| t.name() == name);
+        task.and_then(|task| task.exit_code)
+    }
+
+    #[test]
+    fn programs_parse_into_phases() {
+        let program: Program = "cpu 40ms; io disk 20ms; acquire a; cpu 5ms; release a;"
+            .parse()
+            .unwrap();
+        assert_eq!(program.phases.len(), 5);
+        assert_eq!(
+            program.phases[1],
+            Phase::Io {
+                device: "disk".to_string(),
+                duration: Duration::from_millis(20),
+            }
+        );
+        assert_eq!(program.to_string().parse::<Program>(), Ok(program));
+        assert!("cpu 1ms; release a".parse::<Program>().is_err());
+        assert!("nap 1ms".parse::<Program>().is_err());
+    }
+
+    #[test]
+    fn a_task_blocked_on_io_gives_up_its_slot() {
+        let simulator = run(
+            &[
+                ("reader", "cpu 10ms; io 30ms; cpu 10ms", 1),
+                ("hog", "cpu 60ms", 2),
+            ],
+            1,
+        );
+        let dispatched: Vec<String> =
+            events(&simulator, |kind| matches!(kind, Kind::Dispatched { .. }))
+                .into_iter()
+                .map(|(_, name)| name)
+                .collect();
+        assert_eq!(
+            dispatched,
+            ["reader", "hog", "hog", "hog", "reader", "hog", "hog", "hog"]
+        );
+        assert_eq!(
+            events(&simulator, |kind| matches!(kind, Kind::Terminated { .. })),
+            [(5, "reader".to_string()), (8, "hog".to_string())]
+        );
+    }
+
+    #[test]
+    fn a_lock_is_held_by_one_task_at_a_time() {
+        let simulator = run(
+            &[
+                ("first", "acquire lock; cpu 30ms; release lock", 1),
+                ("second", "acquire lock; cpu 10ms; release lock", 1),
+            ],
+            2,
+        );
+        assert_eq!(
+            events(&simulator, |kind| matches!(kind, Kind::Terminated { .. })),
+            [(3, "first".to_string()), (4, "second".to_string())]
+        );
+        let second: Vec<u64> = events(&simulator, |kind| matches!(kind, Kind::Dispatched { .. }))
+            .into_iter()
+            .filter(|(_, name)| name == "second")
+            .map(|(quantum, _)| quantum)
+            .collect();
+        assert_eq!(second, [1, 4]);
+
+        let program: Program = "acquire lock; cpu 20ms".parse().unwrap();
+        let mut executor = MockExecutor::new()
+            .program("a", &program, QUANTUM)
+            .program("b", &program, QUANTUM);
+        let a = executor
+            .spawn(Ulid::new(), &Spec::new("a", None, 1))
+            .unwrap();
+        let b = executor
+            .spawn(Ulid::new(), &Spec::new("b", None, 1))
+            .unwrap();
+        assert_eq!((executor.blocked(a), executor.blocked(b)), (None, None));
+        executor.poll(a).unwrap();
+        assert_eq!(executor.blocked(b), Some(task::BlockReason::Resource));
+        assert_eq!(
+            (executor.lock_holder(a), executor.lock_holder(b)),
+            (None, Some(a))
+        );
+    }
+
+    #[test]
+    fn a_lock_holder_inherits_the_priority_of_the_task_waiting_on_it() {
+        let programs = [
+            ("low", "acquire lock; cpu 50ms; release lock", 3),
+            ("high", "io 20ms; acquire lock; cpu 10ms; release lock", 1),
+            ("hog", "io 10ms; cpu 50ms", 2),
+        ];
+        let terminated = |donate: bool| {
+            let mut simulator = simulator(&programs, 1).donate_priority(donate);
+            simulator.run();
+            events(&simulator, |kind| matches!(kind, Kind::Terminated { .. }))
+                .into_iter()
+                .map(|(_, name)| name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(terminated(false), ["hog", "low", "high"]);
+        assert_eq!(terminated(true), ["low", "high", "hog"]);
+    }
+
+    #[test]
+    fn crossed_locks_fail_one_task_with_a_deadlock() {
+        let simulator = run(
+            &[
+                (
+                    "left",
+                    "acquire x; cpu 20ms; acquire y; cpu 10ms; release y; release x",
+                    1,
+                ),
+                (
+                    "right",
+                    "acquire y; cpu 20ms; acquire x; cpu 10ms; release x; release y",
+                    1,
+                ),
+            ],
+            2,
+        );
+        let terminated = events(&simulator, |kind| matches!(kind, Kind::Terminated { .. }));
+        let quanta: Vec<u64> = terminated.iter().map(|&(quantum, _)| quantum).collect();
+        assert_eq!(quanta, [2, 3]);
+        assert_eq!(
+            terminated
+                .iter()
+                .map(|(_, name)| exit_code(&simulator, name))
+                .collect::<Vec<_>>(),
+            [Some(ExitCode::Failure), Some(ExitCode::Success)]
+        );
+    }
+}
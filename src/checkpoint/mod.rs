@@ -0,0 +1,46 @@
+use crate::clock::Clock;
+use crate::registry::Registry;
+use crate::task::{self, Task};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct SimulatorCheckpoint {
+    version: u32,
+    tasks: Vec<task::Checkpoint>,
+}
+
+pub fn save(path: &Path, tasks: &Registry, clock: &dyn Clock) -> Result<(), Box<dyn Error>> {
+    let checkpoint = SimulatorCheckpoint {
+        version: VERSION,
+        tasks: tasks.iter().map(|task| task.checkpoint(clock)).collect(),
+    };
+
+    let writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(writer, &checkpoint)?;
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<Vec<Task>, Box<dyn Error>> {
+    let reader = BufReader::new(File::open(path)?);
+    let checkpoint: SimulatorCheckpoint = serde_json::from_reader(reader)?;
+
+    if checkpoint.version != VERSION {
+        return Err(format!(
+            "unsupported checkpoint version {} (expected {})",
+            checkpoint.version, VERSION
+        )
+        .into());
+    }
+
+    Ok(checkpoint
+        .tasks
+        .into_iter()
+        .map(Task::from_checkpoint)
+        .collect())
+}
@@ -0,0 +1,74 @@
+use super::{Clock, Instant};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep_until(&self, deadline: Instant) {
+        let mut now = self.now.lock().unwrap();
+        *now = (*now).max(deadline);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_moves_when_advanced() {
+        let clock = ManualClock::new();
+        let start = clock.now();
+
+        assert_eq!(clock.now(), start);
+
+        clock.advance(Duration::from_millis(150));
+        assert_eq!(clock.now() - start, Duration::from_millis(150));
+    }
+
+    #[test]
+    fn sleep_until_jumps_to_deadline_without_going_back() {
+        let clock = ManualClock::new();
+        let start = clock.now();
+
+        clock.sleep_until(start + Duration::from_secs(3));
+        assert_eq!(clock.now() - start, Duration::from_secs(3));
+
+        clock.sleep_until(start + Duration::from_secs(1));
+        assert_eq!(clock.now() - start, Duration::from_secs(3));
+    }
+
+    #[test]
+    fn clones_share_the_same_time() {
+        let clock = ManualClock::new();
+        let handle = clock.clone();
+
+        handle.advance(Duration::from_secs(2));
+        assert_eq!(clock.now(), handle.now());
+    }
+}
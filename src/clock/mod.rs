@@ -0,0 +1,93 @@
+#[cfg(any(test, feature = "testing"))]
+pub use manual::ManualClock;
+pub use scaled::{parse_scale, ScaledClock};
+use std::thread;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::{Instant, SystemTime, UNIX_EPOCH};
+#[cfg(target_arch = "wasm32")]
+pub use web_time::{Instant, SystemTime, UNIX_EPOCH};
+#[cfg(any(test, feature = "testing"))]
+mod manual;
+mod scaled;
+
+const SPIN: Duration = Duration::from_micros(100);
+
+pub trait Clock {
+    fn now(&self) -> Instant;
+
+    fn sleep_until(&self, deadline: Instant);
+
+    fn timeout(&self, _deadline: Instant) -> Option<Duration> {
+        None
+    }
+}
+
+pub struct RealClock;
+
+impl Clock for RealClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep_until(&self, deadline: Instant) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining > SPIN {
+            thread::sleep(remaining - SPIN);
+        }
+        while Instant::now() < deadline {
+            thread::yield_now();
+        }
+    }
+
+    fn timeout(&self, deadline: Instant) -> Option<Duration> {
+        Some(deadline.saturating_duration_since(Instant::now()))
+    }
+}
+
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .ok_or_else(|| {
+            format!(
+                "Missing unit in duration: {} (expected ns, us, ms, s or m)",
+                value
+            )
+        })?;
+    let (amount, unit) = value.split_at(split);
+    let amount: f64 = amount
+        .parse()
+        .map_err(|_| format!("Invalid duration: {}", value))?;
+
+    let nanos = match unit {
+        "ns" => 1.0,
+        "us" | "µs" => 1e3,
+        "ms" => 1e6,
+        "s" => 1e9,
+        "m" => 60e9,
+        _ => {
+            return Err(format!(
+                "Invalid duration unit: {} (expected ns, us, ms, s or m)",
+                unit
+            ))
+        }
+    };
+
+    Ok(Duration::from_nanos((amount * nanos).round() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_take_a_unit() {
+        assert_eq!(parse_duration("10ms"), Ok(Duration::from_millis(10)));
+        assert_eq!(parse_duration("500us"), Ok(Duration::from_micros(500)));
+        assert_eq!(parse_duration("1.5ms"), Ok(Duration::from_micros(1500)));
+        assert_eq!(parse_duration("1s"), Ok(Duration::from_secs(1)));
+        assert!(parse_duration("150").is_err());
+        assert!(parse_duration("3h").is_err());
+        assert!(parse_duration("ms").is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use super::{Clock, Instant};
+use std::time::Duration;
+
+pub struct ScaledClock {
+    inner: Box<dyn Clock>,
+    origin: Instant,
+    scale: f64,
+}
+
+impl ScaledClock {
+    pub fn new(inner: Box<dyn Clock>, scale: f64) -> Self {
+        Self {
+            origin: inner.now(),
+            inner,
+            scale,
+        }
+    }
+}
+
+impl Clock for ScaledClock {
+    fn now(&self) -> Instant {
+        let real = self.inner.now().saturating_duration_since(self.origin);
+        self.origin + real.mul_f64(self.scale)
+    }
+
+    fn sleep_until(&self, deadline: Instant) {
+        let simulated = deadline.saturating_duration_since(self.origin);
+        self.inner
+            .sleep_until(self.origin + simulated.div_f64(self.scale));
+    }
+
+    fn timeout(&self, deadline: Instant) -> Option<Duration> {
+        let simulated = deadline.saturating_duration_since(self.origin);
+        self.inner
+            .timeout(self.origin + simulated.div_f64(self.scale))
+    }
+}
+
+pub fn parse_scale(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(scale) if scale.is_finite() && scale > 0.0 => Ok(scale),
+        _ => Err(format!(
+            "Invalid time scale: {} (expected a positive number)",
+            value
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::time::Duration;
+
+    #[test]
+    fn time_runs_faster_by_the_scale() {
+        let real = ManualClock::new();
+        let clock = ScaledClock::new(Box::new(real.clone()), 10.0);
+        let start = clock.now();
+
+        real.advance(Duration::from_millis(15));
+        assert_eq!(clock.now() - start, Duration::from_millis(150));
+
+        clock.sleep_until(start + Duration::from_secs(1));
+        assert_eq!(real.now() - start, Duration::from_millis(100));
+        assert_eq!(clock.timeout(start + Duration::from_secs(1)), None);
+
+        let clock = ScaledClock::new(Box::new(crate::clock::RealClock), 10.0);
+        let timeout = clock.timeout(clock.now() + Duration::from_secs(1)).unwrap();
+        assert!(timeout <= Duration::from_millis(100), "{:?}", timeout);
+        assert!(parse_scale("0").is_err());
+        assert_eq!(parse_scale("0.5"), Ok(0.5));
+    }
+}
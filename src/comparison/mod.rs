@@ -0,0 +1,119 @@
+use crate::executor::Executor;
+use crate::procfs::Schedstat;
+use crate::registry::Registry;
+use crate::task::Task;
+use crate::window;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+use ulid::Ulid;
+
+#[derive(Default)]
+struct Sample {
+    granted: u64,
+    idle: u64,
+    kernel: Schedstat,
+}
+
+#[derive(Default)]
+pub struct KernelComparison {
+    samples: HashMap<Ulid, Vec<(u64, Schedstat)>>,
+}
+
+fn summarize(samples: &[(u64, Schedstat)], window: &RangeInclusive<u64>) -> Option<Sample> {
+    let (baseline, inside) = window::split(samples, window);
+
+    let mut sample = Sample::default();
+    let mut previous = baseline;
+    for kernel in inside {
+        sample.granted += 1;
+        if kernel.on_cpu <= previous.on_cpu {
+            sample.idle += 1;
+        }
+        previous = kernel;
+    }
+
+    sample.kernel = Schedstat {
+        on_cpu: previous.on_cpu.saturating_sub(baseline.on_cpu),
+        run_delay: previous.run_delay.saturating_sub(baseline.run_delay),
+        timeslices: previous.timeslices.saturating_sub(baseline.timeslices),
+    };
+    (sample.granted > 0).then_some(sample)
+}
+
+impl KernelComparison {
+    pub fn observe(&mut self, task: &Task, executor: &dyn Executor, quantum: u64) {
+        let Some(kernel) = task.get_pid().and_then(|pid| executor.schedstat(pid)) else {
+            return;
+        };
+
+        self.samples
+            .entry(task.get_id())
+            .or_default()
+            .push((quantum, kernel));
+    }
+
+    pub fn print_report(&self, tasks: &Registry, quantum: Duration, window: &RangeInclusive<u64>) {
+        let compared: Vec<(&Task, Sample)> = tasks
+            .iter()
+            .filter_map(|t| {
+                let samples = self.samples.get(&t.get_id())?;
+                summarize(samples, window).map(|s| (t, s))
+            })
+            .collect();
+
+        crate::report!(
+            "------------------------------------------\n\
+             KERNEL COMPARISON REPORT\n\
+             Measured:       {}",
+            window::describe(window)
+        );
+
+        if compared.is_empty() {
+            crate::report!("No schedstat samples were collected");
+        }
+
+        let (mut granted, mut on_cpu) = (Duration::ZERO, Duration::ZERO);
+        for (task, sample) in compared {
+            let task_granted = quantum * sample.granted as u32;
+            granted += task_granted;
+            on_cpu += sample.kernel.on_cpu;
+
+            crate::report!(
+                "PID:            {}\n\
+                 Granted:        {} seconds ({} quanta)\n\
+                 Kernel On-CPU:  {} seconds ({:.1}% of granted)\n\
+                 Run Delay:      {} seconds\n\
+                 Timeslices:     {}\n\
+                 Idle Quanta:    {}",
+                task.label(),
+                task_granted.as_secs_f64(),
+                sample.granted,
+                sample.kernel.on_cpu.as_secs_f64(),
+                percent(sample.kernel.on_cpu, task_granted),
+                sample.kernel.run_delay.as_secs_f64(),
+                sample.kernel.timeslices,
+                sample.idle
+            );
+        }
+
+        if !granted.is_zero() {
+            crate::report!(
+                "Total Granted:  {} seconds\n\
+                 Total On-CPU:   {} seconds ({:.1}% of granted)",
+                granted.as_secs_f64(),
+                on_cpu.as_secs_f64(),
+                percent(on_cpu, granted)
+            );
+        }
+
+        crate::report!("------------------------------------------");
+    }
+}
+
+fn percent(part: Duration, whole: Duration) -> f64 {
+    if whole.is_zero() {
+        return 0.0;
+    }
+    part.as_secs_f64() / whole.as_secs_f64() * 100.0
+}
@@ -0,0 +1,293 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub const DEFAULT_CONFIG_PATH: &str = "psched.toml";
+
+#[derive(Debug, Default, PartialEq, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Profile {
+    pub policy: Option<String>,
+    pub quantum: Option<String>,
+    pub concurrency: Option<NonZeroUsize>,
+    pub max_quanta: Option<u64>,
+    pub max_sim_time: Option<String>,
+    pub seed: Option<u64>,
+    pub starvation_threshold: Option<u64>,
+    pub backfill: Option<bool>,
+    pub interactivity_boost: Option<bool>,
+    pub qos_window: Option<u64>,
+    pub quota_period: Option<u64>,
+    pub color: Option<String>,
+    pub quiet: Option<bool>,
+    pub output: Option<String>,
+    pub events: Option<String>,
+    pub throughput: Option<bool>,
+    pub explain: Option<bool>,
+    pub findings: Option<bool>,
+    pub trace: Option<PathBuf>,
+    pub graph: Option<PathBuf>,
+    pub timeseries: Option<PathBuf>,
+    pub apply_nice: Option<bool>,
+    pub nice: Option<BTreeMap<String, i32>>,
+}
+
+impl Profile {
+    fn over(self, base: Profile) -> Profile {
+        Profile {
+            policy: self.policy.or(base.policy),
+            quantum: self.quantum.or(base.quantum),
+            concurrency: self.concurrency.or(base.concurrency),
+            max_quanta: self.max_quanta.or(base.max_quanta),
+            max_sim_time: self.max_sim_time.or(base.max_sim_time),
+            seed: self.seed.or(base.seed),
+            starvation_threshold: self.starvation_threshold.or(base.starvation_threshold),
+            backfill: self.backfill.or(base.backfill),
+            interactivity_boost: self.interactivity_boost.or(base.interactivity_boost),
+            qos_window: self.qos_window.or(base.qos_window),
+            quota_period: self.quota_period.or(base.quota_period),
+            color: self.color.or(base.color),
+            quiet: self.quiet.or(base.quiet),
+            output: self.output.or(base.output),
+            events: self.events.or(base.events),
+            throughput: self.throughput.or(base.throughput),
+            explain: self.explain.or(base.explain),
+            findings: self.findings.or(base.findings),
+            trace: self.trace.or(base.trace),
+            graph: self.graph.or(base.graph),
+            timeseries: self.timeseries.or(base.timeseries),
+            apply_nice: self.apply_nice.or(base.apply_nice),
+            nice: self.nice.or(base.nice),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub default: Profile,
+    pub profile: BTreeMap<String, Profile>,
+}
+
+impl std::str::FromStr for Config {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s).map_err(|err| err.message().to_string())
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())?
+            .parse()
+    }
+
+    pub fn locate(path: Option<&Path>) -> Option<PathBuf> {
+        match path {
+            Some(path) => Some(path.to_path_buf()),
+            None => Some(PathBuf::from(DEFAULT_CONFIG_PATH)).filter(|path| path.exists()),
+        }
+    }
+
+    pub fn find(path: Option<&Path>) -> Result<Option<Self>, String> {
+        match Self::locate(path) {
+            Some(path) => Self::load(&path)
+                .map(Some)
+                .map_err(|err| format!("{}: {}", path.display(), err)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile, String> {
+        let Some(name) = name else {
+            return Ok(self.default.clone());
+        };
+        match self.profile.get(name) {
+            Some(profile) => Ok(profile.clone().over(self.default.clone())),
+            None => Err(format!(
+                "No profile named {} (profiles: {})",
+                name,
+                self.profile.keys().cloned().collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+}
+
+pub struct Watch {
+    path: PathBuf,
+    profile: Option<String>,
+    modified: Option<SystemTime>,
+    last: Profile,
+}
+
+impl Watch {
+    pub fn new(path: PathBuf, profile: Option<String>) -> Result<Self, String> {
+        let mut watch = Self {
+            path,
+            profile,
+            modified: None,
+            last: Profile::default(),
+        };
+        watch.last = watch.read()?;
+        Ok(watch)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&mut self) -> Result<Profile, String> {
+        self.modified = modified(&self.path);
+        Config::load(&self.path)?.profile(self.profile.as_deref())
+    }
+
+    pub fn changed(&self) -> bool {
+        modified(&self.path) != self.modified
+    }
+
+    pub fn reload(&mut self) -> Result<Profile, String> {
+        let profile = self.read()?;
+        let last = std::mem::replace(&mut self.last, profile.clone());
+        Ok(profile.changes_since(last))
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Profile {
+    pub fn needs_restart(&self) -> Vec<&'static str> {
+        [
+            ("policy", self.policy.is_some()),
+            ("concurrency", self.concurrency.is_some()),
+            ("max-quanta", self.max_quanta.is_some()),
+            ("max-sim-time", self.max_sim_time.is_some()),
+            ("seed", self.seed.is_some()),
+            ("color", self.color.is_some()),
+            ("quiet", self.quiet.is_some()),
+            ("output", self.output.is_some()),
+            ("events", self.events.is_some()),
+            ("throughput", self.throughput.is_some()),
+            ("explain", self.explain.is_some()),
+            ("findings", self.findings.is_some()),
+            ("trace", self.trace.is_some()),
+            ("graph", self.graph.is_some()),
+            ("timeseries", self.timeseries.is_some()),
+            ("apply-nice", self.apply_nice.is_some()),
+            ("nice", self.nice.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(key, set)| set.then_some(key))
+        .collect()
+    }
+
+    fn changes_since(self, last: Profile) -> Profile {
+        fn changed<T: PartialEq>(now: Option<T>, last: Option<T>) -> Option<T> {
+            now.filter(|value| last.as_ref() != Some(value))
+        }
+
+        Profile {
+            policy: changed(self.policy, last.policy),
+            quantum: changed(self.quantum, last.quantum),
+            concurrency: changed(self.concurrency, last.concurrency),
+            max_quanta: changed(self.max_quanta, last.max_quanta),
+            max_sim_time: changed(self.max_sim_time, last.max_sim_time),
+            seed: changed(self.seed, last.seed),
+            starvation_threshold: changed(self.starvation_threshold, last.starvation_threshold),
+            backfill: changed(self.backfill, last.backfill),
+            interactivity_boost: changed(self.interactivity_boost, last.interactivity_boost),
+            qos_window: changed(self.qos_window, last.qos_window),
+            quota_period: changed(self.quota_period, last.quota_period),
+            color: changed(self.color, last.color),
+            quiet: changed(self.quiet, last.quiet),
+            output: changed(self.output, last.output),
+            events: changed(self.events, last.events),
+            throughput: changed(self.throughput, last.throughput),
+            explain: changed(self.explain, last.explain),
+            findings: changed(self.findings, last.findings),
+            trace: changed(self.trace, last.trace),
+            graph: changed(self.graph, last.graph),
+            timeseries: changed(self.timeseries, last.timeseries),
+            apply_nice: changed(self.apply_nice, last.apply_nice),
+            nice: changed(self.nice, last.nice),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_override_the_defaults() {
+        let config: Config = r#"
+            [default]
+            policy = "fair-share"
+            quantum = "100ms"
+            concurrency = 2
+
+            [profile.fast]
+            quantum = "20ms"
+            color = "always"
+            max-quanta = 50
+
+            [profile.fast.nice]
+            0 = -5
+            3 = 0
+        "#
+        .parse()
+        .unwrap();
+
+        let base = config.profile(None).unwrap();
+        assert_eq!(base.quantum.as_deref(), Some("100ms"));
+        assert_eq!((base.color, base.nice), (None, None));
+
+        let fast = config.profile(Some("fast")).unwrap();
+        assert_eq!(fast.policy.as_deref(), Some("fair-share"));
+        assert_eq!(fast.quantum.as_deref(), Some("20ms"));
+        assert_eq!(fast.concurrency, NonZeroUsize::new(2));
+        assert_eq!(
+            (fast.color.as_deref(), fast.max_quanta),
+            (Some("always"), Some(50))
+        );
+        assert_eq!(fast.nice.unwrap()["3"], 0);
+
+        assert_eq!(
+            config.profile(Some("slow")).unwrap_err(),
+            "No profile named slow (profiles: fast)"
+        );
+        assert!("[default]\ncores = 4".parse::<Config>().is_err());
+    }
+
+    #[test]
+    fn reloads_report_only_the_keys_that_changed() {
+        let path = std::env::temp_dir().join(format!("psched-{}.toml", ulid::Ulid::new()));
+        std::fs::write(&path, "[default]\nquantum = \"100ms\"\nbackfill = true\n").unwrap();
+        let mut watch = Watch::new(path.clone(), None).unwrap();
+        assert!(!watch.changed());
+
+        std::fs::write(
+            &path,
+            "[default]\nquantum = \"50ms\"\nbackfill = true\nseed = 7\n",
+        )
+        .unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::UNIX_EPOCH))
+            .unwrap();
+        assert!(watch.changed());
+
+        let changes = watch.reload().unwrap();
+        assert_eq!(changes.quantum.as_deref(), Some("50ms"));
+        assert_eq!(changes.backfill, None);
+        assert_eq!(changes.needs_restart(), ["seed"]);
+        assert!(!watch.changed());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,161 @@
+use super::{parse_task_line, Command, Message, Reply};
+use std::io;
+use std::path::Path;
+#[cfg(unix)]
+use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncBufReadExt, BufReader};
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::UnboundedSender;
+
+pub fn spawn_stdin_reader_async(tx: UnboundedSender<Message>) {
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match line.parse::<Command>() {
+                Ok(command) => {
+                    let message = Message {
+                        command,
+                        reply: Reply::Stdout,
+                    };
+                    if tx.send(message).is_err() {
+                        break;
+                    }
+                }
+                Err(err) => println!("{}", err),
+            }
+        }
+    });
+}
+
+pub fn spawn_task_reader_async(tx: UnboundedSender<Message>) {
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            match parse_task_line(&line) {
+                Ok(spec) => {
+                    let message = Message {
+                        command: Command::Submit(Box::new(spec)),
+                        reply: Reply::Log,
+                    };
+                    if tx.send(message).is_err() {
+                        return;
+                    }
+                }
+                Err(err) => eprintln!("{}", err),
+            }
+        }
+
+        let _ = tx.send(Message {
+            command: Command::EndOfInput,
+            reply: Reply::Log,
+        });
+    });
+}
+
+#[cfg(unix)]
+pub fn spawn_fifo_reader_async(path: &Path, tx: UnboundedSender<Message>) -> io::Result<()> {
+    super::read_fifo(path, move |message| tx.send(message).is_ok())
+}
+
+#[cfg(not(unix))]
+pub fn spawn_fifo_reader_async(_path: &Path, _tx: UnboundedSender<Message>) -> io::Result<()> {
+    Err(super::unsupported())
+}
+
+#[cfg(not(unix))]
+pub fn spawn_socket_listener_async(_path: &Path, _tx: UnboundedSender<Message>) -> io::Result<()> {
+    Err(super::unsupported())
+}
+
+#[cfg(unix)]
+pub fn spawn_socket_listener_async(path: &Path, tx: UnboundedSender<Message>) -> io::Result<()> {
+    super::claim_socket(path)?;
+
+    let listener = UnixListener::bind(path)?;
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+
+            tokio::spawn(serve_connection(stream, tx.clone()));
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn serve_connection(stream: UnixStream, tx: UnboundedSender<Message>) {
+    let peer = stream.peer_cred().ok().map(|credentials| credentials.uid());
+    let owner = peer.map(super::owner_name);
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut command = match line.parse::<Command>() {
+            Ok(command) => command,
+            Err(err) => {
+                if writer
+                    .write_all(format!("{}\n", err).as_bytes())
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        if let Err(err) = super::check_run_as(&command, peer) {
+            if writer
+                .write_all(format!("{}\n", err).as_bytes())
+                .await
+                .is_err()
+            {
+                break;
+            }
+            continue;
+        }
+        super::set_owner(&mut command, &owner);
+
+        let (reply_tx, mut reply_rx) = mpsc::unbounded_channel();
+        let message = Message {
+            command,
+            reply: Reply::Async(reply_tx),
+        };
+
+        if tx.send(message).is_err() {
+            let _ = writer.write_all(b"Daemon is shutting down\n").await;
+            break;
+        }
+
+        while let Some(text) = reply_rx.recv().await {
+            if writer
+                .write_all(format!("{}\n", text).as_bytes())
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,933 @@
+use crate::burst::Program;
+use crate::debugger::Breakpoint;
+use crate::selector::Selector;
+use crate::task;
+#[cfg(feature = "async")]
+pub use asynchronous::{
+    spawn_fifo_reader_async, spawn_socket_listener_async, spawn_stdin_reader_async,
+    spawn_task_reader_async,
+};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead};
+#[cfg(unix)]
+use std::io::{BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use ulid::Ulid;
+
+#[cfg(feature = "async")]
+mod asynchronous;
+
+const DEFAULT_CHECKPOINT_PATH: &str = "checkpoint.json";
+pub const DEFAULT_PRIORITY: u8 = 3;
+pub const DEFAULT_SOCKET_PATH: &str = "/tmp/psched.sock";
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TaskRef(pub String);
+
+impl FromStr for TaskRef {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl From<Ulid> for TaskRef {
+    fn from(id: Ulid) -> Self {
+        Self(id.to_string())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Selection {
+    Task(TaskRef),
+    Filter(Selector),
+}
+
+impl From<Ulid> for Selection {
+    fn from(id: Ulid) -> Self {
+        Self::Task(id.into())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Command {
+    Pause,
+    Resume,
+    Checkpoint(PathBuf),
+    Submit(Box<task::Spec>),
+    Renice(Selection, u8),
+    Kill(Selection),
+    Suspend(Selection),
+    ResumeTask(Selection),
+    Step,
+    Continue,
+    Until(TaskRef),
+    Inspect(TaskRef),
+    Break(Option<Breakpoint>),
+    Delete(usize),
+    Status,
+    List(Option<Selector>),
+    Reload,
+    Shutdown,
+    EndOfInput,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = split_words(s)?;
+        let mut words = words.iter().map(String::as_str);
+
+        let command = match words.next() {
+            Some("pause") => Command::Pause,
+            Some("resume") if words.len() == 0 => Command::Resume,
+            Some("resume") => Command::ResumeTask(selection(&mut words, "resume")?),
+            Some("checkpoint") => {
+                Command::Checkpoint(words.next().unwrap_or(DEFAULT_CHECKPOINT_PATH).into())
+            }
+            Some("submit" | "add") => {
+                return parse_submit(words).map(|spec| Command::Submit(Box::new(spec)))
+            }
+            Some("renice") => {
+                let selection = selection(&mut words, "renice")?;
+                Command::Renice(selection, option_value(&mut words, "renice")?)
+            }
+            Some("kill") => Command::Kill(selection(&mut words, "kill")?),
+            Some("suspend") => Command::Suspend(selection(&mut words, "suspend")?),
+            Some("step") => Command::Step,
+            Some("continue") => Command::Continue,
+            Some("until") => {
+                let id = option_value(&mut words, "until")?;
+                if words.next() != Some("terminates") {
+                    return Err(format!("Expected `until PID terminates`: {}", s.trim()));
+                }
+                Command::Until(id)
+            }
+            Some("inspect") => Command::Inspect(option_value(&mut words, "inspect")?),
+            Some("break") => {
+                let condition: Vec<&str> = words.collect();
+                if condition.is_empty() {
+                    return Ok(Command::Break(None));
+                }
+                return condition
+                    .join(" ")
+                    .parse()
+                    .map(|breakpoint| Command::Break(Some(breakpoint)));
+            }
+            Some("delete") => Command::Delete(option_value(&mut words, "delete")?),
+            Some("status") => Command::Status,
+            Some("list") => match words.next() {
+                Some("--filter") => Command::List(Some(filter(&mut words)?)),
+                Some(word) => return Err(format!("Unknown list option: {}", word)),
+                None => Command::List(None),
+            },
+            Some("reload") => Command::Reload,
+            Some("shutdown") => Command::Shutdown,
+            _ => return Err(format!("Unknown command: {}", s.trim())),
+        };
+
+        if words.next().is_some() {
+            return Err(format!("Too many arguments: {}", s.trim()));
+        }
+
+        Ok(command)
+    }
+}
+
+fn filter<'a>(words: &mut impl Iterator<Item = &'a str>) -> Result<Selector, String> {
+    words
+        .next()
+        .ok_or("Missing value for --filter")?
+        .parse()
+        .map_err(|err| format!("Invalid --filter: {}", err))
+}
+
+fn selection<'a>(
+    words: &mut impl Iterator<Item = &'a str>,
+    command: &str,
+) -> Result<Selection, String> {
+    match words.next() {
+        Some("--filter") => filter(words).map(Selection::Filter),
+        Some(task) => Ok(Selection::Task(TaskRef(task.to_string()))),
+        None => Err(format!("Missing value for {}", command)),
+    }
+}
+
+fn option_value<'a, T: FromStr>(
+    words: &mut impl Iterator<Item = &'a str>,
+    option: &str,
+) -> Result<T, String> {
+    let value = words
+        .next()
+        .ok_or_else(|| format!("Missing value for {}", option))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: {}", option, value))
+}
+
+pub fn parse_meta(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!(
+            "Invalid value for --meta: {} (expected KEY=VALUE)",
+            value
+        )),
+    }
+}
+
+pub fn parse_submit<'a>(mut words: impl Iterator<Item = &'a str>) -> Result<task::Spec, String> {
+    let mut name = None;
+    let mut priority = DEFAULT_PRIORITY;
+    let mut width = 1;
+    let mut estimate = None;
+    let mut quantum_ms = None;
+    let mut deadline_ms = None;
+    let mut on_miss = task::MissPolicy::default();
+    let mut period_ms = None;
+    let mut jobs = 1;
+    let mut after = Vec::new();
+    let mut apart_from = Vec::new();
+    let mut near = None;
+    let mut gang = None;
+    let mut qos = task::Qos::default();
+    let mut share = None;
+    let mut cpu_quota = None;
+    let mut image = None;
+    let mut runtime = None;
+    let mut cpus = None;
+    let mut memory = None;
+    let mut host = None;
+    let mut run_as = None;
+    let mut sandbox = None;
+    let mut burst = None;
+    let mut metadata = BTreeMap::new();
+
+    let path_to_binary = loop {
+        match words.next() {
+            Some("--name") => name = Some(option_value(&mut words, "--name")?),
+            Some("--priority") => priority = option_value(&mut words, "--priority")?,
+            Some("--width") => width = option_value(&mut words, "--width")?,
+            Some("--gang") => gang = Some(option_value(&mut words, "--gang")?),
+            Some("--qos") => qos = option_value(&mut words, "--qos")?,
+            Some("--share") => share = Some(option_value(&mut words, "--share")?),
+            Some("--cpu-quota") => {
+                let value: String = option_value(&mut words, "--cpu-quota")?;
+                cpu_quota = Some(crate::throttle::parse_quota(&value)?);
+            }
+            Some("--container") => image = Some(option_value(&mut words, "--container")?),
+            Some("--runtime") => runtime = Some(option_value(&mut words, "--runtime")?),
+            Some("--cpus") => cpus = Some(option_value(&mut words, "--cpus")?),
+            Some("--memory") => memory = Some(option_value(&mut words, "--memory")?),
+            Some("--host") => host = Some(option_value(&mut words, "--host")?),
+            Some("--run-as") => run_as = Some(option_value(&mut words, "--run-as")?),
+            Some("--sandbox") => sandbox = Some(option_value(&mut words, "--sandbox")?),
+            Some("--burst") => {
+                let value: String = option_value(&mut words, "--burst")?;
+                check_burst(&value)?;
+                burst = Some(value);
+            }
+            Some("--meta") => {
+                let (key, value) = parse_meta(words.next().unwrap_or_default())?;
+                metadata.insert(key, value);
+            }
+            Some("--quantum") => quantum_ms = Some(option_value(&mut words, "--quantum")?),
+            Some("--deadline") => deadline_ms = Some(option_value(&mut words, "--deadline")?),
+            Some("--on-miss") => on_miss = option_value(&mut words, "--on-miss")?,
+            Some("--period") => period_ms = Some(option_value(&mut words, "--period")?),
+            Some("--jobs") => jobs = option_value(&mut words, "--jobs")?,
+            Some("--after") => after.push(option_value(&mut words, "--after")?),
+            Some("--apart-from") => apart_from.push(option_value(&mut words, "--apart-from")?),
+            Some("--near") => near = Some(option_value(&mut words, "--near")?),
+            Some("--estimate") => {
+                estimate = Some(Duration::from_millis(option_value(
+                    &mut words,
+                    "--estimate",
+                )?))
+            }
+            Some("--") => break words.next().ok_or("Missing path to binary")?,
+            Some(word) if word.starts_with("--") => {
+                return Err(format!("Unknown submit option: {}", word));
+            }
+            Some(word) => break word,
+            None => return Err("Missing path to binary".to_string()),
+        }
+    };
+
+    let args: Vec<String> = words.map(str::to_string).collect();
+
+    if width == 0 {
+        return Err("A task needs a --width of at least 1".to_string());
+    }
+    if quantum_ms == Some(0) {
+        return Err("A task needs a --quantum of at least 1ms".to_string());
+    }
+    if period_ms == Some(0) {
+        return Err("A task needs a --period of at least 1ms".to_string());
+    }
+    match (period_ms, jobs) {
+        (_, 0) => return Err("A task needs --jobs of at least 1".to_string()),
+        (None, 2..) => return Err("--jobs needs a --period".to_string()),
+        _ => {}
+    }
+    match on_miss {
+        task::MissPolicy::Skip if period_ms.is_none() => {
+            return Err("--on-miss skip only applies to periodic tasks (--period)".to_string());
+        }
+        task::MissPolicy::Abort if deadline_ms.is_none() && period_ms.is_none() => {
+            return Err("--on-miss needs a --deadline or --period".to_string());
+        }
+        _ => {}
+    }
+
+    match (qos, share) {
+        (task::Qos::Guaranteed, None) => {
+            return Err("Guaranteed tasks need a --share".to_string());
+        }
+        (task::Qos::Guaranteed, Some(share)) if !(share > 0.0 && share <= 1.0) => {
+            return Err(format!(
+                "Invalid --share {} (expected 0 < share <= 1)",
+                share
+            ));
+        }
+        (task::Qos::Guaranteed, _) => {}
+        (_, Some(_)) => return Err("--share only applies to guaranteed tasks".to_string()),
+        (_, None) => {}
+    }
+
+    let container = match image {
+        Some(image) => Some(task::Container {
+            image,
+            runtime: runtime.unwrap_or_default(),
+            cpus,
+        }),
+        None if runtime.is_some() || cpus.is_some() => {
+            return Err("--runtime and --cpus need a --container image".to_string());
+        }
+        None => None,
+    };
+
+    if container.is_some() && host.is_some() {
+        return Err("--container and --host cannot be combined".to_string());
+    }
+    if run_as.is_some() && (container.is_some() || host.is_some()) {
+        return Err("--run-as only applies to local tasks".to_string());
+    }
+    if sandbox.is_some() && (container.is_some() || host.is_some()) {
+        return Err("--sandbox only applies to local tasks".to_string());
+    }
+
+    let args = if args.is_empty() { None } else { Some(args) };
+    Ok(task::Spec {
+        name,
+        width,
+        estimate,
+        quantum_ms,
+        deadline_ms,
+        on_miss,
+        period_ms,
+        jobs,
+        gang,
+        qos,
+        share,
+        cpu_quota,
+        container,
+        host,
+        memory,
+        run_as,
+        sandbox,
+        after,
+        apart_from,
+        near,
+        burst,
+        metadata,
+        ..task::Spec::new(path_to_binary, args, priority)
+    })
+}
+
+fn check_burst(program: &str) -> Result<(), String> {
+    program
+        .parse::<Program>()
+        .map(drop)
+        .map_err(|err| format!("Invalid --burst {:?}: {}", program, err))
+}
+
+const SUBMIT_OPTIONS: [&str; 25] = [
+    "--name",
+    "--priority",
+    "--width",
+    "--gang",
+    "--qos",
+    "--share",
+    "--cpu-quota",
+    "--container",
+    "--runtime",
+    "--cpus",
+    "--memory",
+    "--host",
+    "--run-as",
+    "--sandbox",
+    "--burst",
+    "--meta",
+    "--estimate",
+    "--quantum",
+    "--deadline",
+    "--on-miss",
+    "--period",
+    "--jobs",
+    "--after",
+    "--apart-from",
+    "--near",
+];
+
+pub fn parse_task(line: &str) -> Result<task::Spec, String> {
+    let words = split_words(line)?;
+    let mut start = 0;
+    while let Some(word) = words.get(start) {
+        match word.as_str() {
+            "--" => break,
+            word if word.starts_with("--") => start += 2,
+            _ => break,
+        }
+    }
+    let start = start.min(words.len());
+    if words.get(start).is_some_and(|word| word == "--") {
+        return parse_submit(words.iter().map(String::as_str));
+    }
+
+    let end = words[start..]
+        .iter()
+        .skip(1)
+        .position(|word| SUBMIT_OPTIONS.contains(&word.as_str()))
+        .map_or(words.len(), |at| start + 1 + at);
+    let command = &words[start..end];
+    if command.is_empty() {
+        return Err("Missing path to binary".to_string());
+    }
+    if let Some(option) = words[end..]
+        .last()
+        .filter(|word| SUBMIT_OPTIONS.contains(&word.as_str()))
+    {
+        return Err(format!("Missing value for {}", option));
+    }
+
+    let options = words[..start].iter().chain(&words[end..]);
+    parse_submit(
+        options
+            .map(String::as_str)
+            .chain(["--"])
+            .chain(command.iter().map(String::as_str)),
+    )
+}
+
+pub fn parse_task_line(line: &str) -> Result<task::Spec, String> {
+    if line.trim_start().starts_with('{') {
+        let spec: task::Spec =
+            serde_json::from_str(line).map_err(|err| format!("Invalid task JSON: {}", err))?;
+        if let Some(burst) = &spec.burst {
+            check_burst(burst)?;
+        }
+        return Ok(spec);
+    }
+    parse_task(line)
+}
+
+pub fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => match chars.next() {
+                Some(escaped) => word.push(escaped),
+                None => return Err("Trailing backslash".to_string()),
+            },
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err("Unterminated quote".to_string());
+    }
+
+    if in_word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+pub fn quote_words<S: AsRef<str>>(words: &[S]) -> String {
+    words
+        .iter()
+        .map(|word| {
+            let word = word.as_ref();
+            if !word.is_empty()
+                && word
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c))
+            {
+                word.to_string()
+            } else {
+                format!("'{}'", word.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub enum Reply {
+    Stdout,
+    Log,
+    Channel(mpsc::Sender<String>),
+    #[cfg(feature = "async")]
+    Async(tokio::sync::mpsc::UnboundedSender<String>),
+}
+
+impl Reply {
+    pub fn send(&self, text: impl Into<String>) {
+        match self {
+            Reply::Stdout => println!("{}", text.into()),
+            Reply::Log => crate::say!("{}", text.into()),
+            Reply::Channel(tx) => {
+                let _ = tx.send(text.into());
+            }
+            #[cfg(feature = "async")]
+            Reply::Async(tx) => {
+                let _ = tx.send(text.into());
+            }
+        }
+    }
+}
+
+pub struct Message {
+    pub command: Command,
+    pub reply: Reply,
+}
+
+pub fn spawn_stdin_reader(tx: mpsc::Sender<Message>) {
+    thread::spawn(move || {
+        let stdin = std::io::stdin();
+        for line in stdin.lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match line.parse::<Command>() {
+                Ok(command) => {
+                    let message = Message {
+                        command,
+                        reply: Reply::Stdout,
+                    };
+                    if tx.send(message).is_err() {
+                        break;
+                    }
+                }
+                Err(err) => println!("{}", err),
+            }
+        }
+    });
+}
+
+pub fn spawn_task_reader(tx: mpsc::Sender<Message>) {
+    thread::spawn(move || {
+        let stdin = std::io::stdin();
+        for line in stdin.lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            match parse_task_line(&line) {
+                Ok(spec) => {
+                    let message = Message {
+                        command: Command::Submit(Box::new(spec)),
+                        reply: Reply::Log,
+                    };
+                    if tx.send(message).is_err() {
+                        return;
+                    }
+                }
+                Err(err) => eprintln!("{}", err),
+            }
+        }
+
+        let _ = tx.send(Message {
+            command: Command::EndOfInput,
+            reply: Reply::Log,
+        });
+    });
+}
+
+#[cfg(unix)]
+pub fn owner_name(uid: u32) -> String {
+    #[cfg(feature = "process")]
+    if let Ok(Some(user)) = nix::unistd::User::from_uid(nix::unistd::Uid::from_raw(uid)) {
+        return user.name;
+    }
+
+    uid.to_string()
+}
+
+#[cfg(unix)]
+pub fn local_owner() -> String {
+    #[cfg(feature = "process")]
+    return owner_name(nix::unistd::getuid().as_raw());
+
+    #[cfg(not(feature = "process"))]
+    std::env::var("USER").unwrap_or_else(|_| "unknown".to_string())
+}
+
+#[cfg(unix)]
+fn set_owner(command: &mut Command, owner: &Option<String>) {
+    if let Command::Submit(spec) = command {
+        spec.owner = owner.clone();
+    }
+}
+
+#[cfg(unix)]
+fn check_run_as(command: &Command, peer: Option<u32>) -> Result<(), String> {
+    let Command::Submit(spec) = command else {
+        return Ok(());
+    };
+    let Some(user) = &spec.run_as else {
+        return Ok(());
+    };
+    match peer {
+        Some(0) => Ok(()),
+        Some(uid) if owner_name(uid) == *user || uid.to_string() == *user => Ok(()),
+        _ => Err(format!("Not allowed to run tasks as {}", user)),
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "process"))]
+fn peer_uid(stream: &UnixStream) -> Option<u32> {
+    use nix::sys::socket::{getsockopt, sockopt::PeerCredentials};
+
+    let credentials = getsockopt(stream, PeerCredentials).ok()?;
+    Some(credentials.uid())
+}
+
+#[cfg(all(unix, not(all(target_os = "linux", feature = "process"))))]
+fn peer_uid(_stream: &UnixStream) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn claim_socket(path: &Path) -> io::Result<()> {
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("a daemon is already listening on {}", path.display()),
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn spawn_socket_listener(path: &Path, tx: mpsc::Sender<Message>) -> io::Result<()> {
+    claim_socket(path)?;
+
+    let listener = UnixListener::bind(path)?;
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+
+            let tx = mpsc::Sender::clone(&tx);
+            thread::spawn(move || serve_connection(stream, tx));
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn claim_fifo(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.file_type().is_fifo() => Ok(()),
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists and is not a FIFO", path.display()),
+        )),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => make_fifo(path),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(all(unix, feature = "process"))]
+fn make_fifo(path: &Path) -> io::Result<()> {
+    use nix::sys::stat::Mode;
+
+    nix::unistd::mkfifo(path, Mode::S_IRUSR | Mode::S_IWUSR).map_err(io::Error::from)
+}
+
+#[cfg(all(unix, not(feature = "process")))]
+fn make_fifo(path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} does not exist, create it with mkfifo", path.display()),
+    ))
+}
+
+#[cfg(unix)]
+fn read_fifo(path: &Path, send: impl Fn(Message) -> bool + Send + 'static) -> io::Result<()> {
+    claim_fifo(path)?;
+    let path = path.to_path_buf();
+
+    thread::spawn(move || loop {
+        let Ok(fifo) = std::fs::File::open(&path) else {
+            return;
+        };
+
+        for line in BufReader::new(fifo).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match line.parse::<Command>() {
+                Ok(command) => {
+                    let message = Message {
+                        command,
+                        reply: Reply::Stdout,
+                    };
+                    if !send(message) {
+                        return;
+                    }
+                }
+                Err(err) => println!("{}", err),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn spawn_fifo_reader(path: &Path, tx: mpsc::Sender<Message>) -> io::Result<()> {
+    read_fifo(path, move |message| tx.send(message).is_ok())
+}
+
+#[cfg(unix)]
+fn serve_connection(stream: UnixStream, tx: mpsc::Sender<Message>) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let peer = peer_uid(&stream);
+    let owner = peer.map(owner_name);
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut command = match line.parse::<Command>() {
+            Ok(command) => command,
+            Err(err) => {
+                if writeln!(writer, "{}", err).is_err() {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        if let Err(err) = check_run_as(&command, peer) {
+            if writeln!(writer, "{}", err).is_err() {
+                break;
+            }
+            continue;
+        }
+        set_owner(&mut command, &owner);
+
+        let (reply_tx, reply_rx) = mpsc::channel();
+        let message = Message {
+            command,
+            reply: Reply::Channel(reply_tx),
+        };
+
+        if tx.send(message).is_err() {
+            let _ = writeln!(writer, "Daemon is shutting down");
+            break;
+        }
+
+        for text in reply_rx {
+            if writeln!(writer, "{}", text).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+pub fn send(path: &Path, line: &str) -> io::Result<()> {
+    let mut stream = UnixStream::connect(path)?;
+    writeln!(stream, "{}", line)?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    for text in BufReader::new(stream).lines() {
+        println!("{}", text?);
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the control socket needs UNIX domain sockets",
+    )
+}
+
+#[cfg(not(unix))]
+pub fn spawn_socket_listener(_path: &Path, _tx: mpsc::Sender<Message>) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(unix))]
+pub fn spawn_fifo_reader(_path: &Path, _tx: mpsc::Sender<Message>) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(unix))]
+pub fn send(_path: &Path, _line: &str) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_strings_take_submit_options_before_or_after_the_command() {
+        let spec = parse_task("/bin/tar czf 'out dir.tgz' src --priority 2 --name backup").unwrap();
+        assert_eq!(spec.path_to_binary, Path::new("/bin/tar"));
+        assert_eq!(
+            spec.args.as_deref(),
+            Some(
+                &[
+                    "czf".to_string(),
+                    "out dir.tgz".to_string(),
+                    "src".to_string()
+                ][..]
+            )
+        );
+        assert_eq!((spec.priority, spec.name.as_deref()), (2, Some("backup")));
+
+        let spec = parse_task("--meta run=1 /bin/echo hi --width 2").unwrap();
+        assert_eq!(spec.args.as_deref(), Some(&["hi".to_string()][..]));
+        assert_eq!((spec.width, spec.metadata["run"].as_str()), (2, "1"));
+
+        let spec = parse_task("--priority 1 -- /bin/grep --name x").unwrap();
+        assert_eq!(spec.priority, 1);
+        assert_eq!(
+            spec.args.as_deref(),
+            Some(&["--name".to_string(), "x".to_string()][..])
+        );
+
+        assert_eq!(parse_task("").unwrap_err(), "Missing path to binary");
+        assert_eq!(
+            parse_task("/bin/ls --priority").unwrap_err(),
+            "Missing value for --priority"
+        );
+        let spec = parse_task_line(r#"{"path_to_binary": "/bin/true", "priority": 4}"#).unwrap();
+        assert_eq!(
+            (spec.path_to_binary, spec.priority),
+            ("/bin/true".into(), 4)
+        );
+        assert!(parse_task_line("{not json").is_err());
+
+        let spec = parse_task("--burst 'cpu 10ms; io 5ms' psched-worker --exit 1").unwrap();
+        assert_eq!(spec.arguments(), ["--exit", "1", "cpu 10ms; io 5ms"]);
+        assert!(parse_task("--burst 'nap 1ms' psched-worker").is_err());
+        let json = r#"{"path_to_binary": "psched-worker", "priority": 1, "burst": "release a"}"#;
+        assert!(parse_task_line(json).is_err());
+        assert!(parse_task("/bin/echo 'unterminated").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn only_root_or_the_user_itself_can_submit_tasks_as_that_user() {
+        let submit: Command = "submit --run-as 4242 /bin/true".parse().unwrap();
+        assert_eq!(check_run_as(&submit, Some(0)), Ok(()));
+        assert_eq!(check_run_as(&submit, Some(4242)), Ok(()));
+        let refused = Err("Not allowed to run tasks as 4242".to_string());
+        assert_eq!(check_run_as(&submit, Some(4243)), refused);
+        assert_eq!(check_run_as(&submit, None), refused);
+
+        let own: Command = "submit /bin/true".parse().unwrap();
+        assert_eq!(check_run_as(&own, None), Ok(()));
+        assert_eq!(check_run_as(&Command::Pause, None), Ok(()));
+    }
+
+    #[cfg(all(unix, feature = "process"))]
+    #[test]
+    fn commands_echoed_into_the_fifo_reach_the_simulator() {
+        let path = std::env::temp_dir().join(format!("psched-{}.fifo", Ulid::new()));
+        let (tx, rx) = mpsc::channel();
+        spawn_fifo_reader(&path, tx).unwrap();
+
+        std::fs::write(&path, "pause\n\nadd --name job /bin/true\n").unwrap();
+        std::fs::write(&path, "kill job\n").unwrap();
+
+        let commands: Vec<Command> = rx.iter().take(3).map(|message| message.command).collect();
+        assert_eq!(commands[0], Command::Pause);
+        assert!(
+            matches!(&commands[1], Command::Submit(spec) if spec.name.as_deref() == Some("job"))
+        );
+        assert_eq!(
+            commands[2],
+            Command::Kill(Selection::Task(TaskRef("job".to_string())))
+        );
+        assert!(spawn_fifo_reader(&std::env::temp_dir(), mpsc::channel().0).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,204 @@
+use crate::control::{Command, Message, Reply};
+use crate::selector::Selector;
+use std::sync::mpsc;
+use std::time::Duration;
+use zbus::blocking::{connection, Connection};
+use zbus::fdo;
+use zbus::interface;
+use zbus::object_server::SignalEmitter;
+
+pub const NAME: &str = "org.psched.Scheduler1";
+pub const PATH: &str = "/org/psched/Scheduler1";
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, PartialEq, Copy, Clone, clap::ValueEnum)]
+pub enum Bus {
+    Session,
+    System,
+}
+
+impl std::fmt::Display for Bus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Bus::Session => "session",
+            Bus::System => "system",
+        })
+    }
+}
+
+type Send = Box<dyn Fn(Message) -> bool + std::marker::Send + Sync>;
+
+fn parse(line: String) -> fdo::Result<Command> {
+    line.parse().map_err(fdo::Error::InvalidArgs)
+}
+
+struct Scheduler {
+    send: Send,
+}
+
+impl Scheduler {
+    fn request(&self, command: Command) -> fdo::Result<String> {
+        let (tx, rx) = mpsc::channel();
+        let message = Message {
+            command,
+            reply: Reply::Channel(tx),
+        };
+        if !(self.send)(message) {
+            return Err(fdo::Error::Failed("the scheduler has stopped".to_string()));
+        }
+
+        let mut lines = Vec::new();
+        loop {
+            match rx.recv_timeout(REPLY_TIMEOUT) {
+                Ok(line) => lines.push(line),
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(lines.join("\n")),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    return Err(fdo::Error::TimedOut(
+                        "the scheduler did not answer".to_string(),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+#[interface(name = "org.psched.Scheduler1")]
+impl Scheduler {
+    fn submit(&self, task: &str) -> fdo::Result<String> {
+        self.request(parse(format!("submit {}", task))?)
+    }
+
+    fn list(&self, filter: &str) -> fdo::Result<String> {
+        let filter = match filter {
+            "" => None,
+            filter => Some(
+                filter
+                    .parse::<Selector>()
+                    .map_err(|err| fdo::Error::InvalidArgs(format!("Invalid filter: {}", err)))?,
+            ),
+        };
+        self.request(Command::List(filter))
+    }
+
+    fn kill(&self, selection: &str) -> fdo::Result<String> {
+        self.request(parse(format!("kill {}", selection))?)
+    }
+
+    #[zbus(signal)]
+    async fn state_changed(
+        emitter: &SignalEmitter<'_>,
+        id: &str,
+        name: &str,
+        event: &str,
+    ) -> zbus::Result<()>;
+}
+
+pub struct Service {
+    connection: Connection,
+}
+
+impl Service {
+    pub fn on(
+        builder: connection::Builder<'_>,
+        send: impl Fn(Message) -> bool + std::marker::Send + Sync + 'static,
+    ) -> zbus::Result<Self> {
+        let scheduler = Scheduler {
+            send: Box::new(send),
+        };
+        let connection = builder.serve_at(PATH, scheduler)?.build()?;
+        Ok(Self { connection })
+    }
+
+    pub fn state_changed(&self, id: &str, name: &str, event: &str) {
+        let Ok(scheduler) = self
+            .connection
+            .object_server()
+            .interface::<_, Scheduler>(PATH)
+        else {
+            return;
+        };
+        if let Err(err) = zbus::block_on(Scheduler::state_changed(
+            scheduler.signal_emitter(),
+            id,
+            name,
+            event,
+        )) {
+            eprintln!("Failed to emit D-Bus signal: {}", err);
+        }
+    }
+}
+
+pub fn serve(
+    bus: Bus,
+    send: impl Fn(Message) -> bool + std::marker::Send + Sync + 'static,
+) -> zbus::Result<Service> {
+    let builder = match bus {
+        Bus::Session => connection::Builder::session()?,
+        Bus::System => connection::Builder::system()?,
+    };
+    Service::on(builder.name(NAME)?, send)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+    use std::thread;
+    use zbus::blocking::MessageIterator;
+    use zbus::message::Type;
+    use zbus::Guid;
+
+    #[test]
+    fn methods_reach_the_scheduler_and_state_changes_are_signalled() {
+        let (server, client) = UnixStream::pair().unwrap();
+        let (tx, rx) = mpsc::channel::<Message>();
+        thread::spawn(move || {
+            for message in rx {
+                let text = match message.command {
+                    Command::List(None) => "no tasks".to_string(),
+                    Command::Kill(_) => "Killed PID: backup".to_string(),
+                    _ => "unexpected".to_string(),
+                };
+                message.reply.send(text);
+            }
+        });
+        let service = thread::spawn(move || {
+            let builder = connection::Builder::async_io_unix_stream(server)
+                .p2p()
+                .server(Guid::generate())
+                .unwrap();
+            Service::on(builder, move |message| tx.send(message).is_ok()).unwrap()
+        });
+        let client = connection::Builder::async_io_unix_stream(client)
+            .p2p()
+            .build()
+            .unwrap();
+        let service = service.join().unwrap();
+
+        let call = |method: &str, argument: &str| -> String {
+            client
+                .call_method(None::<&str>, PATH, Some(NAME), method, &(argument,))
+                .unwrap()
+                .body()
+                .deserialize()
+                .unwrap()
+        };
+        assert_eq!(call("List", ""), "no tasks");
+        assert_eq!(call("Kill", "backup"), "Killed PID: backup");
+        assert!(client
+            .call_method(None::<&str>, PATH, Some(NAME), "Submit", &("--width",))
+            .is_err());
+
+        let mut messages = MessageIterator::from(&client);
+        service.state_changed("01J", "backup", "dispatched");
+        let signal = messages
+            .find_map(|message| {
+                let message = message.ok()?;
+                (message.message_type() == Type::Signal).then_some(message)
+            })
+            .unwrap();
+        assert_eq!(signal.header().member().unwrap().as_str(), "StateChanged");
+        let body: (String, String, String) = signal.body().deserialize().unwrap();
+        assert_eq!(body, ("01J".into(), "backup".into(), "dispatched".into()));
+    }
+}
@@ -0,0 +1,208 @@
+use crate::registry::Registry;
+use crate::task::{MissPolicy, Spec, State, Task};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use ulid::Ulid;
+
+struct Job {
+    label: String,
+    policy: MissPolicy,
+    due: Duration,
+    release: Option<usize>,
+    missed: bool,
+    aborted: bool,
+    late_by: Option<Duration>,
+}
+
+struct Release {
+    name: String,
+    spec: Spec,
+    period: Duration,
+    next: Duration,
+    left: u32,
+    skip: bool,
+}
+
+#[derive(Default)]
+pub struct DeadlineTracker {
+    jobs: BTreeMap<Ulid, Job>,
+    releases: Vec<Release>,
+    skipped: u64,
+}
+
+impl DeadlineTracker {
+    pub fn admitted(&mut self, task: &Task, now: Duration) {
+        let spec = task.get_spec();
+        let release = match spec.period_ms {
+            Some(period) if spec.jobs > 1 => {
+                let period = Duration::from_millis(period);
+                self.releases.push(Release {
+                    name: task.name(),
+                    spec: Spec {
+                        jobs: 1,
+                        ..spec.clone()
+                    },
+                    period,
+                    next: now + period,
+                    left: spec.jobs - 1,
+                    skip: false,
+                });
+                Some(self.releases.len() - 1)
+            }
+            _ => None,
+        };
+        let Some(deadline) = spec.deadline_ms.or(spec.period_ms) else {
+            return;
+        };
+
+        self.jobs.insert(
+            task.get_id(),
+            Job {
+                label: task.label(),
+                policy: spec.on_miss,
+                due: now + Duration::from_millis(deadline),
+                release,
+                missed: false,
+                aborted: false,
+                late_by: None,
+            },
+        );
+    }
+
+    pub fn released(&mut self, job: Ulid, release: usize) {
+        if let Some(job) = self.jobs.get_mut(&job) {
+            job.release = Some(release);
+        }
+    }
+
+    pub fn due(&mut self, now: Duration) -> Vec<(usize, Spec)> {
+        let mut due = Vec::new();
+        for (index, release) in self.releases.iter_mut().enumerate() {
+            while release.left > 0 && release.next <= now {
+                release.left -= 1;
+                release.next += release.period;
+                if std::mem::take(&mut release.skip) {
+                    self.skipped += 1;
+                    crate::say!(
+                        "Skipped a release of {} after a missed deadline",
+                        release.name
+                    );
+                    continue;
+                }
+                due.push((index, release.spec.clone()));
+            }
+        }
+        due
+    }
+
+    pub fn missed(&self) -> usize {
+        self.jobs.values().filter(|job| job.missed).count()
+    }
+
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    pub fn pending(&self) -> bool {
+        self.releases.iter().any(|release| release.left > 0)
+    }
+
+    pub fn overdue(&mut self, tasks: &Registry, now: Duration) -> Vec<Ulid> {
+        let mut abort = Vec::new();
+        for (&id, job) in &mut self.jobs {
+            if job.missed || now < job.due || tasks[id].state == State::Terminated {
+                continue;
+            }
+
+            job.missed = true;
+            crate::say!(
+                "Deadline missed: PID: {} was due at {} seconds ({})",
+                job.label,
+                job.due.as_secs_f64(),
+                job.policy
+            );
+            match (job.policy, job.release) {
+                (MissPolicy::Abort, _) => {
+                    job.aborted = true;
+                    abort.push(id);
+                }
+                (MissPolicy::Skip, Some(release)) => self.releases[release].skip = true,
+                _ => {}
+            }
+        }
+        abort
+    }
+
+    pub fn finished(&mut self, task: &Task, now: Duration) {
+        let Some(job) = self.jobs.get_mut(&task.get_id()) else {
+            return;
+        };
+        if job.aborted || now <= job.due {
+            return;
+        }
+
+        job.late_by = Some(now - job.due);
+        if !job.missed {
+            job.missed = true;
+            if let (MissPolicy::Skip, Some(release)) = (job.policy, job.release) {
+                self.releases[release].skip = true;
+            }
+        }
+    }
+
+    pub fn print_report(&self) {
+        if self.jobs.is_empty() {
+            return;
+        }
+
+        let missed = self.missed();
+        crate::report!(
+            "------------------------------------------\n\
+             DEADLINE REPORT\n\
+             Deadlines:      {} met, {} missed",
+            self.jobs.len() - missed,
+            missed
+        );
+
+        for job in self.jobs.values().filter(|job| job.missed) {
+            let outcome = match job.late_by {
+                _ if job.aborted => "aborted".to_string(),
+                Some(late_by) => format!("finished {} seconds late", late_by.as_secs_f64()),
+                None => "unfinished".to_string(),
+            };
+            crate::report!(
+                "PID:            {}\n\
+                 Due:            {} seconds\n\
+                 Policy:         {}\n\
+                 Outcome:        {}",
+                job.label,
+                job.due.as_secs_f64(),
+                job.policy,
+                outcome
+            );
+        }
+
+        for policy in [MissPolicy::Abort, MissPolicy::Late, MissPolicy::Skip] {
+            let jobs: Vec<&Job> = self.jobs.values().filter(|j| j.policy == policy).collect();
+            if jobs.is_empty() {
+                continue;
+            }
+            let missed = jobs.iter().filter(|job| job.missed).count();
+            let detail = match policy {
+                MissPolicy::Abort => {
+                    format!(", {} aborted", jobs.iter().filter(|j| j.aborted).count())
+                }
+                MissPolicy::Late => String::new(),
+                MissPolicy::Skip => format!(", {} releases skipped", self.skipped),
+            };
+            crate::report!(
+                "Policy {:<8} {} of {} missed{}",
+                format!("{}:", policy),
+                missed,
+                jobs.len(),
+                detail
+            );
+        }
+        crate::report!("------------------------------------------");
+    }
+}
@@ -0,0 +1,267 @@
+use crate::registry::Registry;
+use crate::task::{State, Task};
+use std::collections::HashMap;
+use std::str::FromStr;
+use ulid::Ulid;
+
+pub const COMMANDS: &str =
+    "step, continue, until PID terminates, inspect PID, break [PID|any state=STATE [count=N]], delete N";
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Mode {
+    Step,
+    Continue,
+    Until(Ulid),
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Breakpoint {
+    pub task: Option<Ulid>,
+    pub state: State,
+    pub count: u32,
+}
+
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let task = match words.next() {
+            Some("any") => None,
+            Some(id) => Some(
+                id.parse()
+                    .map_err(|_| format!("Invalid PID in breakpoint: {}", id))?,
+            ),
+            None => return Err("A breakpoint needs a PID or `any`".to_string()),
+        };
+
+        let mut state = None;
+        let mut count = 1;
+        for word in words {
+            match word.split_once('=') {
+                Some(("state", value)) => state = Some(value.parse()?),
+                Some(("count", value)) => {
+                    count = value
+                        .parse()
+                        .ok()
+                        .filter(|&count| count > 0)
+                        .ok_or_else(|| format!("Invalid count in breakpoint: {}", value))?;
+                }
+                _ => return Err(format!("Unknown breakpoint condition: {}", word)),
+            }
+        }
+
+        Ok(Self {
+            task,
+            state: state.ok_or("A breakpoint needs a state=STATE condition")?,
+            count,
+        })
+    }
+}
+
+impl std::fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.task {
+            Some(id) => write!(f, "{}", id)?,
+            None => write!(f, "any")?,
+        }
+        write!(f, " state={}", self.state)?;
+        if self.count > 1 {
+            write!(f, " count={}", self.count)?;
+        }
+        Ok(())
+    }
+}
+
+struct Armed {
+    number: usize,
+    breakpoint: Breakpoint,
+    hits: u32,
+}
+
+pub struct Debugger {
+    pub mode: Mode,
+    pub stopped: bool,
+    breakpoints: Vec<Armed>,
+    numbered: usize,
+    seen: HashMap<Ulid, State>,
+}
+
+impl Default for Debugger {
+    fn default() -> Self {
+        Self::new(Mode::Step)
+    }
+}
+
+impl Debugger {
+    pub fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            stopped: false,
+            breakpoints: Vec::new(),
+            numbered: 0,
+            seen: HashMap::new(),
+        }
+    }
+
+    pub fn breaks(&self) -> bool {
+        self.mode == Mode::Step
+    }
+
+    pub fn terminated(&mut self, id: Ulid) -> bool {
+        if self.mode != Mode::Until(id) {
+            return false;
+        }
+        self.mode = Mode::Step;
+        true
+    }
+
+    pub fn add(&mut self, breakpoint: Breakpoint) -> usize {
+        self.numbered += 1;
+        self.breakpoints.push(Armed {
+            number: self.numbered,
+            breakpoint,
+            hits: 0,
+        });
+        self.numbered
+    }
+
+    pub fn delete(&mut self, number: usize) -> bool {
+        let before = self.breakpoints.len();
+        self.breakpoints.retain(|armed| armed.number != number);
+        self.breakpoints.len() < before
+    }
+
+    pub fn list(&self) -> String {
+        if self.breakpoints.is_empty() {
+            return "No breakpoints".to_string();
+        }
+        self.breakpoints
+            .iter()
+            .map(|armed| {
+                format!(
+                    "Breakpoint {}: {} ({} of {} hits)",
+                    armed.number, armed.breakpoint, armed.hits, armed.breakpoint.count
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn observe(&mut self, tasks: &Registry) -> Vec<String> {
+        let mut hits = Vec::new();
+        for task in tasks.iter() {
+            let id = task.get_id();
+            if self.seen.insert(id, task.state) == Some(task.state) {
+                continue;
+            }
+            for armed in &mut self.breakpoints {
+                let breakpoint = armed.breakpoint;
+                if breakpoint.state != task.state || breakpoint.task.is_some_and(|t| t != id) {
+                    continue;
+                }
+                armed.hits += 1;
+                if armed.hits < breakpoint.count {
+                    continue;
+                }
+                armed.hits = 0;
+                hits.push(format!(
+                    "Breakpoint {} hit: PID: {} entered {}",
+                    armed.number, id, task.state
+                ));
+            }
+        }
+
+        if !hits.is_empty() {
+            self.mode = Mode::Step;
+        }
+        hits
+    }
+}
+
+pub fn inspect(task: &Task) -> String {
+    let mut command = task.get_path_to_binary().display().to_string();
+    let args = task.get_spec().arguments();
+    if !args.is_empty() {
+        command.push(' ');
+        command.push_str(&crate::control::quote_words(&args));
+    }
+
+    let metadata = task
+        .get_spec()
+        .metadata
+        .iter()
+        .map(|(key, value)| format!("\nMetadata:       {}={}", key, value))
+        .collect::<String>();
+
+    format!(
+        "PID:            {}\n\
+         Name:           {}\n\
+         Command:        {}\n\
+         State:          {}\n\
+         Priority:       {} (boost {:+}, effective {})\n\
+         CPU Time:       {} seconds\n\
+         Enqueued At:    quantum {}\n\
+         Process:        {}{}",
+        task.get_id(),
+        task.name(),
+        command,
+        task.state,
+        task.priority,
+        task.boost,
+        task.effective_priority(),
+        task.cpu_time.as_secs_f64(),
+        task.enqueued_at,
+        task.get_pid()
+            .map_or("not started".to_string(), |pid| pid.to_string()),
+        metadata
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breakpoints_fire_on_every_nth_matching_transition() {
+        let mut tasks = Registry::default();
+        let a = tasks.insert(Task::new("a", None, 1));
+        let b = tasks.insert(Task::new("b", None, 1));
+
+        let mut debugger = Debugger::new(Mode::Continue);
+        assert_eq!(
+            debugger.add("any state=waiting count=2".parse().unwrap()),
+            1
+        );
+        let only_b = format!("{} state=Terminated", b);
+        assert_eq!(debugger.add(only_b.parse().unwrap()), 2);
+
+        assert!(debugger.observe(&tasks).is_empty());
+
+        tasks[a].set_state(State::Waiting);
+        assert!(debugger.observe(&tasks).is_empty());
+        assert!(debugger.observe(&tasks).is_empty());
+        tasks[b].set_state(State::Waiting);
+        assert_eq!(
+            debugger.observe(&tasks),
+            [format!("Breakpoint 1 hit: PID: {} entered WAITING", b)]
+        );
+        assert_eq!(debugger.mode, Mode::Step);
+
+        debugger.mode = Mode::Continue;
+        tasks[a].set_state(State::Terminated);
+        assert!(debugger.observe(&tasks).is_empty());
+        tasks[b].set_state(State::Terminated);
+        assert_eq!(debugger.observe(&tasks).len(), 1);
+
+        assert!(debugger.delete(1));
+        assert!(!debugger.delete(1));
+        assert_eq!(
+            debugger.list(),
+            format!("Breakpoint 2: {} state=TERMINATED (0 of 1 hits)", b)
+        );
+        assert!("any".parse::<Breakpoint>().is_err());
+        assert!("any state=asleep".parse::<Breakpoint>().is_err());
+        assert!("any state=ready count=0".parse::<Breakpoint>().is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use crate::registry::Registry;
+use crate::task::{State, Task};
+use std::collections::{HashMap, HashSet};
+use ulid::Ulid;
+
+fn unfinished<'a>(tasks: &'a Registry, after: &'a [String]) -> impl Iterator<Item = &'a Task> {
+    tasks.iter().filter(move |task| {
+        task.state != State::Terminated && after.iter().any(|reference| task.is_called(reference))
+    })
+}
+
+pub fn blockers(tasks: &Registry, id: Ulid) -> Vec<Ulid> {
+    let after = &tasks[id].get_spec().after;
+    unfinished(tasks, after)
+        .map(Task::get_id)
+        .filter(|&blocker| blocker != id)
+        .collect()
+}
+
+pub fn leads_back(tasks: &Registry, task: &Task) -> bool {
+    let mut seen = HashSet::new();
+    let mut stack = vec![task.get_spec().after.as_slice()];
+    while let Some(after) = stack.pop() {
+        for blocker in unfinished(tasks, after) {
+            if !seen.insert(blocker.get_id()) {
+                continue;
+            }
+            let after = &blocker.get_spec().after;
+            if after.iter().any(|reference| task.is_called(reference)) {
+                return true;
+            }
+            stack.push(after);
+        }
+    }
+    false
+}
+
+pub fn donations(
+    tasks: &Registry,
+    waiting: &[Ulid],
+    holders: &HashMap<Ulid, Ulid>,
+) -> HashMap<Ulid, (u8, Ulid)> {
+    let blocked: Vec<(Ulid, Vec<Ulid>)> = waiting
+        .iter()
+        .map(|&id| {
+            let mut blockers = blockers(tasks, id);
+            blockers.extend(holders.get(&id));
+            (id, blockers)
+        })
+        .filter(|(_, blockers)| !blockers.is_empty())
+        .collect();
+
+    let mut donated: HashMap<Ulid, (u8, Ulid)> = HashMap::new();
+    let priority = |donated: &HashMap<Ulid, (u8, Ulid)>, id: Ulid| {
+        let own = tasks[id].boosted_priority();
+        donated
+            .get(&id)
+            .map_or(own, |&(priority, _)| priority.min(own))
+    };
+    loop {
+        let mut changed = false;
+        for (id, blockers) in &blocked {
+            let offered = priority(&donated, *id);
+            for &blocker in blockers {
+                if offered < priority(&donated, blocker) {
+                    donated.insert(blocker, (offered, *id));
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return donated;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Spec;
+
+    fn task(name: &str, priority: u8, after: &[&str]) -> Task {
+        Task::from_spec(Spec {
+            name: Some(name.to_string()),
+            after: after.iter().map(|s| s.to_string()).collect(),
+            ..Spec::new("/bin/true", None, priority)
+        })
+    }
+
+    #[test]
+    fn priority_flows_down_the_dependency_chain() {
+        let mut tasks = Registry::default();
+        let build = tasks.insert(task("build", 6, &[]));
+        let test = tasks.insert(task("test", 4, &["build"]));
+        let deploy = tasks.insert(task("deploy", 1, &["test"]));
+
+        assert_eq!(blockers(&tasks, deploy), [test]);
+        let donated = donations(&tasks, &[build, test, deploy], &HashMap::new());
+        assert_eq!(donated[&test], (1, deploy));
+        assert_eq!(donated[&build], (1, test));
+
+        assert!(leads_back(&tasks, &task("build", 2, &["deploy"])));
+        assert!(!leads_back(&tasks, &task("lint", 2, &["deploy"])));
+    }
+}
@@ -0,0 +1,249 @@
+use crate::output::{self, Style};
+use crate::report::Export;
+use crate::summary::{RunSummary, TaskResult};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Verdict {
+    Better,
+    Worse,
+    Unchanged,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Verdict::Better => "better",
+            Verdict::Worse => "worse",
+            Verdict::Unchanged => "unchanged",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct TaskDelta<'a> {
+    pub name: String,
+    pub before: &'a TaskResult,
+    pub after: &'a TaskResult,
+}
+
+impl TaskDelta<'_> {
+    pub fn verdict(&self) -> Verdict {
+        match (self.before.succeeded(), self.after.succeeded()) {
+            (true, false) => return Verdict::Worse,
+            (false, true) => return Verdict::Better,
+            _ => {}
+        }
+        match self.after.waited.cmp(&self.before.waited) {
+            std::cmp::Ordering::Less => Verdict::Better,
+            std::cmp::Ordering::Greater => Verdict::Worse,
+            std::cmp::Ordering::Equal => Verdict::Unchanged,
+        }
+    }
+}
+
+pub struct Comparison<'a> {
+    pub tasks: Vec<TaskDelta<'a>>,
+    pub only_before: Vec<String>,
+    pub only_after: Vec<String>,
+}
+
+fn keyed(summary: &RunSummary) -> Vec<(String, &TaskResult)> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    summary
+        .iter()
+        .map(|result| {
+            let count = seen.entry(&result.name).or_default();
+            *count += 1;
+            let key = match count {
+                1 => result.name.clone(),
+                n => format!("{}#{}", result.name, n),
+            };
+            (key, result)
+        })
+        .collect()
+}
+
+pub fn compare<'a>(before: &'a RunSummary, after: &'a RunSummary) -> Comparison<'a> {
+    let before = keyed(before);
+    let after = keyed(after);
+    let tasks = before
+        .iter()
+        .filter_map(|(name, before)| {
+            after
+                .iter()
+                .find(|(other, _)| other == name)
+                .map(|&(_, after)| TaskDelta {
+                    name: name.clone(),
+                    before,
+                    after,
+                })
+        })
+        .collect();
+    let missing = |from: &[(String, &TaskResult)], to: &[(String, &TaskResult)]| {
+        from.iter()
+            .filter(|(name, _)| !to.iter().any(|(other, _)| other == name))
+            .map(|(name, _)| name.clone())
+            .collect()
+    };
+
+    Comparison {
+        only_before: missing(&before, &after),
+        only_after: missing(&after, &before),
+        tasks,
+    }
+}
+
+fn signed(after: Duration, before: Duration) -> String {
+    let delta = after.as_secs_f64() - before.as_secs_f64();
+    format!("{:+.2}s", delta)
+}
+
+fn styled(verdict: Verdict) -> String {
+    let style = match verdict {
+        Verdict::Better => Style::Green,
+        Verdict::Worse => Style::Red,
+        Verdict::Unchanged => Style::Dim,
+    };
+    output::paint(verdict, style)
+}
+
+pub fn print(before: &Path, after: &Path) -> Result<(), String> {
+    let old = Export::load(before)?;
+    let new = Export::load(after)?;
+    let comparison = compare(&old.summary, &new.summary);
+
+    println!(
+        "------------------------------------------\n\
+         RUN DIFF\n\
+         Before:         {}\n\
+         After:          {}",
+        before.display(),
+        after.display()
+    );
+    for (setting, value) in &old.configuration {
+        match new.configuration.get(setting) {
+            Some(other) if other != value => {
+                println!("{:<16}{} -> {}", format!("{}:", setting), value, other)
+            }
+            _ => {}
+        }
+    }
+
+    println!(
+        "{:<20}  {:>11}  {:>11}  {:>9}  {:>9}  {:>5}  VERDICT",
+        "TASK", "WAIT BEFORE", "WAIT AFTER", "DELTA", "CPU", "RUNS"
+    );
+    for task in &comparison.tasks {
+        println!(
+            "{:<20}  {:>10.2}s  {:>10.2}s  {:>9}  {:>9}  {:>+5}  {}",
+            task.name,
+            task.before.waited.as_secs_f64(),
+            task.after.waited.as_secs_f64(),
+            signed(task.after.waited, task.before.waited),
+            signed(task.after.cpu_time, task.before.cpu_time),
+            i64::from(task.after.runs) - i64::from(task.before.runs),
+            styled(task.verdict())
+        );
+    }
+
+    let count = |verdict: Verdict| {
+        comparison
+            .tasks
+            .iter()
+            .filter(|task| task.verdict() == verdict)
+            .count()
+    };
+    let (old, new) = (&old.summary, &new.summary);
+    println!(
+        "Mean Wait:      {:.2}s -> {:.2}s ({})\n\
+         Mean Duration:  {:.2}s -> {:.2}s ({:+.2}s)\n\
+         Quanta:         {} -> {} ({:+})\n\
+         Succeeded:      {} -> {}\n\
+         Failed:         {} -> {}\n\
+         Tasks:          {} better, {} worse, {} unchanged",
+        old.mean_wait().as_secs_f64(),
+        new.mean_wait().as_secs_f64(),
+        signed(new.mean_wait(), old.mean_wait()),
+        old.mean_duration(),
+        new.mean_duration(),
+        new.mean_duration() - old.mean_duration(),
+        old.quanta,
+        new.quanta,
+        new.quanta as i64 - old.quanta as i64,
+        old.succeeded(),
+        new.succeeded(),
+        old.failed() + old.poisoned(),
+        new.failed() + new.poisoned(),
+        count(Verdict::Better),
+        count(Verdict::Worse),
+        count(Verdict::Unchanged)
+    );
+    if !comparison.only_before.is_empty() {
+        println!("Only Before:    {}", comparison.only_before.join(", "));
+    }
+    if !comparison.only_after.is_empty() {
+        println!("Only After:     {}", comparison.only_after.join(", "));
+    }
+    println!("------------------------------------------");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{ExitCode, State, Task};
+
+    fn summary(waits: &[(&str, u64, Option<ExitCode>)]) -> RunSummary {
+        let tasks: Vec<Task> = waits
+            .iter()
+            .map(|&(name, waited, exit_code)| {
+                let mut task = Task::new(name, None, 1);
+                task.waited = waited;
+                task.exit_code = exit_code;
+                task.set_state(State::Terminated);
+                task
+            })
+            .collect();
+        RunSummary::new(tasks.iter(), 10, Duration::from_millis(100), false)
+    }
+
+    #[test]
+    fn tasks_are_matched_by_name_and_judged_by_their_wait() {
+        let ok = Some(ExitCode::Success);
+        let before = summary(&[
+            ("a", 5, ok),
+            ("b", 1, ok),
+            ("c", 2, ok),
+            ("c", 3, ok),
+            ("gone", 0, ok),
+        ]);
+        let after = summary(&[
+            ("a", 2, ok),
+            ("b", 1, Some(ExitCode::Failure)),
+            ("c", 2, ok),
+            ("c", 4, ok),
+            ("new", 0, ok),
+        ]);
+
+        let comparison = compare(&before, &after);
+        let verdicts: Vec<(&str, Verdict)> = comparison
+            .tasks
+            .iter()
+            .map(|task| (task.name.as_str(), task.verdict()))
+            .collect();
+        assert_eq!(
+            verdicts,
+            [
+                ("a", Verdict::Better),
+                ("b", Verdict::Worse),
+                ("c", Verdict::Unchanged),
+                ("c#2", Verdict::Worse)
+            ]
+        );
+        assert_eq!(comparison.only_before, ["gone"]);
+        assert_eq!(comparison.only_after, ["new"]);
+    }
+}
@@ -0,0 +1,269 @@
+use crate::executor::Pid;
+use crate::procfs;
+use nix::libc;
+use nix::sys::signal::{self, Signal};
+use nix::sys::wait::{self, WaitPidFlag, WaitStatus};
+use nix::unistd::{self, AccessFlags};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Degraded,
+    Failed,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Ok => "ok",
+            Self::Degraded => "degraded",
+            Self::Failed => "FAILED",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    pub affects: &'static str,
+}
+
+impl Check {
+    fn new(name: &'static str, affects: &'static str, result: Result<String, String>) -> Self {
+        let (status, detail) = match result {
+            Ok(detail) => (Status::Ok, detail),
+            Err(detail) => (Status::Degraded, detail),
+        };
+        Self {
+            name,
+            status,
+            detail,
+            affects,
+        }
+    }
+
+    fn required(mut self) -> Self {
+        if self.status == Status::Degraded {
+            self.status = Status::Failed;
+        }
+        self
+    }
+}
+
+fn raw(child: &Child) -> unistd::Pid {
+    unistd::Pid::from_raw(child.id() as i32)
+}
+
+fn signals(child: &Child) -> Result<String, String> {
+    let pid = raw(child);
+    signal::kill(pid, Signal::SIGSTOP).map_err(|err| format!("SIGSTOP failed: {}", err))?;
+    match wait::waitpid(pid, Some(WaitPidFlag::WUNTRACED)) {
+        Ok(WaitStatus::Stopped(_, Signal::SIGSTOP)) => {}
+        other => return Err(format!("SIGSTOP was not reported as a stop: {:?}", other)),
+    }
+    signal::kill(pid, Signal::SIGCONT).map_err(|err| format!("SIGCONT failed: {}", err))?;
+    match wait::waitpid(pid, Some(WaitPidFlag::WCONTINUED)) {
+        Ok(WaitStatus::Continued(_)) => {}
+        other => return Err(format!("SIGCONT was not reported as a resume: {:?}", other)),
+    }
+    Ok("SIGSTOP and SIGCONT pause and resume a child".to_string())
+}
+
+fn waitpid(child: &Child) -> Result<String, String> {
+    match wait::waitpid(raw(child), Some(WaitPidFlag::WNOHANG)) {
+        Ok(WaitStatus::StillAlive) => {
+            Ok("WNOHANG, WUNTRACED and WCONTINUED are supported".to_string())
+        }
+        other => Err(format!("WNOHANG on a running child returned {:?}", other)),
+    }
+}
+
+fn proc() -> Result<String, String> {
+    let pid = Pid::from_raw(std::process::id());
+    procfs::process_stats(pid).ok_or("/proc/self/stat is not readable")?;
+    procfs::cpu_times().ok_or("/proc/stat is not readable")?;
+    procfs::schedstat(pid).ok_or("/proc/self/schedstat is missing (no CONFIG_SCHEDSTATS)")?;
+    Ok("/proc/[pid]/stat, /proc/[pid]/schedstat and /proc/stat are readable".to_string())
+}
+
+fn writable(path: &Path) -> bool {
+    unistd::access(path, AccessFlags::W_OK).is_ok()
+}
+
+fn cgroups() -> Result<String, String> {
+    let root = Path::new("/sys/fs/cgroup");
+    if !root.join("cgroup.controllers").exists() {
+        return Err("cgroup v2 is not mounted at /sys/fs/cgroup".to_string());
+    }
+    let own = std::fs::read_to_string("/proc/self/cgroup").unwrap_or_default();
+    let group: PathBuf = match own.lines().find_map(|line| line.strip_prefix("0::")) {
+        Some(path) => root.join(path.trim_start_matches('/')),
+        None => return Err("/proc/self/cgroup has no cgroup v2 entry".to_string()),
+    };
+    if !writable(&group.join("cgroup.procs")) {
+        return Err(format!("{} is not delegated to this user", group.display()));
+    }
+    if which("systemd-run").is_none() {
+        return Err("systemd-run is not on PATH".to_string());
+    }
+    Ok(format!(
+        "{} is delegated and systemd-run is available",
+        group.display()
+    ))
+}
+
+fn which(program: &str) -> Option<PathBuf> {
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|path| path.is_file())
+}
+
+fn nice(child: &Child) -> Result<String, String> {
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, child.id(), -1) };
+    if result == 0 {
+        Ok("negative nice values can be set".to_string())
+    } else {
+        Err(format!(
+            "setting nice -1 failed: {} (needs CAP_SYS_NICE or RLIMIT_NICE)",
+            std::io::Error::last_os_error()
+        ))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn affinity(child: &Child) -> Result<String, String> {
+    use nix::sched::{self, CpuSet};
+    let allowed = sched::sched_getaffinity(raw(child)).map_err(|err| err.to_string())?;
+    let core = (0..CpuSet::count())
+        .find(|&core| allowed.is_set(core).unwrap_or(false))
+        .ok_or("no CPU is allowed")?;
+    let mut set = CpuSet::new();
+    set.set(core).map_err(|err| err.to_string())?;
+    sched::sched_setaffinity(raw(child), &set)
+        .map_err(|err| format!("sched_setaffinity failed: {}", err))?;
+    Ok(format!("tasks can be pinned (tried CPU {})", core))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn affinity(_child: &Child) -> Result<String, String> {
+    Err("sched_setaffinity is Linux only".to_string())
+}
+
+fn ptrace() -> Result<String, String> {
+    let scope = std::fs::read_to_string("/proc/sys/kernel/yama/ptrace_scope")
+        .ok()
+        .and_then(|scope| scope.trim().parse::<u8>().ok());
+    match scope {
+        Some(3) => Err("Yama ptrace_scope is 3, ptrace is disabled".to_string()),
+        Some(2) if !unistd::geteuid().is_root() => {
+            Err("Yama ptrace_scope is 2, ptrace needs CAP_SYS_PTRACE".to_string())
+        }
+        Some(scope) => Ok(format!("Yama ptrace_scope is {}", scope)),
+        None => Ok("no Yama restrictions".to_string()),
+    }
+}
+
+fn namespaces() -> Result<String, String> {
+    if unistd::geteuid().is_root() {
+        return Ok("running as root".to_string());
+    }
+    let setting = |path: &str| {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+    };
+    if setting("/proc/sys/user/max_user_namespaces") == Some(0) {
+        return Err("user.max_user_namespaces is 0".to_string());
+    }
+    if setting("/proc/sys/kernel/unprivileged_userns_clone") == Some(0) {
+        return Err("kernel.unprivileged_userns_clone is 0".to_string());
+    }
+    Ok("unprivileged user namespaces are allowed".to_string())
+}
+
+pub fn run() -> Vec<Check> {
+    let mut checks = Vec::new();
+    let child = Command::new("sleep").arg("30").spawn();
+    match &child {
+        Ok(child) => {
+            checks
+                .push(Check::new("signals", "time-slicing local tasks", signals(child)).required());
+            checks.push(
+                Check::new("waitpid", "reaping and pausing local tasks", waitpid(child)).required(),
+            );
+            checks.push(Check::new(
+                "nice",
+                "negative nice values for tasks",
+                nice(child),
+            ));
+            checks.push(Check::new(
+                "affinity",
+                "pinning tasks to CPUs",
+                affinity(child),
+            ));
+        }
+        Err(err) => checks.push(
+            Check::new(
+                "signals",
+                "time-slicing local tasks",
+                Err(format!("could not spawn sleep: {}", err)),
+            )
+            .required(),
+        ),
+    }
+    if let Ok(mut child) = child {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    checks.push(Check::new(
+        "proc",
+        "kernel statistics, --compare-kernel and confirming paused tasks stopped",
+        proc(),
+    ));
+    checks.push(Check::new("cgroups", "--systemd", cgroups()));
+    checks.push(Check::new("ptrace", "--count-syscalls", ptrace()));
+    checks.push(Check::new(
+        "namespaces",
+        "--sandbox without root",
+        namespaces(),
+    ));
+    checks
+}
+
+pub fn print(checks: &[Check]) {
+    for check in checks {
+        println!("{:<9} {:<11} {}", check.status, check.name, check.detail);
+        if check.status != Status::Ok {
+            println!("{:<21} affects: {}", "", check.affects);
+        }
+    }
+    let degraded = checks.iter().filter(|c| c.status != Status::Ok).count();
+    if degraded == 0 {
+        println!("All checks passed");
+    } else {
+        println!("{} of {} checks did not pass", degraded, checks.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_child_can_be_paused_resumed_and_polled() {
+        let checks = run();
+        for name in ["signals", "waitpid"] {
+            let check = checks.iter().find(|c| c.name == name).unwrap();
+            assert_eq!(check.status, Status::Ok, "{}", check.detail);
+        }
+        assert_eq!(
+            Check::new("x", "y", Err(String::new())).required().status,
+            Status::Failed
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use super::{stop, Executor, Pid};
+use crate::procfs::{self, ProcessStats, Schedstat};
+use crate::task::{ExitCode, Spec, Status, Task};
+use nix::errno::Errno;
+use nix::sys::signal::{self, SigHandler, Signal};
+use std::io;
+use std::sync::OnceLock;
+use std::time::Duration;
+use ulid::Ulid;
+
+static ADOPTED: OnceLock<Vec<Pid>> = OnceLock::new();
+
+#[derive(Default)]
+pub struct AdoptedExecutor;
+
+fn raw(pid: Pid) -> nix::unistd::Pid {
+    nix::unistd::Pid::from_raw(pid.as_raw() as i32)
+}
+
+fn signal_unless_gone(pid: Pid, signal: Signal) -> io::Result<()> {
+    match signal::kill(raw(pid), signal) {
+        Ok(()) | Err(Errno::ESRCH) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+extern "C" fn release_and_exit(signal: i32) {
+    for &pid in ADOPTED.get().into_iter().flatten() {
+        let _ = signal::kill(raw(pid), Signal::SIGCONT);
+    }
+    unsafe { nix::libc::_exit(128 + signal) }
+}
+
+impl AdoptedExecutor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn adopt(&mut self, pid: Pid, priority: u8) -> io::Result<Task> {
+        if pid.as_raw() == std::process::id() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "psched cannot adopt itself",
+            ));
+        }
+
+        signal::kill(raw(pid), Signal::SIGSTOP)?;
+
+        let mut command = procfs::cmdline(pid).unwrap_or_else(|| vec![format!("pid {}", pid)]);
+        let path_to_binary = command.remove(0);
+        let args = if command.is_empty() {
+            None
+        } else {
+            Some(command)
+        };
+
+        Ok(Task::adopt(pid, Spec::new(path_to_binary, args, priority)))
+    }
+
+    pub fn release_on_signal(pids: Vec<Pid>) {
+        if ADOPTED.set(pids).is_err() {
+            return;
+        }
+
+        for signal in [Signal::SIGINT, Signal::SIGTERM, Signal::SIGHUP] {
+            let _ = unsafe { signal::signal(signal, SigHandler::Handler(release_and_exit)) };
+        }
+    }
+}
+
+impl Executor for AdoptedExecutor {
+    fn spawn(&mut self, _id: Ulid, spec: &Spec) -> io::Result<Pid> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "adopted tasks are never spawned ({})",
+                spec.path_to_binary.display()
+            ),
+        ))
+    }
+
+    fn poll(&mut self, pid: Pid) -> io::Result<Status> {
+        match signal::kill(raw(pid), None) {
+            Err(Errno::ESRCH) => return Ok(Status::Terminated(ExitCode::Success)),
+            Err(err) => return Err(err.into()),
+            Ok(()) => {}
+        }
+
+        match procfs::state(pid) {
+            Some('Z' | 'X') => Ok(Status::Terminated(ExitCode::Success)),
+            _ => Ok(Status::Running),
+        }
+    }
+
+    fn stop(&mut self, pid: Pid) -> io::Result<()> {
+        signal_unless_gone(pid, Signal::SIGSTOP)?;
+        stop::confirm(pid, || signal_unless_gone(pid, Signal::SIGSTOP))
+    }
+
+    fn cont(&mut self, pid: Pid) -> io::Result<()> {
+        signal_unless_gone(pid, Signal::SIGCONT)
+    }
+
+    fn kill(&mut self, pid: Pid) {
+        let _ = signal::kill(raw(pid), Signal::SIGCONT);
+    }
+
+    fn cpu_time(&self, pid: Pid) -> Option<Duration> {
+        procfs::cpu_time(pid)
+    }
+
+    fn process_stats(&self, pid: Pid) -> Option<ProcessStats> {
+        procfs::process_stats(pid)
+    }
+
+    fn schedstat(&self, pid: Pid) -> Option<Schedstat> {
+        procfs::schedstat(pid)
+    }
+
+    fn set_nice(&mut self, pid: Pid, nice: i32) -> io::Result<()> {
+        super::renice(pid, nice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn adopted_processes_are_stopped_until_dispatched() {
+        let mut child = Command::new("sleep").arg("5").spawn().unwrap();
+        let pid = Pid::from_raw(child.id());
+        let mut executor = AdoptedExecutor::new();
+        while procfs::cmdline(pid).is_none_or(|words| words[0] != "sleep") {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        let task = executor.adopt(pid, 2).unwrap();
+        assert_eq!(task.get_pid(), Some(pid));
+        assert!(task.get_path_to_binary().ends_with("sleep"));
+        for _ in 0..1000 {
+            if procfs::state(pid) == Some('T') {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(procfs::state(pid), Some('T'));
+        assert_eq!(executor.poll(pid).unwrap(), Status::Running);
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert_eq!(
+            executor.poll(pid).unwrap(),
+            Status::Terminated(ExitCode::Success)
+        );
+        assert!(executor.stop(pid).is_ok());
+    }
+}
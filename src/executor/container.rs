@@ -0,0 +1,136 @@
+use super::Pid;
+use crate::task::{Container, Runtime, Spec};
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::process::{Child, Command, Stdio};
+use ulid::Ulid;
+
+struct Running {
+    runtime: Runtime,
+    name: String,
+}
+
+#[derive(Default)]
+pub struct Containers {
+    running: HashMap<Pid, Running>,
+}
+
+fn cli<S: AsRef<OsStr>>(runtime: Runtime, args: &[S]) -> io::Result<()> {
+    let output = Command::new(runtime.program())
+        .args(args)
+        .stdin(Stdio::null())
+        .output()?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    Err(io::Error::other(format!(
+        "{} {} failed: {}",
+        runtime.program(),
+        args[0].as_ref().to_string_lossy(),
+        String::from_utf8_lossy(&output.stderr).trim()
+    )))
+}
+
+fn create_args(container: &Container, spec: &Spec, name: &str) -> Vec<OsString> {
+    let mut args: Vec<OsString> =
+        vec!["create".into(), "--rm".into(), "--name".into(), name.into()];
+
+    if let Some(cpus) = container.cpus {
+        args.extend(["--cpus".into(), cpus.to_string().into()]);
+    }
+    if let Some(memory) = &spec.memory {
+        args.extend(["--memory".into(), memory.into()]);
+    }
+
+    args.push(container.image.as_str().into());
+    args.push(spec.path_to_binary.clone().into_os_string());
+    args.extend(spec.arguments().into_iter().map(OsString::from));
+    args
+}
+
+impl Containers {
+    pub fn spawn(&mut self, id: Ulid, container: &Container, spec: &Spec) -> io::Result<Child> {
+        let name = format!("psched-{}", id);
+        cli(container.runtime, &create_args(container, spec, &name))?;
+
+        let child = Command::new(container.runtime.program())
+            .args(["start", "--attach", &name])
+            .stdin(Stdio::null())
+            .spawn();
+
+        match child {
+            Ok(child) => {
+                self.running.insert(
+                    Pid::from_raw(child.id()),
+                    Running {
+                        runtime: container.runtime,
+                        name,
+                    },
+                );
+                Ok(child)
+            }
+            Err(err) => {
+                let _ = cli(container.runtime, &["rm", "--force", &name]);
+                Err(err)
+            }
+        }
+    }
+
+    pub fn pause(&self, pid: Pid) -> Option<io::Result<()>> {
+        let running = self.running.get(&pid)?;
+        Some(cli(running.runtime, &["pause", &running.name]))
+    }
+
+    pub fn unpause(&self, pid: Pid) -> Option<io::Result<()>> {
+        let running = self.running.get(&pid)?;
+        Some(cli(running.runtime, &["unpause", &running.name]))
+    }
+
+    pub fn remove(&mut self, pid: Pid) {
+        if let Some(running) = self.running.remove(&pid) {
+            let _ = cli(running.runtime, &["rm", "--force", &running.name]);
+        }
+    }
+
+    pub fn exited(&mut self, pid: Pid) {
+        self.running.remove(&pid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_and_the_command_follow_the_image() {
+        let container = Container {
+            image: "alpine:3".to_string(),
+            runtime: Runtime::Podman,
+            cpus: Some(0.5),
+        };
+        let spec = Spec {
+            memory: Some("64m".to_string()),
+            ..Spec::new("sleep", Some(vec!["1".to_string()]), 2)
+        };
+
+        assert_eq!(
+            create_args(&container, &spec, "psched-test"),
+            [
+                "create",
+                "--rm",
+                "--name",
+                "psched-test",
+                "--cpus",
+                "0.5",
+                "--memory",
+                "64m",
+                "alpine:3",
+                "sleep",
+                "1"
+            ]
+        );
+    }
+}
@@ -0,0 +1,497 @@
+use super::{Executor, Pid};
+use crate::burst::{Phase, Program};
+use crate::procfs::ProcessStats;
+use crate::simulator::DEFAULT_QUANTUM;
+use crate::task::{BlockReason, ExitCode, Spec, Status};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use ulid::Ulid;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Outcome {
+    Exit(ExitCode),
+    PollError(io::ErrorKind),
+    SpawnError,
+    Panic,
+}
+
+#[derive(Clone)]
+enum Step {
+    Cpu(u32),
+    Io { device: Option<String>, quanta: u64 },
+    Acquire(String),
+    Release(String),
+}
+
+#[derive(Clone)]
+struct Script {
+    steps: Vec<Step>,
+    outcome: Outcome,
+}
+
+enum Next {
+    Cpu(u32),
+    Blocked,
+    Deadlock(String),
+    Done,
+}
+
+struct Process {
+    path: PathBuf,
+    script: Script,
+    running: bool,
+    used: u32,
+    progress: u32,
+    step: usize,
+    blocked_until: Option<u64>,
+    warm: bool,
+    exited: bool,
+}
+
+#[derive(Default)]
+struct Inner {
+    scripts: HashMap<PathBuf, Script>,
+    processes: HashMap<Pid, Process>,
+    spawned: Vec<PathBuf>,
+    next_pid: u32,
+    cold_penalty: Option<u32>,
+    resident: HashMap<PathBuf, u64>,
+    cores: HashMap<usize, Pid>,
+    warm_quanta: u64,
+    cold_quanta: u64,
+    programs: bool,
+    tick: u64,
+    devices: HashMap<String, u64>,
+    locks: HashMap<String, Pid>,
+    waiting: HashMap<Pid, String>,
+    nice: HashMap<PathBuf, i32>,
+    quantum: Option<Duration>,
+}
+
+fn programmed(program: &Program, quantum: Duration) -> Script {
+    let quanta = |duration: &Duration| duration.as_nanos().div_ceil(quantum.as_nanos());
+    let steps = program
+        .phases
+        .iter()
+        .map(|phase| match phase {
+            Phase::Cpu(duration) => {
+                Step::Cpu(u32::try_from(quanta(duration)).unwrap_or(u32::MAX).max(1))
+            }
+            Phase::Io { device, duration } => Step::Io {
+                device: Some(device.clone()),
+                quanta: u64::try_from(quanta(duration)).unwrap_or(u64::MAX),
+            },
+            Phase::Sleep(duration) => Step::Io {
+                device: None,
+                quanta: u64::try_from(quanta(duration)).unwrap_or(u64::MAX),
+            },
+            Phase::Acquire(lock) => Step::Acquire(lock.clone()),
+            Phase::Release(lock) => Step::Release(lock.clone()),
+        })
+        .collect();
+    Script {
+        steps,
+        outcome: Outcome::Exit(ExitCode::Success),
+    }
+}
+
+fn waits_on(inner: &Inner, owner: Pid, pid: Pid) -> bool {
+    let mut owner = owner;
+    for _ in 0..=inner.waiting.len() {
+        if owner == pid {
+            return true;
+        }
+        let Some(next) = inner
+            .waiting
+            .get(&owner)
+            .and_then(|lock| inner.locks.get(lock))
+        else {
+            return false;
+        };
+        owner = *next;
+    }
+    false
+}
+
+impl Inner {
+    fn advance(&mut self, pid: Pid, now: u64) -> Next {
+        loop {
+            let Some(process) = self.processes.get_mut(&pid) else {
+                return Next::Done;
+            };
+            let Some(step) = process.script.steps.get(process.step) else {
+                return Next::Done;
+            };
+            match step {
+                Step::Cpu(quanta) => return Next::Cpu(*quanta),
+                Step::Io { device, quanta } => match process.blocked_until {
+                    Some(until) if now >= until => process.blocked_until = None,
+                    Some(_) => return Next::Blocked,
+                    None => {
+                        let until = match device {
+                            Some(device) => {
+                                let free = self.devices.entry(device.clone()).or_default();
+                                *free = (*free).max(now) + quanta;
+                                *free
+                            }
+                            None => now + quanta,
+                        };
+                        process.blocked_until = Some(until);
+                        continue;
+                    }
+                },
+                Step::Acquire(lock) => match self.locks.get(lock) {
+                    Some(&owner) if owner != pid => {
+                        let lock = lock.clone();
+                        if waits_on(self, owner, pid) {
+                            return Next::Deadlock(lock);
+                        }
+                        self.waiting.insert(pid, lock);
+                        return Next::Blocked;
+                    }
+                    _ => {
+                        self.locks.insert(lock.clone(), pid);
+                        self.waiting.remove(&pid);
+                    }
+                },
+                Step::Release(lock) => {
+                    self.locks.remove(lock);
+                }
+            }
+            if let Some(process) = self.processes.get_mut(&pid) {
+                process.step += 1;
+            }
+        }
+    }
+
+    fn blocker(&self, pid: Pid, now: u64) -> Option<(BlockReason, Option<Pid>)> {
+        let process = self.processes.get(&pid)?;
+        let steps = process.script.steps.iter().skip(process.step);
+        for (offset, step) in steps.enumerate() {
+            match step {
+                Step::Io { .. } => match process.blocked_until {
+                    Some(until) if offset == 0 && now < until => {
+                        return Some((BlockReason::Io, None))
+                    }
+                    Some(_) if offset == 0 => {}
+                    _ => return None,
+                },
+                Step::Acquire(lock) => {
+                    return match self.locks.get(lock) {
+                        Some(&owner) if owner != pid => Some((BlockReason::Resource, Some(owner))),
+                        _ => None,
+                    };
+                }
+                Step::Release(_) => {}
+                Step::Cpu(_) => return None,
+            }
+        }
+        None
+    }
+
+    fn exit(&mut self, pid: Pid) {
+        self.locks.retain(|_, owner| *owner != pid);
+        self.waiting.remove(&pid);
+        if let Some(process) = self.processes.get_mut(&pid) {
+            process.exited = true;
+        }
+    }
+}
+
+fn deadlock(lock: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Deadlock,
+        format!("deadlock acquiring {}", lock),
+    )
+}
+
+fn no_such_process() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "no such mock process")
+}
+
+#[derive(Clone, Default)]
+pub struct MockExecutor {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl MockExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn script(self, path: impl Into<PathBuf>, quanta: u32, outcome: Outcome) -> Self {
+        self.inner.lock().unwrap().scripts.insert(
+            path.into(),
+            Script {
+                steps: vec![Step::Cpu(quanta.max(1))],
+                outcome,
+            },
+        );
+        self
+    }
+
+    pub fn program(self, path: impl Into<PathBuf>, program: &Program, quantum: Duration) -> Self {
+        let mut inner = self.inner.lock().unwrap();
+        inner.programs = true;
+        inner
+            .scripts
+            .insert(path.into(), programmed(program, quantum));
+        drop(inner);
+        self
+    }
+
+    pub fn quantum(self, quantum: Duration) -> Self {
+        self.inner.lock().unwrap().quantum = Some(quantum);
+        self
+    }
+
+    pub fn resident(self, path: impl Into<PathBuf>, kib: u64) -> Self {
+        self.inner.lock().unwrap().resident.insert(path.into(), kib);
+        self
+    }
+
+    pub fn cache_warmth(self, cold_penalty: u32) -> Self {
+        self.inner.lock().unwrap().cold_penalty = Some(cold_penalty.min(99));
+        self
+    }
+
+    pub fn warm_quanta(&self) -> (u64, u64) {
+        let inner = self.inner.lock().unwrap();
+        (inner.warm_quanta, inner.cold_quanta)
+    }
+
+    pub fn spawned(&self) -> Vec<PathBuf> {
+        self.inner.lock().unwrap().spawned.clone()
+    }
+
+    pub fn vanish(&self, path: impl AsRef<Path>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.processes.retain(|_, p| p.path != path.as_ref());
+    }
+
+    pub fn nice(&self, path: impl AsRef<Path>) -> Option<i32> {
+        self.inner.lock().unwrap().nice.get(path.as_ref()).copied()
+    }
+
+    pub fn quanta_used(&self, path: impl AsRef<Path>) -> u32 {
+        self.inner
+            .lock()
+            .unwrap()
+            .processes
+            .values()
+            .filter(|p| p.path == path.as_ref())
+            .map(|p| p.used)
+            .sum()
+    }
+}
+
+impl Executor for MockExecutor {
+    fn spawn(&mut self, _id: Ulid, spec: &Spec) -> io::Result<Pid> {
+        let mut inner = self.inner.lock().unwrap();
+        let burst = match &spec.burst {
+            Some(burst) => Some(
+                burst
+                    .parse::<Program>()
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?,
+            ),
+            None => None,
+        };
+        let script = match (inner.scripts.get(&spec.path_to_binary), burst) {
+            (Some(script), _) => script.clone(),
+            (None, Some(program)) => {
+                let quantum = spec.quantum_ms.map(Duration::from_millis);
+                let quantum = quantum.or(inner.quantum).unwrap_or(DEFAULT_QUANTUM);
+                inner.programs = true;
+                programmed(&program, quantum)
+            }
+            (None, None) => Script {
+                steps: vec![Step::Cpu(1)],
+                outcome: Outcome::Exit(ExitCode::Success),
+            },
+        };
+
+        if script.outcome == Outcome::SpawnError {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "scripted spawn failure",
+            ));
+        }
+
+        inner.next_pid += 1;
+        let pid = Pid::from_raw(inner.next_pid);
+        inner.spawned.push(spec.path_to_binary.clone());
+        inner.processes.insert(
+            pid,
+            Process {
+                path: spec.path_to_binary.clone(),
+                script,
+                running: true,
+                used: 0,
+                progress: 0,
+                step: 0,
+                blocked_until: None,
+                warm: false,
+                exited: false,
+            },
+        );
+
+        Ok(pid)
+    }
+
+    fn poll(&mut self, pid: Pid) -> io::Result<Status> {
+        let mut inner = self.inner.lock().unwrap();
+        let cold_penalty = inner.cold_penalty;
+        let process = inner.processes.get_mut(&pid).ok_or_else(no_such_process)?;
+
+        if process.exited {
+            return Err(no_such_process());
+        }
+        if !process.running {
+            return Ok(Status::Running);
+        }
+
+        let tick = inner.tick;
+        let quanta = match inner.advance(pid, tick) {
+            Next::Cpu(quanta) => quanta,
+            Next::Blocked => return Ok(Status::Running),
+            Next::Deadlock(lock) => {
+                inner.exit(pid);
+                return Err(deadlock(&lock));
+            }
+            Next::Done => 0,
+        };
+        let process = inner.processes.get_mut(&pid).ok_or_else(no_such_process)?;
+        let mut done = quanta == 0;
+        if !done {
+            process.used += 1;
+            let warm = std::mem::take(&mut process.warm);
+            process.progress += match cold_penalty {
+                Some(penalty) if !warm => 100 - penalty,
+                _ => 100,
+            };
+            if process.progress >= quanta.saturating_mul(100) {
+                process.progress = 0;
+                process.step += 1;
+                match inner.advance(pid, tick) {
+                    Next::Done => done = true,
+                    Next::Deadlock(lock) => {
+                        inner.exit(pid);
+                        return Err(deadlock(&lock));
+                    }
+                    Next::Cpu(_) | Next::Blocked => {}
+                }
+            }
+            if cold_penalty.is_some() {
+                if warm {
+                    inner.warm_quanta += 1;
+                } else {
+                    inner.cold_quanta += 1;
+                }
+            }
+        }
+        if !done {
+            return Ok(Status::Running);
+        }
+
+        let outcome = inner
+            .processes
+            .get(&pid)
+            .ok_or_else(no_such_process)?
+            .script
+            .outcome;
+        inner.exit(pid);
+        drop(inner);
+        match outcome {
+            Outcome::Exit(exit_code) => Ok(Status::Terminated(exit_code)),
+            Outcome::PollError(kind) => Err(io::Error::new(kind, "scripted poll failure")),
+            Outcome::SpawnError => unreachable!(),
+            Outcome::Panic => panic!("scripted panic"),
+        }
+    }
+
+    fn stop(&mut self, pid: Pid) -> io::Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .processes
+            .get_mut(&pid)
+            .ok_or_else(no_such_process)?
+            .running = false;
+        Ok(())
+    }
+
+    fn cont(&mut self, pid: Pid) -> io::Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .processes
+            .get_mut(&pid)
+            .ok_or_else(no_such_process)?
+            .running = true;
+        Ok(())
+    }
+
+    fn kill(&mut self, pid: Pid) {
+        self.inner.lock().unwrap().exit(pid);
+    }
+
+    fn cpu_time(&self, _pid: Pid) -> Option<Duration> {
+        None
+    }
+
+    fn process_stats(&self, pid: Pid) -> Option<ProcessStats> {
+        let inner = self.inner.lock().unwrap();
+        let process = inner.processes.get(&pid)?;
+        Some(ProcessStats {
+            state: if process.running { 'R' } else { 'T' },
+            cpu_time: Duration::ZERO,
+            resident_kib: Some(*inner.resident.get(&process.path)?),
+            voluntary_switches: 0,
+            involuntary_switches: 0,
+            processor: None,
+        })
+    }
+
+    fn blocks(&self) -> bool {
+        self.inner.lock().unwrap().programs
+    }
+
+    fn blocked(&self, pid: Pid) -> Option<BlockReason> {
+        let inner = self.inner.lock().unwrap();
+        let blocker = inner.blocker(pid, inner.tick.saturating_sub(1));
+        blocker.map(|(reason, _)| reason)
+    }
+
+    fn lock_holder(&self, pid: Pid) -> Option<Pid> {
+        let inner = self.inner.lock().unwrap();
+        inner.blocker(pid, inner.tick.saturating_sub(1))?.1
+    }
+
+    fn tick(&mut self, quantum: u64) {
+        self.inner.lock().unwrap().tick = quantum;
+    }
+
+    fn tracks_cores(&self) -> bool {
+        self.inner.lock().unwrap().cold_penalty.is_some()
+    }
+
+    fn placed(&mut self, pid: Pid, core: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        let warm = inner.cores.insert(core, pid) == Some(pid);
+        if let Some(process) = inner.processes.get_mut(&pid) {
+            process.warm = warm;
+        }
+    }
+
+    fn set_nice(&mut self, pid: Pid, nice: i32) -> io::Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        let path = inner
+            .processes
+            .get(&pid)
+            .map(|process| process.path.clone())
+            .ok_or_else(no_such_process)?;
+        inner.nice.insert(path, nice);
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use crate::procfs::{ProcessStats, Schedstat};
+use crate::task::{BlockReason, Spec, Status};
+#[cfg(all(unix, feature = "process"))]
+pub use adopted::AdoptedExecutor;
+#[cfg(any(
+    test,
+    feature = "testing",
+    not(all(any(unix, windows), feature = "process"))
+))]
+pub use mock::{MockExecutor, Outcome};
+use std::io;
+use std::time::Duration;
+#[cfg(all(target_os = "linux", feature = "process"))]
+pub use systemd::SystemdExecutor;
+use ulid::Ulid;
+#[cfg(all(unix, feature = "process"))]
+pub use unix::ProcessExecutor;
+#[cfg(all(windows, feature = "process"))]
+pub use windows::ProcessExecutor;
+#[cfg(all(unix, feature = "process"))]
+mod adopted;
+#[cfg(all(any(unix, windows), feature = "process"))]
+mod container;
+#[cfg(any(
+    test,
+    feature = "testing",
+    not(all(any(unix, windows), feature = "process"))
+))]
+mod mock;
+#[cfg(all(target_os = "linux", feature = "process"))]
+mod ptrace;
+#[cfg(all(any(unix, windows), feature = "process"))]
+mod remote;
+#[cfg(all(target_os = "linux", feature = "process"))]
+mod sandbox;
+#[cfg(all(unix, feature = "process"))]
+mod stop;
+#[cfg(all(target_os = "linux", feature = "process"))]
+mod systemd;
+#[cfg(all(unix, feature = "process"))]
+mod unix;
+#[cfg(all(windows, feature = "process"))]
+mod windows;
+
+#[cfg(all(any(unix, windows), feature = "process"))]
+pub type DefaultExecutor = ProcessExecutor;
+#[cfg(not(all(any(unix, windows), feature = "process")))]
+pub type DefaultExecutor = MockExecutor;
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Pid(u32);
+
+impl Pid {
+    pub fn from_raw(pid: u32) -> Self {
+        Self(pid)
+    }
+
+    pub fn as_raw(self) -> u32 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for Pid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+pub trait Executor {
+    fn spawn(&mut self, id: Ulid, spec: &Spec) -> io::Result<Pid>;
+
+    fn poll(&mut self, pid: Pid) -> io::Result<Status>;
+
+    fn stop(&mut self, pid: Pid) -> io::Result<()>;
+
+    fn cont(&mut self, pid: Pid) -> io::Result<()>;
+
+    fn kill(&mut self, pid: Pid);
+
+    fn cpu_time(&self, pid: Pid) -> Option<Duration>;
+
+    fn process_stats(&self, _pid: Pid) -> Option<ProcessStats> {
+        None
+    }
+
+    fn schedstat(&self, _pid: Pid) -> Option<Schedstat> {
+        None
+    }
+
+    fn syscalls(&self, _pid: Pid) -> Option<u64> {
+        None
+    }
+
+    fn blocks(&self) -> bool {
+        false
+    }
+
+    fn blocked(&self, _pid: Pid) -> Option<BlockReason> {
+        None
+    }
+
+    fn lock_holder(&self, _pid: Pid) -> Option<Pid> {
+        None
+    }
+
+    fn tick(&mut self, _quantum: u64) {}
+
+    fn tracks_cores(&self) -> bool {
+        false
+    }
+
+    fn placed(&mut self, _pid: Pid, _core: usize) {}
+
+    fn set_nice(&mut self, _pid: Pid, _nice: i32) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "this executor cannot set nice values",
+        ))
+    }
+}
+
+#[cfg(all(unix, feature = "process"))]
+fn renice(pid: Pid, nice: i32) -> io::Result<()> {
+    let result = unsafe { nix::libc::setpriority(nix::libc::PRIO_PROCESS, pid.as_raw(), nice) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
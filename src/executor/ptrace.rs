@@ -0,0 +1,249 @@
+use super::Pid;
+use nix::errno::Errno;
+use nix::sys::ptrace::{self, Options};
+use nix::sys::signal::Signal;
+use nix::sys::wait::{self, WaitPidFlag, WaitStatus};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+const MIN_IDLE: Duration = Duration::from_micros(50);
+const MAX_IDLE: Duration = Duration::from_millis(10);
+
+fn raw(pid: Pid) -> nix::unistd::Pid {
+    nix::unistd::Pid::from_raw(pid.as_raw() as i32)
+}
+
+enum Request {
+    Spawn(Box<Command>, Sender<io::Result<Pid>>),
+    Resume(Pid),
+}
+
+#[derive(Default)]
+struct State {
+    traced: HashSet<Pid>,
+    totals: HashMap<Pid, u64>,
+    group_stopped: HashSet<Pid>,
+    exits: HashMap<Pid, WaitStatus>,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    fn wait_until(&self, done: impl Fn(&State) -> bool) {
+        drop(self.changed.wait_while(self.lock(), |state| !done(state)));
+    }
+}
+
+// ptrace requests are only accepted from the thread that became the tracer,
+// so one thread spawns every traced task and handles all of their stops.
+struct Worker {
+    shared: Arc<Shared>,
+    entering: HashMap<Pid, bool>,
+}
+
+impl Worker {
+    fn spawn(&mut self, mut command: Command) -> io::Result<Pid> {
+        unsafe {
+            command.pre_exec(|| Ok(ptrace::traceme()?));
+        }
+        let pid = Pid::from_raw(command.spawn()?.id());
+
+        match wait::waitpid(raw(pid), Some(WaitPidFlag::__WALL))? {
+            WaitStatus::Stopped(_, Signal::SIGTRAP) => {}
+            status => {
+                return Err(io::Error::other(format!(
+                    "expected the tracee to stop at exec, got {:?}",
+                    status
+                )))
+            }
+        }
+        ptrace::setoptions(
+            raw(pid),
+            Options::PTRACE_O_TRACESYSGOOD | Options::PTRACE_O_EXITKILL,
+        )?;
+        ptrace::syscall(raw(pid), None)?;
+
+        self.entering.insert(pid, true);
+        let mut state = self.shared.lock();
+        state.traced.insert(pid);
+        state.totals.insert(pid, 0);
+        Ok(pid)
+    }
+
+    fn serve(&mut self, request: Request) {
+        match request {
+            Request::Spawn(command, reply) => {
+                let _ = reply.send(self.spawn(*command));
+            }
+            Request::Resume(pid) => {
+                let _ = ptrace::syscall(raw(pid), None);
+            }
+        }
+    }
+
+    fn handle(&mut self, status: WaitStatus) {
+        let Some(pid) = status.pid().map(|pid| Pid::from_raw(pid.as_raw() as u32)) else {
+            return;
+        };
+
+        match status {
+            WaitStatus::PtraceSyscall(_) => {
+                let entering = self.entering.entry(pid).or_insert(true);
+                if *entering {
+                    *self.shared.lock().totals.entry(pid).or_default() += 1;
+                }
+                *entering = !*entering;
+                let _ = ptrace::syscall(raw(pid), None);
+            }
+            WaitStatus::Stopped(_, signal) => {
+                if ptrace::getsiginfo(raw(pid)).is_ok() {
+                    let _ = ptrace::syscall(raw(pid), signal);
+                } else {
+                    self.shared.lock().group_stopped.insert(pid);
+                    self.shared.changed.notify_all();
+                }
+            }
+            WaitStatus::Exited(..) | WaitStatus::Signaled(..) => {
+                self.entering.remove(&pid);
+                self.shared.lock().exits.insert(pid, status);
+                self.shared.changed.notify_all();
+            }
+            _ => {
+                let _ = ptrace::syscall(raw(pid), None);
+            }
+        }
+    }
+
+    fn run(mut self, requests: Receiver<Request>) {
+        let flags = WaitPidFlag::WNOHANG | WaitPidFlag::__WALL | WaitPidFlag::__WNOTHREAD;
+        let mut idle = MIN_IDLE;
+
+        loop {
+            while let Ok(request) = requests.try_recv() {
+                self.serve(request);
+            }
+
+            match wait::waitpid(None, Some(flags)) {
+                Ok(WaitStatus::StillAlive) => match requests.recv_timeout(idle) {
+                    Ok(request) => self.serve(request),
+                    Err(RecvTimeoutError::Timeout) => {
+                        idle = (idle * 2).min(MAX_IDLE);
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => return,
+                },
+                Ok(status) => self.handle(status),
+                Err(Errno::ECHILD) => match requests.recv() {
+                    Ok(request) => self.serve(request),
+                    Err(_) => return,
+                },
+                Err(_) => {}
+            }
+            idle = MIN_IDLE;
+        }
+    }
+}
+
+pub struct Tracer {
+    requests: Sender<Request>,
+    shared: Arc<Shared>,
+}
+
+impl Default for Tracer {
+    fn default() -> Self {
+        let (requests, received) = mpsc::channel();
+        let shared = Arc::new(Shared::default());
+
+        let worker = Worker {
+            shared: Arc::clone(&shared),
+            entering: HashMap::new(),
+        };
+        thread::spawn(move || worker.run(received));
+
+        Self { requests, shared }
+    }
+}
+
+fn stopped() -> io::Error {
+    io::Error::other("the tracer thread has stopped")
+}
+
+impl Tracer {
+    pub fn spawn(&self, command: Command) -> io::Result<Pid> {
+        let (reply, result) = mpsc::channel();
+        self.requests
+            .send(Request::Spawn(Box::new(command), reply))
+            .map_err(|_| stopped())?;
+        result.recv().map_err(|_| stopped())?
+    }
+
+    pub fn is_traced(&self, pid: Pid) -> bool {
+        self.shared.lock().traced.contains(&pid)
+    }
+
+    pub fn poll(&self, pid: Pid) -> Option<WaitStatus> {
+        let mut state = self.shared.lock();
+        let status = state.exits.remove(&pid)?;
+        state.traced.remove(&pid);
+        state.group_stopped.remove(&pid);
+        Some(status)
+    }
+
+    pub fn has_exited(&self, pid: Pid) -> bool {
+        self.shared.lock().exits.contains_key(&pid)
+    }
+
+    pub fn wait_for_stop(&self, pid: Pid) {
+        self.shared.wait_until(|state| {
+            state.group_stopped.contains(&pid) || state.exits.contains_key(&pid)
+        });
+    }
+
+    pub fn resume(&self, pid: Pid) {
+        if self.shared.lock().group_stopped.remove(&pid) {
+            let _ = self.requests.send(Request::Resume(pid));
+        }
+    }
+
+    pub fn reap(&self, pid: Pid) {
+        self.shared
+            .wait_until(|state| state.exits.contains_key(&pid));
+        self.poll(pid);
+    }
+
+    pub fn syscalls(&self, pid: Pid) -> Option<u64> {
+        self.shared.lock().totals.get(&pid).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_the_syscalls_of_a_traced_child() {
+        let tracer = Tracer::default();
+        let mut command = Command::new("/bin/true");
+        command.stdin(std::process::Stdio::null());
+
+        let pid = tracer.spawn(command).unwrap();
+        tracer.reap(pid);
+
+        assert!(!tracer.is_traced(pid));
+        assert!(tracer.syscalls(pid).unwrap() > 0);
+    }
+}
@@ -0,0 +1,119 @@
+use super::Pid;
+use crate::control::quote_words;
+use crate::task::Spec;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+
+struct Remote {
+    host: String,
+    pid: u32,
+}
+
+#[derive(Default)]
+pub struct Remotes {
+    running: HashMap<Pid, Remote>,
+}
+
+fn ssh(host: &str) -> Command {
+    let mut command = Command::new("ssh");
+    command.args(["-T", "-o", "BatchMode=yes", host]);
+    command
+}
+
+fn remote_command(spec: &Spec) -> String {
+    let mut words = vec![spec.path_to_binary.to_string_lossy().into_owned()];
+    words.extend(spec.arguments());
+    format!("echo $$; exec {}", quote_words(&words))
+}
+
+fn signal(remote: &Remote, name: &str) -> io::Result<()> {
+    let output = ssh(&remote.host)
+        .arg(format!("kill -{} {}", name, remote.pid))
+        .stdin(Stdio::null())
+        .output()?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    Err(io::Error::other(format!(
+        "kill -{} on {} failed: {}",
+        name,
+        remote.host,
+        String::from_utf8_lossy(&output.stderr).trim()
+    )))
+}
+
+impl Remotes {
+    pub fn spawn(&mut self, host: &str, spec: &Spec) -> io::Result<Child> {
+        let mut child = ssh(host)
+            .arg(remote_command(spec))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let mut stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        let mut line = String::new();
+        stdout.read_line(&mut line)?;
+
+        let Ok(pid) = line.trim().parse() else {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::other(format!(
+                "ssh {} did not report a remote process id",
+                host
+            )));
+        };
+
+        thread::spawn(move || {
+            let _ = io::copy(&mut stdout, &mut io::stdout());
+            let _ = io::stdout().flush();
+        });
+
+        self.running.insert(
+            Pid::from_raw(child.id()),
+            Remote {
+                host: host.to_string(),
+                pid,
+            },
+        );
+        Ok(child)
+    }
+
+    pub fn stop(&self, pid: Pid) -> Option<io::Result<()>> {
+        let remote = self.running.get(&pid)?;
+        Some(signal(remote, "STOP"))
+    }
+
+    pub fn cont(&self, pid: Pid) -> Option<io::Result<()>> {
+        let remote = self.running.get(&pid)?;
+        Some(signal(remote, "CONT"))
+    }
+
+    pub fn kill(&mut self, pid: Pid) {
+        if let Some(remote) = self.running.remove(&pid) {
+            let _ = signal(&remote, "KILL");
+        }
+    }
+
+    pub fn exited(&mut self, pid: Pid) {
+        self.running.remove(&pid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_remote_shell_reports_its_pid_before_exec() {
+        let spec = Spec::new("/bin/echo", Some(vec!["Howdy Y'all!".to_string()]), 2);
+
+        assert_eq!(
+            remote_command(&spec),
+            "echo $$; exec /bin/echo 'Howdy Y'\\''all!'"
+        );
+    }
+}
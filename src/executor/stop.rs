@@ -0,0 +1,73 @@
+use super::Pid;
+use crate::procfs;
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const ATTEMPTS: u32 = 5;
+const FIRST_WAIT: Duration = Duration::from_millis(2);
+const POLL: Duration = Duration::from_micros(50);
+
+fn is_stopped(state: char) -> bool {
+    matches!(state, 'T' | 't' | 'Z' | 'X')
+}
+
+fn stops_within(pid: Pid, wait: Duration) -> bool {
+    let deadline = Instant::now() + wait;
+    loop {
+        if procfs::state(pid).is_none_or(is_stopped) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(POLL);
+    }
+}
+
+pub fn confirm(pid: Pid, mut resend: impl FnMut() -> io::Result<()>) -> io::Result<()> {
+    let mut wait = FIRST_WAIT;
+    for attempt in 0..ATTEMPTS {
+        if stops_within(pid, wait) {
+            return Ok(());
+        }
+        wait *= 2;
+        if attempt > 0 {
+            resend()?;
+        }
+    }
+
+    let state = match procfs::state(pid) {
+        Some(state) if !is_stopped(state) => state,
+        _ => return Ok(()),
+    };
+    let reason = match procfs::tracer_pid(pid) {
+        Some(tracer) if tracer != 0 => format!("it is traced by process {}", tracer),
+        _ if state == 'D' => "it is in uninterruptible sleep".to_string(),
+        _ => format!("it is still in state {}", state),
+    };
+    Err(io::Error::other(format!(
+        "process {} did not stop after {} attempts: {}",
+        pid, ATTEMPTS, reason
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nix::sys::signal::{self, Signal};
+    use std::process::Command;
+
+    #[test]
+    fn a_stopped_child_is_confirmed() {
+        let mut child = Command::new("/bin/sleep").arg("5").spawn().unwrap();
+        let pid = nix::unistd::Pid::from_raw(child.id() as i32);
+
+        signal::kill(pid, Signal::SIGSTOP).unwrap();
+        let confirmed = confirm(Pid::from_raw(child.id()), || Ok(()));
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        assert!(confirmed.is_ok());
+    }
+}
@@ -0,0 +1,171 @@
+use super::{Executor, Pid};
+use crate::procfs::{self, ProcessStats, Schedstat};
+use crate::task::{ExitCode, Spec, Status};
+use nix::sys::signal::{self, Signal};
+use nix::sys::wait::{self, WaitPidFlag, WaitStatus};
+use std::collections::HashMap;
+use std::io;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use ulid::Ulid;
+
+#[derive(Default)]
+pub struct SystemdExecutor {
+    units: HashMap<Pid, String>,
+}
+
+impl SystemdExecutor {
+    pub fn connect() -> io::Result<Self> {
+        systemctl("show", "--property=Version")?;
+        Ok(Self::default())
+    }
+
+    fn unit(&self, pid: Pid) -> io::Result<&str> {
+        self.units
+            .get(&pid)
+            .map(String::as_str)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no unit for {}", pid)))
+    }
+}
+
+fn raw(pid: Pid) -> nix::unistd::Pid {
+    nix::unistd::Pid::from_raw(pid.as_raw() as i32)
+}
+
+fn manager() -> Option<&'static str> {
+    (!nix::unistd::geteuid().is_root()).then_some("--user")
+}
+
+fn cpu_weight(priority: u8) -> u32 {
+    (800u32 >> priority.min(9)).max(1)
+}
+
+fn run_args(id: Ulid, spec: &Spec) -> Vec<String> {
+    let mut args: Vec<String> = manager().into_iter().map(str::to_string).collect();
+    args.extend([
+        "--scope".to_string(),
+        "--quiet".to_string(),
+        "--collect".to_string(),
+        format!("--unit=psched-{}", id),
+        format!("--property=CPUWeight={}", cpu_weight(spec.priority)),
+    ]);
+
+    if let Some(memory) = &spec.memory {
+        args.push(format!("--property=MemoryMax={}", memory));
+    }
+    if let Some(user) = &spec.run_as {
+        args.push(format!("--uid={}", user));
+    }
+
+    args.push("--".to_string());
+    args
+}
+
+fn systemctl(action: &str, unit: &str) -> io::Result<()> {
+    let output = Command::new("systemctl")
+        .args(manager())
+        .args([action, unit])
+        .stdin(Stdio::null())
+        .output()?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    Err(io::Error::other(format!(
+        "systemctl {} {} failed: {}",
+        action,
+        unit,
+        String::from_utf8_lossy(&output.stderr).trim()
+    )))
+}
+
+impl Executor for SystemdExecutor {
+    fn spawn(&mut self, id: Ulid, spec: &Spec) -> io::Result<Pid> {
+        if spec.sandbox.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "sandboxed tasks cannot run as systemd scopes",
+            ));
+        }
+
+        let mut command = Command::new("systemd-run");
+        command
+            .args(run_args(id, spec))
+            .arg(&spec.path_to_binary)
+            .stdin(Stdio::null());
+
+        command.args(spec.arguments());
+
+        let child = command.spawn()?;
+        let pid = Pid::from_raw(child.id());
+        self.units.insert(pid, format!("psched-{}.scope", id));
+        Ok(pid)
+    }
+
+    fn poll(&mut self, pid: Pid) -> io::Result<Status> {
+        let status = match wait::waitpid(raw(pid), Some(WaitPidFlag::WNOHANG))? {
+            WaitStatus::StillAlive => return Ok(Status::Running),
+            WaitStatus::Exited(_, 0) => Status::Terminated(ExitCode::Success),
+            _ => Status::Terminated(ExitCode::Failure),
+        };
+
+        self.units.remove(&pid);
+        Ok(status)
+    }
+
+    fn stop(&mut self, pid: Pid) -> io::Result<()> {
+        systemctl("freeze", self.unit(pid)?)
+    }
+
+    fn cont(&mut self, pid: Pid) -> io::Result<()> {
+        systemctl("thaw", self.unit(pid)?)
+    }
+
+    fn kill(&mut self, pid: Pid) {
+        if let Some(unit) = self.units.remove(&pid) {
+            let _ = systemctl("thaw", &unit);
+        }
+        let _ = signal::kill(raw(pid), Signal::SIGKILL);
+        let _ = wait::waitpid(raw(pid), None);
+    }
+
+    fn cpu_time(&self, pid: Pid) -> Option<Duration> {
+        procfs::cpu_time(pid)
+    }
+
+    fn process_stats(&self, pid: Pid) -> Option<ProcessStats> {
+        procfs::process_stats(pid)
+    }
+
+    fn schedstat(&self, pid: Pid) -> Option<Schedstat> {
+        procfs::schedstat(pid)
+    }
+
+    fn set_nice(&mut self, pid: Pid, nice: i32) -> io::Result<()> {
+        super::renice(pid, nice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units_are_named_after_the_task_and_weighted_by_priority() {
+        let id = Ulid::from_string("01M4WRF3N5CKBF6ZCXKFD4NYDF").unwrap();
+        let spec = Spec {
+            memory: Some("256M".to_string()),
+            ..Spec::new("/bin/ls", None, 3)
+        };
+
+        let args = run_args(id, &spec);
+        assert!(args.contains(&"--unit=psched-01M4WRF3N5CKBF6ZCXKFD4NYDF".to_string()));
+        assert!(args.contains(&"--property=CPUWeight=100".to_string()));
+        assert!(args.contains(&"--property=MemoryMax=256M".to_string()));
+        assert_eq!(args.last().map(String::as_str), Some("--"));
+
+        assert_eq!(cpu_weight(0), 800);
+        assert_eq!(cpu_weight(9), 1);
+    }
+}
@@ -0,0 +1,242 @@
+use super::container::Containers;
+#[cfg(target_os = "linux")]
+use super::ptrace::Tracer;
+use super::remote::Remotes;
+#[cfg(target_os = "linux")]
+use super::sandbox;
+use super::stop;
+use super::{Executor, Pid};
+use crate::procfs::{self, ProcessStats, Schedstat};
+use crate::task::{ExitCode, Sandbox, Spec, Status};
+use nix::sys::signal::{self, Signal};
+use nix::sys::wait::{self, WaitPidFlag, WaitStatus};
+use nix::unistd::{self, User};
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::io;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use ulid::Ulid;
+
+#[derive(Default)]
+pub struct ProcessExecutor {
+    containers: Containers,
+    remotes: Remotes,
+    sandboxed: HashMap<Pid, Sandbox>,
+    #[cfg(target_os = "linux")]
+    tracer: Option<Tracer>,
+}
+
+impl ProcessExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn count_syscalls(mut self) -> Self {
+        self.tracer = Some(Tracer::default());
+        self
+    }
+}
+
+fn raw(pid: Pid) -> nix::unistd::Pid {
+    nix::unistd::Pid::from_raw(pid.as_raw() as i32)
+}
+
+fn switch_user(command: &mut Command, name: &str) -> io::Result<()> {
+    let user = User::from_name(name)?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no such user {}", name)))?;
+
+    let euid = unistd::geteuid();
+    if user.uid == euid {
+        return Ok(());
+    }
+    if !euid.is_root() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("running tasks as {} needs root", name),
+        ));
+    }
+
+    let (uid, gid) = (user.uid, user.gid);
+    let name =
+        CString::new(user.name).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    unsafe {
+        command.pre_exec(move || {
+            unistd::initgroups(&name, gid)?;
+            unistd::setgid(gid)?;
+            unistd::setuid(uid)?;
+            Ok(())
+        });
+    }
+    Ok(())
+}
+
+impl ProcessExecutor {
+    fn signal(&self, pid: Pid, signal: Signal) -> nix::Result<()> {
+        if self.sandboxed.contains_key(&pid) {
+            signal::killpg(raw(pid), signal)
+        } else {
+            signal::kill(raw(pid), signal)
+        }
+    }
+
+    fn measured(&self, pid: Pid) -> Pid {
+        #[cfg(target_os = "linux")]
+        if self.sandboxed.get(&pid).is_some_and(|sandbox| sandbox.pid) {
+            return sandbox::init_pid(pid).unwrap_or(pid);
+        }
+        pid
+    }
+
+    #[cfg(target_os = "linux")]
+    fn traced(&self, pid: Pid) -> Option<&Tracer> {
+        self.tracer.as_ref().filter(|tracer| tracer.is_traced(pid))
+    }
+
+    fn wait(&self, pid: Pid) -> io::Result<WaitStatus> {
+        #[cfg(target_os = "linux")]
+        if let Some(tracer) = self.traced(pid) {
+            return Ok(tracer.poll(pid).unwrap_or(WaitStatus::StillAlive));
+        }
+        Ok(wait::waitpid(raw(pid), Some(WaitPidFlag::WNOHANG))?)
+    }
+}
+
+impl Executor for ProcessExecutor {
+    fn spawn(&mut self, id: Ulid, spec: &Spec) -> io::Result<Pid> {
+        if let Some(container) = &spec.container {
+            let child = self.containers.spawn(id, container, spec)?;
+            return Ok(Pid::from_raw(child.id()));
+        }
+        if let Some(host) = &spec.host {
+            let child = self.remotes.spawn(host, spec)?;
+            return Ok(Pid::from_raw(child.id()));
+        }
+
+        let mut command = Command::new(&spec.path_to_binary);
+        command.stdin(Stdio::null());
+
+        command.args(spec.arguments());
+        #[cfg(target_os = "linux")]
+        let traced = self.tracer.is_some() && !spec.sandbox.is_some_and(|sandbox| sandbox.pid);
+        #[cfg(target_os = "linux")]
+        if let Some(sandbox) = spec.sandbox {
+            sandbox::confine(&mut command, sandbox);
+        }
+        #[cfg(not(target_os = "linux"))]
+        if spec.sandbox.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "sandboxed tasks need Linux namespaces",
+            ));
+        }
+        if let Some(user) = &spec.run_as {
+            switch_user(&mut command, user)?;
+        }
+        #[cfg(target_os = "linux")]
+        if spec.sandbox.is_some_and(|sandbox| sandbox.seccomp) {
+            sandbox::restrict(&mut command)?;
+        }
+
+        #[cfg(target_os = "linux")]
+        let pid = match self.tracer.as_ref().filter(|_| traced) {
+            Some(tracer) => tracer.spawn(command)?,
+            None => Pid::from_raw(command.spawn()?.id()),
+        };
+        #[cfg(not(target_os = "linux"))]
+        let pid = Pid::from_raw(command.spawn()?.id());
+        if let Some(sandbox) = spec.sandbox {
+            self.sandboxed.insert(pid, sandbox);
+        }
+        Ok(pid)
+    }
+
+    fn poll(&mut self, pid: Pid) -> io::Result<Status> {
+        let status = match self.wait(pid)? {
+            WaitStatus::StillAlive => return Ok(Status::Running),
+            WaitStatus::Exited(_, 0) => Status::Terminated(ExitCode::Success),
+            _ => Status::Terminated(ExitCode::Failure),
+        };
+
+        self.containers.exited(pid);
+        self.remotes.exited(pid);
+        self.sandboxed.remove(&pid);
+        Ok(status)
+    }
+
+    fn stop(&mut self, pid: Pid) -> io::Result<()> {
+        if let Some(result) = self
+            .containers
+            .pause(pid)
+            .or_else(|| self.remotes.stop(pid))
+        {
+            return result;
+        }
+        self.signal(pid, Signal::SIGSTOP)?;
+        #[cfg(target_os = "linux")]
+        if let Some(tracer) = self.traced(pid) {
+            tracer.wait_for_stop(pid);
+            return Ok(());
+        }
+        stop::confirm(pid, || Ok(self.signal(pid, Signal::SIGSTOP)?))
+    }
+
+    fn cont(&mut self, pid: Pid) -> io::Result<()> {
+        if let Some(result) = self
+            .containers
+            .unpause(pid)
+            .or_else(|| self.remotes.cont(pid))
+        {
+            return result;
+        }
+        #[cfg(target_os = "linux")]
+        if self
+            .traced(pid)
+            .is_some_and(|tracer| tracer.has_exited(pid))
+        {
+            return Ok(());
+        }
+        self.signal(pid, Signal::SIGCONT)?;
+        #[cfg(target_os = "linux")]
+        if let Some(tracer) = self.traced(pid) {
+            tracer.resume(pid);
+        }
+        Ok(())
+    }
+
+    fn kill(&mut self, pid: Pid) {
+        self.containers.remove(pid);
+        self.remotes.kill(pid);
+        let _ = self.signal(pid, Signal::SIGKILL);
+        self.sandboxed.remove(&pid);
+        #[cfg(target_os = "linux")]
+        if let Some(tracer) = self.traced(pid) {
+            tracer.reap(pid);
+            return;
+        }
+        let _ = wait::waitpid(raw(pid), None);
+    }
+
+    fn cpu_time(&self, pid: Pid) -> Option<Duration> {
+        procfs::cpu_time(self.measured(pid))
+    }
+
+    fn process_stats(&self, pid: Pid) -> Option<ProcessStats> {
+        procfs::process_stats(self.measured(pid))
+    }
+
+    fn schedstat(&self, pid: Pid) -> Option<Schedstat> {
+        procfs::schedstat(self.measured(pid))
+    }
+
+    fn set_nice(&mut self, pid: Pid, nice: i32) -> io::Result<()> {
+        super::renice(self.measured(pid), nice)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn syscalls(&self, pid: Pid) -> Option<u64> {
+        self.tracer.as_ref()?.syscalls(pid)
+    }
+}
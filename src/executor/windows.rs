@@ -0,0 +1,193 @@
+use super::container::Containers;
+use super::remote::Remotes;
+use super::{Executor, Pid};
+use crate::task::{ExitCode, Spec, Status};
+use std::collections::HashMap;
+use std::io;
+use std::os::windows::io::AsRawHandle;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+use ulid::Ulid;
+use windows_sys::Win32::Foundation::{CloseHandle, FILETIME, HANDLE, INVALID_HANDLE_VALUE};
+use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+};
+use windows_sys::Win32::System::Threading::{
+    GetProcessTimes, OpenThread, ResumeThread, SuspendThread, THREAD_SUSPEND_RESUME,
+};
+
+#[derive(Default)]
+pub struct ProcessExecutor {
+    children: HashMap<Pid, Child>,
+    containers: Containers,
+    remotes: Remotes,
+}
+
+impl ProcessExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn no_such_process(pid: Pid) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("no child process {}", pid))
+}
+
+fn threads_of(pid: Pid) -> io::Result<Vec<u32>> {
+    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0) };
+    if snapshot == INVALID_HANDLE_VALUE {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut threads = Vec::new();
+    let mut entry = THREADENTRY32 {
+        dwSize: std::mem::size_of::<THREADENTRY32>() as u32,
+        cntUsage: 0,
+        th32ThreadID: 0,
+        th32OwnerProcessID: 0,
+        tpBasePri: 0,
+        tpDeltaPri: 0,
+        dwFlags: 0,
+    };
+
+    let mut found = unsafe { Thread32First(snapshot, &mut entry) } != 0;
+    while found {
+        if entry.th32OwnerProcessID == pid.as_raw() {
+            threads.push(entry.th32ThreadID);
+        }
+        found = unsafe { Thread32Next(snapshot, &mut entry) } != 0;
+    }
+
+    unsafe { CloseHandle(snapshot) };
+    Ok(threads)
+}
+
+fn for_each_thread(pid: Pid, action: unsafe extern "system" fn(HANDLE) -> u32) -> io::Result<()> {
+    for id in threads_of(pid)? {
+        let thread = unsafe { OpenThread(THREAD_SUSPEND_RESUME, 0, id) };
+        if thread.is_null() {
+            continue;
+        }
+
+        let result = unsafe { action(thread) };
+        unsafe { CloseHandle(thread) };
+        if result == u32::MAX {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+fn duration(time: FILETIME) -> Duration {
+    let ticks = (u64::from(time.dwHighDateTime) << 32) | u64::from(time.dwLowDateTime);
+    Duration::from_nanos(ticks * 100)
+}
+
+impl Executor for ProcessExecutor {
+    fn spawn(&mut self, id: Ulid, spec: &Spec) -> io::Result<Pid> {
+        let child = match (&spec.container, &spec.host) {
+            (Some(container), _) => self.containers.spawn(id, container, spec)?,
+            (None, Some(host)) => self.remotes.spawn(host, spec)?,
+            (None, None) if spec.sandbox.is_some() => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "sandboxed tasks need Linux namespaces",
+                ));
+            }
+            (None, None) if spec.run_as.is_some() => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "running tasks as another user is not supported on Windows",
+                ));
+            }
+            (None, None) => {
+                let mut command = Command::new(&spec.path_to_binary);
+                command.stdin(Stdio::null());
+
+                command.args(spec.arguments());
+
+                command.spawn()?
+            }
+        };
+        let pid = Pid::from_raw(child.id());
+        self.children.insert(pid, child);
+        Ok(pid)
+    }
+
+    fn poll(&mut self, pid: Pid) -> io::Result<Status> {
+        let child = self
+            .children
+            .get_mut(&pid)
+            .ok_or_else(|| no_such_process(pid))?;
+
+        match child.try_wait()? {
+            None => Ok(Status::Running),
+            Some(status) => {
+                self.children.remove(&pid);
+                self.containers.exited(pid);
+                self.remotes.exited(pid);
+                if status.success() {
+                    Ok(Status::Terminated(ExitCode::Success))
+                } else {
+                    Ok(Status::Terminated(ExitCode::Failure))
+                }
+            }
+        }
+    }
+
+    fn stop(&mut self, pid: Pid) -> io::Result<()> {
+        if let Some(result) = self
+            .containers
+            .pause(pid)
+            .or_else(|| self.remotes.stop(pid))
+        {
+            return result;
+        }
+        for_each_thread(pid, SuspendThread)
+    }
+
+    fn cont(&mut self, pid: Pid) -> io::Result<()> {
+        if let Some(result) = self
+            .containers
+            .unpause(pid)
+            .or_else(|| self.remotes.cont(pid))
+        {
+            return result;
+        }
+        for_each_thread(pid, ResumeThread)
+    }
+
+    fn kill(&mut self, pid: Pid) {
+        self.containers.remove(pid);
+        self.remotes.kill(pid);
+        if let Some(mut child) = self.children.remove(&pid) {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    fn cpu_time(&self, pid: Pid) -> Option<Duration> {
+        let child = self.children.get(&pid)?;
+        let empty = FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        };
+        let (mut created, mut exited, mut kernel, mut user) = (empty, empty, empty, empty);
+
+        let ok = unsafe {
+            GetProcessTimes(
+                child.as_raw_handle() as HANDLE,
+                &mut created,
+                &mut exited,
+                &mut kernel,
+                &mut user,
+            )
+        };
+        if ok == 0 {
+            return None;
+        }
+
+        Some(duration(kernel) + duration(user))
+    }
+}
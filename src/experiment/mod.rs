@@ -0,0 +1,175 @@
+use crate::clock::{SystemTime, UNIX_EPOCH};
+use crate::summary::RunSummary;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_ROOT: &str = "experiments";
+const INDEX: &str = "index.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub experiment: String,
+    pub directory: PathBuf,
+    pub started: String,
+    pub command: String,
+    pub artifacts: Vec<PathBuf>,
+    pub tasks: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub quanta: u64,
+    pub mean_wait_secs: f64,
+}
+
+pub struct Experiment {
+    name: String,
+    root: PathBuf,
+    directory: PathBuf,
+    started: String,
+    command: String,
+    artifacts: Vec<PathBuf>,
+}
+
+fn civil(secs: u64) -> (u64, u64, u64, u64, u64, u64) {
+    let (days, time) = (secs / 86_400, secs % 86_400);
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day, time / 3600, time / 60 % 60, time % 60)
+}
+
+fn validate(name: &str) -> io::Result<()> {
+    let valid = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\'])
+        && name != INDEX;
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid experiment name: {:?}", name),
+        ))
+    }
+}
+
+pub fn load_index(root: &Path) -> io::Result<Vec<Entry>> {
+    match fs::read_to_string(root.join(INDEX)) {
+        Ok(text) => serde_json::from_str(&text).map_err(io::Error::from),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+impl Experiment {
+    pub fn start(root: &Path, name: &str, command: String) -> io::Result<Self> {
+        validate(name)?;
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        let (year, month, day, hour, minute, second) = civil(secs);
+        let stamp = format!(
+            "{:04}{:02}{:02}-{:02}{:02}{:02}",
+            year, month, day, hour, minute, second
+        );
+
+        let parent = root.join(name);
+        fs::create_dir_all(&parent)?;
+        let mut directory = parent.join(&stamp);
+        let mut attempt = 1;
+        loop {
+            match fs::create_dir(&directory) {
+                Ok(()) => break,
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                    attempt += 1;
+                    directory = parent.join(format!("{}-{}", stamp, attempt));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            root: root.to_path_buf(),
+            directory,
+            started: format!(
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                year, month, day, hour, minute, second
+            ),
+            command,
+            artifacts: Vec::new(),
+        })
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn artifact(&mut self, explicit: Option<PathBuf>, file: &str) -> PathBuf {
+        let path = explicit.unwrap_or_else(|| self.directory.join(file));
+        self.artifacts.push(path.clone());
+        path
+    }
+
+    pub fn finish(&self, summary: &RunSummary) -> io::Result<PathBuf> {
+        let mut index = load_index(&self.root)?;
+        index.push(Entry {
+            experiment: self.name.clone(),
+            directory: self.directory.clone(),
+            started: self.started.clone(),
+            command: self.command.clone(),
+            artifacts: self.artifacts.clone(),
+            tasks: summary.iter().count(),
+            succeeded: summary.succeeded(),
+            failed: summary.failed() + summary.poisoned(),
+            quanta: summary.quanta,
+            mean_wait_secs: summary.mean_wait().as_secs_f64(),
+        });
+        let path = self.root.join(INDEX);
+        fs::write(&path, serde_json::to_string_pretty(&index)?)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{ExitCode, State, Task};
+    use std::time::Duration;
+
+    #[test]
+    fn each_run_gets_its_own_directory_and_an_index_entry() {
+        let root = std::env::temp_dir().join(format!("psched-{}", ulid::Ulid::new()));
+        let mut task = Task::new("/bin/true", None, 1);
+        task.set_state(State::Terminated);
+        task.exit_code = Some(ExitCode::Success);
+        let summary = RunSummary::new([task].iter(), 4, Duration::from_millis(100), false);
+
+        let mut first = Experiment::start(&root, "rr-vs-fifo", "psched".into()).unwrap();
+        let report = first.artifact(None, "report.json");
+        let second = Experiment::start(&root, "rr-vs-fifo", "psched".into()).unwrap();
+        assert_ne!(first.directory(), second.directory());
+        assert_eq!(report, first.directory().join("report.json"));
+        first.finish(&summary).unwrap();
+        second.finish(&summary).unwrap();
+
+        let index = load_index(&root).unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index[0].artifacts, [report]);
+        assert_eq!(
+            (index[1].tasks, index[1].succeeded, index[1].quanta),
+            (1, 1, 4)
+        );
+        assert!(Experiment::start(&root, "../escape", String::new()).is_err());
+        assert_eq!(civil(1_791_978_292), (2026, 10, 14, 11, 44, 52));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,259 @@
+use crate::clock::ManualClock;
+use crate::executor::{MockExecutor, Outcome};
+use crate::simulator::Simulator;
+use crate::task::{ExitCode, Task};
+use crate::trace::Kind;
+use std::collections::HashMap;
+use std::ffi::{c_char, c_int, CStr};
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use ulid::Ulid;
+
+pub const PSCHED_ADMITTED: u32 = 0;
+pub const PSCHED_DISPATCHED: u32 = 1;
+pub const PSCHED_PREEMPTED: u32 = 2;
+pub const PSCHED_TERMINATED: u32 = 3;
+pub const PSCHED_SUSPENDED: u32 = 4;
+pub const PSCHED_RESUMED: u32 = 5;
+pub const PSCHED_THROTTLED: u32 = 6;
+pub const PSCHED_UNTHROTTLED: u32 = 7;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct PschedEvent {
+    pub quantum: u64,
+    pub task: u64,
+    pub kind: u32,
+    pub backfilled: u8,
+    pub exit_code: i32,
+}
+
+pub struct Psched {
+    simulator: Simulator,
+    executor: Option<MockExecutor>,
+    handles: HashMap<Ulid, u64>,
+    next_event: usize,
+}
+
+impl Psched {
+    fn event(&self, index: usize) -> Option<PschedEvent> {
+        let event = self.simulator.events().get(index)?;
+        let mut out = PschedEvent {
+            quantum: event.quantum,
+            task: self.handles.get(&event.task).copied().unwrap_or(u64::MAX),
+            exit_code: -1,
+            ..PschedEvent::default()
+        };
+
+        match event.kind {
+            Kind::Admitted => out.kind = PSCHED_ADMITTED,
+            Kind::Dispatched { backfilled } => {
+                out.kind = PSCHED_DISPATCHED;
+                out.backfilled = u8::from(backfilled);
+            }
+            Kind::Preempted => out.kind = PSCHED_PREEMPTED,
+            Kind::Suspended => out.kind = PSCHED_SUSPENDED,
+            Kind::Resumed => out.kind = PSCHED_RESUMED,
+            Kind::Throttled => out.kind = PSCHED_THROTTLED,
+            Kind::Unthrottled => out.kind = PSCHED_UNTHROTTLED,
+            Kind::Terminated { exit_code } => {
+                out.kind = PSCHED_TERMINATED;
+                out.exit_code = match exit_code {
+                    Some(ExitCode::Success) => 0,
+                    Some(ExitCode::Failure) => 1,
+                    Some(ExitCode::Poisoned) => 2,
+                    None => -1,
+                };
+            }
+        }
+
+        Some(out)
+    }
+}
+
+// A panic must not unwind into C, so every entry point returns `failed` instead.
+fn guarded<T>(failed: T, body: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(failed)
+}
+
+unsafe fn path_arg(path: *const c_char) -> Option<String> {
+    if path.is_null() {
+        return None;
+    }
+    CStr::from_ptr(path).to_str().ok().map(str::to_string)
+}
+
+#[no_mangle]
+pub extern "C" fn psched_create(concurrency: usize, simulated: c_int) -> *mut Psched {
+    guarded(std::ptr::null_mut(), || {
+        let Some(concurrency) = NonZeroUsize::new(concurrency) else {
+            return std::ptr::null_mut();
+        };
+
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(Vec::new(), control_rx)
+            .concurrency(concurrency)
+            .record_trace();
+
+        let executor = (simulated != 0).then(MockExecutor::new);
+        if let Some(executor) = &executor {
+            simulator = simulator
+                .executor(executor.clone())
+                .clock(ManualClock::new());
+        }
+
+        Box::into_raw(Box::new(Psched {
+            simulator,
+            executor,
+            handles: HashMap::new(),
+            next_event: 0,
+        }))
+    })
+}
+
+/// # Safety
+///
+/// `psched` must come from `psched_create` and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn psched_destroy(psched: *mut Psched) {
+    if !psched.is_null() {
+        guarded((), || drop(Box::from_raw(psched)));
+    }
+}
+
+/// # Safety
+///
+/// `psched` must come from `psched_create` and `path` must be a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn psched_script(
+    psched: *mut Psched,
+    path: *const c_char,
+    quanta: u32,
+    exit_code: c_int,
+) -> c_int {
+    guarded(-1, || {
+        let (Some(psched), Some(path)) = (psched.as_mut(), path_arg(path)) else {
+            return -1;
+        };
+        let Some(executor) = psched.executor.take() else {
+            return -1;
+        };
+
+        let exit_code = match exit_code {
+            0 => ExitCode::Success,
+            _ => ExitCode::Failure,
+        };
+        psched.executor = Some(executor.script(path, quanta, Outcome::Exit(exit_code)));
+        0
+    })
+}
+
+/// # Safety
+///
+/// `psched` must come from `psched_create` and `path` must be a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn psched_add_task(
+    psched: *mut Psched,
+    path: *const c_char,
+    priority: u8,
+) -> i64 {
+    guarded(-1, || {
+        let (Some(psched), Some(path)) = (psched.as_mut(), path_arg(path)) else {
+            return -1;
+        };
+
+        let handle = psched.handles.len() as u64;
+        let id = psched.simulator.add_task(Task::new(path, None, priority));
+        psched.handles.insert(id, handle);
+        handle as i64
+    })
+}
+
+/// # Safety
+///
+/// `psched` must come from `psched_create`.
+#[no_mangle]
+pub unsafe extern "C" fn psched_step(psched: *mut Psched) -> c_int {
+    guarded(-1, || match psched.as_mut() {
+        Some(psched) => c_int::from(psched.simulator.step()),
+        None => -1,
+    })
+}
+
+/// # Safety
+///
+/// `psched` must come from `psched_create` and `event` must point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn psched_poll_event(psched: *mut Psched, event: *mut PschedEvent) -> c_int {
+    guarded(-1, || {
+        let (Some(psched), Some(event)) = (psched.as_mut(), event.as_mut()) else {
+            return -1;
+        };
+
+        match psched.event(psched.next_event) {
+            Some(next) => {
+                *event = next;
+                psched.next_event += 1;
+                1
+            }
+            None => 0,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_simulated_run_reports_its_events_in_order() {
+        let psched = psched_create(1, 1);
+        let mut events = Vec::new();
+
+        unsafe {
+            assert_eq!(psched_script(psched, c"slow".as_ptr(), 2, 0), 0);
+            assert_eq!(psched_script(psched, c"bad".as_ptr(), 1, 1), 0);
+            assert_eq!(psched_add_task(psched, c"slow".as_ptr(), 2), 0);
+            assert_eq!(psched_add_task(psched, c"bad".as_ptr(), 1), 1);
+
+            while psched_step(psched) == 1 {}
+
+            let mut event = PschedEvent::default();
+            while psched_poll_event(psched, &mut event) == 1 {
+                events.push((event.task, event.kind, event.exit_code));
+            }
+            psched_destroy(psched);
+        }
+
+        assert_eq!(
+            events,
+            vec![
+                (0, PSCHED_ADMITTED, -1),
+                (1, PSCHED_ADMITTED, -1),
+                (1, PSCHED_DISPATCHED, -1),
+                (1, PSCHED_TERMINATED, 1),
+                (0, PSCHED_DISPATCHED, -1),
+                (0, PSCHED_PREEMPTED, -1),
+                (0, PSCHED_DISPATCHED, -1),
+                (0, PSCHED_TERMINATED, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn null_handles_are_rejected() {
+        unsafe {
+            assert_eq!(psched_step(std::ptr::null_mut()), -1);
+            assert_eq!(psched_add_task(std::ptr::null_mut(), c"ls".as_ptr(), 1), -1);
+        }
+        assert!(psched_create(0, 1).is_null());
+    }
+
+    #[test]
+    fn a_panic_is_returned_as_a_failure_instead_of_unwinding() {
+        assert_eq!(guarded(-1, || panic!("scripted panic")), -1);
+        assert!(guarded(std::ptr::null_mut::<Psched>(), || panic!("scripted panic")).is_null());
+        assert_eq!(guarded(-1, || 1), 1);
+    }
+}
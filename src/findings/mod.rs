@@ -0,0 +1,336 @@
+use crate::registry::Registry;
+use crate::task::Task;
+use crate::trace::{Event, Kind};
+use crate::window;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+use ulid::Ulid;
+
+const CONVOY_RUN: u64 = 5;
+const CONVOY_WAITERS: usize = 2;
+const MIN_MIGRATIONS: usize = 3;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Pathology {
+    ConvoyEffect,
+    Starvation,
+    PriorityInversion,
+    ExcessiveMigrations,
+}
+
+impl std::fmt::Display for Pathology {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Pathology::ConvoyEffect => "convoy effect",
+            Pathology::Starvation => "starvation",
+            Pathology::PriorityInversion => "priority inversion",
+            Pathology::ExcessiveMigrations => "excessive migrations",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Finding {
+    pub pathology: Pathology,
+    pub quanta: RangeInclusive<u64>,
+    pub tasks: Vec<Ulid>,
+    pub detail: String,
+}
+
+#[derive(Default)]
+struct Quantum {
+    dispatched: Vec<(Ulid, bool)>,
+    waiting: Vec<Ulid>,
+}
+
+fn timeline(events: &[Event]) -> Vec<Quantum> {
+    let last = events.last().map_or(0, |event| event.quantum);
+    let mut quanta: Vec<Quantum> = (0..=last).map(|_| Quantum::default()).collect();
+    let mut ready = BTreeSet::new();
+
+    let mut events = events.iter().peekable();
+    for (number, quantum) in quanta.iter_mut().enumerate() {
+        let mut now = Vec::new();
+        while let Some(event) = events.next_if(|event| event.quantum == number as u64) {
+            now.push(event);
+        }
+
+        for event in &now {
+            if let Kind::Dispatched { backfilled } = event.kind {
+                quantum.dispatched.push((event.task, backfilled));
+            }
+        }
+        quantum.waiting = ready
+            .iter()
+            .copied()
+            .filter(|id| !quantum.dispatched.iter().any(|(running, _)| running == id))
+            .collect();
+
+        for event in now {
+            match event.kind {
+                Kind::Admitted | Kind::Preempted | Kind::Resumed | Kind::Unthrottled => {
+                    ready.insert(event.task)
+                }
+                Kind::Dispatched { .. }
+                | Kind::Suspended
+                | Kind::Throttled
+                | Kind::Terminated { .. } => ready.remove(&event.task),
+            };
+        }
+    }
+    quanta
+}
+
+fn stretches(quanta: impl Iterator<Item = u64>) -> Vec<RangeInclusive<u64>> {
+    let mut stretches: Vec<RangeInclusive<u64>> = Vec::new();
+    for quantum in quanta {
+        match stretches.last_mut() {
+            Some(last) if *last.end() + 1 == quantum => *last = *last.start()..=quantum,
+            _ => stretches.push(quantum..=quantum),
+        }
+    }
+    stretches
+}
+
+fn length(range: &RangeInclusive<u64>) -> u64 {
+    range.end() - range.start() + 1
+}
+
+fn name(tasks: &Registry, id: Ulid) -> String {
+    tasks
+        .iter()
+        .find(|task| task.get_id() == id)
+        .map_or(id.to_string(), Task::name)
+}
+
+pub fn analyze(
+    events: &[Event],
+    tasks: &Registry,
+    quantum: Duration,
+    starvation_threshold: Duration,
+) -> Vec<Finding> {
+    let quanta = timeline(events);
+    let mut ran: BTreeMap<Ulid, Vec<u64>> = BTreeMap::new();
+    let mut waited: BTreeMap<Ulid, Vec<u64>> = BTreeMap::new();
+    for (number, q) in quanta.iter().enumerate() {
+        for &(id, _) in &q.dispatched {
+            ran.entry(id).or_default().push(number as u64);
+        }
+        for &id in &q.waiting {
+            waited.entry(id).or_default().push(number as u64);
+        }
+    }
+    let burst = |id: Ulid| ran.get(&id).map_or(0, Vec::len) as u64;
+    let priority = |id: Ulid| {
+        tasks
+            .iter()
+            .find(|task| task.get_id() == id)
+            .map(|task| task.priority)
+    };
+    let mut findings = Vec::new();
+
+    for (&id, quanta) in &ran {
+        for run in stretches(quanta.iter().copied()) {
+            if length(&run) < CONVOY_RUN {
+                continue;
+            }
+            let waiters: Vec<Ulid> = waited
+                .iter()
+                .filter(|(&other, waits)| {
+                    burst(other) < length(&run)
+                        && waits.iter().filter(|q| run.contains(q)).count() as u64 >= CONVOY_RUN
+                })
+                .map(|(&other, _)| other)
+                .collect();
+            if waiters.len() < CONVOY_WAITERS {
+                continue;
+            }
+            findings.push(Finding {
+                pathology: Pathology::ConvoyEffect,
+                detail: format!(
+                    "{} ran for {} quanta while {} shorter tasks waited",
+                    name(tasks, id),
+                    length(&run),
+                    waiters.len()
+                ),
+                quanta: run,
+                tasks: std::iter::once(id).chain(waiters).collect(),
+            });
+        }
+    }
+
+    let starving = (starvation_threshold.as_nanos() / quantum.as_nanos().max(1)) as u64 + 1;
+    for (&id, quanta) in &waited {
+        for wait in stretches(quanta.iter().copied()) {
+            if length(&wait) < starving {
+                continue;
+            }
+            findings.push(Finding {
+                pathology: Pathology::Starvation,
+                detail: format!(
+                    "{} waited {} quanta without CPU",
+                    name(tasks, id),
+                    length(&wait)
+                ),
+                quanta: wait,
+                tasks: vec![id],
+            });
+        }
+    }
+
+    let mut passed_over: BTreeMap<Ulid, Vec<(u64, Vec<Ulid>)>> = BTreeMap::new();
+    for (number, q) in quanta.iter().enumerate() {
+        for &id in &q.waiting {
+            let Some(waiting) = priority(id) else {
+                continue;
+            };
+            let lower: Vec<Ulid> = q
+                .dispatched
+                .iter()
+                .filter(|&&(other, backfilled)| {
+                    !backfilled && priority(other).is_some_and(|p| p > waiting)
+                })
+                .map(|&(other, _)| other)
+                .collect();
+            if !lower.is_empty() {
+                passed_over
+                    .entry(id)
+                    .or_default()
+                    .push((number as u64, lower));
+            }
+        }
+    }
+    for (id, quanta) in passed_over {
+        for stretch in stretches(quanta.iter().map(|(q, _)| *q)) {
+            let mut ahead = BTreeSet::new();
+            for (_, lower) in quanta.iter().filter(|(q, _)| stretch.contains(q)) {
+                ahead.extend(lower.iter().copied());
+            }
+            findings.push(Finding {
+                pathology: Pathology::PriorityInversion,
+                detail: format!(
+                    "{} (priority {}) waited while lower-priority {} ran",
+                    name(tasks, id),
+                    priority(id).unwrap_or_default(),
+                    ahead
+                        .iter()
+                        .map(|&other| name(tasks, other))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                quanta: stretch,
+                tasks: std::iter::once(id).chain(ahead).collect(),
+            });
+        }
+    }
+
+    for task in tasks.iter() {
+        let migrations = task.processors.len().saturating_sub(1);
+        let quanta = burst(task.get_id());
+        if migrations < MIN_MIGRATIONS || migrations as u64 * 2 <= quanta {
+            continue;
+        }
+        findings.push(Finding {
+            pathology: Pathology::ExcessiveMigrations,
+            detail: format!(
+                "{} moved between CPUs {} times in {} quanta",
+                task.name(),
+                migrations,
+                quanta
+            ),
+            quanta: task.processors[1].0..=task.processors[migrations].0,
+            tasks: vec![task.get_id()],
+        });
+    }
+
+    findings.sort_by_key(|finding| {
+        (
+            finding.pathology,
+            *finding.quanta.start(),
+            *finding.quanta.end(),
+        )
+    });
+    findings
+}
+
+pub fn print_report(findings: &[Finding], tasks: &Registry) {
+    crate::report!("------------------------------------------\nFINDINGS");
+    if findings.is_empty() {
+        crate::report!("No known pathologies were found");
+    }
+    for finding in findings {
+        crate::report!(
+            "Finding:        {}, {}\n\
+             Detail:         {}",
+            finding.pathology,
+            window::describe(&finding.quanta),
+            finding.detail
+        );
+        for &id in &finding.tasks {
+            crate::report!("PID:            {} ({})", id, name(tasks, id));
+        }
+    }
+    crate::report!("------------------------------------------");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::Trace;
+
+    #[test]
+    fn finds_convoys_starvation_and_priority_inversion() {
+        let mut tasks = Registry::default();
+        let long = tasks.insert(Task::new("long", None, 3));
+        let short = tasks.insert(Task::new("short", None, 2));
+        let tiny = tasks.insert(Task::new("tiny", None, 4));
+
+        let mut trace = Trace::default();
+        for id in [long, short, tiny] {
+            trace.record(0, id, Kind::Admitted);
+        }
+        for quantum in 1..=6 {
+            trace.record(quantum, long, Kind::Dispatched { backfilled: false });
+            trace.record(quantum, long, Kind::Preempted);
+        }
+        trace.record(7, long, Kind::Dispatched { backfilled: false });
+        trace.record(7, long, Kind::Terminated { exit_code: None });
+        trace.record(8, short, Kind::Dispatched { backfilled: false });
+        trace.record(8, short, Kind::Terminated { exit_code: None });
+        trace.record(9, tiny, Kind::Dispatched { backfilled: false });
+        trace.record(9, tiny, Kind::Terminated { exit_code: None });
+
+        let findings = analyze(
+            trace.events(),
+            &tasks,
+            Duration::from_millis(100),
+            Duration::from_millis(650),
+        );
+        let sorted = |mut ids: Vec<Ulid>| {
+            ids.sort();
+            ids
+        };
+        let found: Vec<_> = findings
+            .iter()
+            .map(|f| (f.pathology, f.quanta.clone(), sorted(f.tasks.clone())))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    Pathology::ConvoyEffect,
+                    1..=7,
+                    sorted(vec![long, short, tiny])
+                ),
+                (Pathology::Starvation, 1..=7, vec![short]),
+                (Pathology::Starvation, 1..=8, vec![tiny]),
+                (
+                    Pathology::PriorityInversion,
+                    1..=7,
+                    sorted(vec![short, long])
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use crate::registry::Registry;
+use crate::task::{State, Task};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use ulid::Ulid;
+
+fn is_complete(unit: &[Ulid], tasks: &Registry) -> bool {
+    let Some(gang) = tasks[unit[0]].get_spec().gang.as_deref() else {
+        return true;
+    };
+
+    let unfinished = tasks
+        .iter()
+        .filter(|t| t.state != State::Terminated && t.get_spec().gang.as_deref() == Some(gang))
+        .count();
+
+    unfinished == unit.len()
+}
+
+pub fn units(ranked: &[Ulid], tasks: &Registry) -> Vec<Vec<Ulid>> {
+    let mut units: Vec<Vec<Ulid>> = Vec::new();
+    let mut gangs: HashMap<&str, usize> = HashMap::new();
+
+    for &id in ranked {
+        match tasks[id].get_spec().gang.as_deref() {
+            None => units.push(vec![id]),
+            Some(gang) => match gangs.get(gang) {
+                Some(&unit) => units[unit].push(id),
+                None => {
+                    gangs.insert(gang, units.len());
+                    units.push(vec![id]);
+                }
+            },
+        }
+    }
+
+    units.retain(|unit| is_complete(unit, tasks));
+    units
+}
+
+pub fn width(unit: &[Ulid], tasks: &Registry) -> usize {
+    unit.iter().map(|&id| tasks[id].get_spec().width).sum()
+}
+
+pub fn gang_width(gang: &str, tasks: &Registry) -> usize {
+    tasks
+        .iter()
+        .filter(|t| t.state != State::Terminated && t.get_spec().gang.as_deref() == Some(gang))
+        .map(|t| t.get_spec().width)
+        .sum()
+}
+
+#[derive(Default)]
+struct Gang {
+    members: usize,
+    first_starts: Vec<u64>,
+    finishes: Vec<u64>,
+    quanta: u64,
+    last_quantum: Option<u64>,
+}
+
+#[derive(Default)]
+pub struct GangTracker {
+    gangs: BTreeMap<String, Gang>,
+    idle_slot_quanta: u64,
+    total_slot_quanta: u64,
+}
+
+impl GangTracker {
+    pub fn admitted(&mut self, task: &Task) {
+        if let Some(gang) = &task.get_spec().gang {
+            self.gangs.entry(gang.clone()).or_default().members += 1;
+        }
+    }
+
+    pub fn dispatched(&mut self, quantum: u64, task: &Task) {
+        let Some(gang) = task.get_spec().gang.as_ref() else {
+            return;
+        };
+        let gang = self.gangs.entry(gang.clone()).or_default();
+
+        if !task.has_started() {
+            gang.first_starts.push(quantum);
+        }
+        if gang.last_quantum != Some(quantum) {
+            gang.last_quantum = Some(quantum);
+            gang.quanta += 1;
+        }
+    }
+
+    pub fn finished(&mut self, quantum: u64, task: &Task) {
+        if let Some(gang) = &task.get_spec().gang {
+            self.gangs
+                .entry(gang.clone())
+                .or_default()
+                .finishes
+                .push(quantum);
+        }
+    }
+
+    pub fn record_utilisation(&mut self, used: usize, slots: usize, waiting: bool) {
+        self.total_slot_quanta += slots as u64;
+        if waiting {
+            self.idle_slot_quanta += slots.saturating_sub(used) as u64;
+        }
+    }
+
+    pub fn print_report(&self, quantum: Duration) {
+        if self.gangs.is_empty() {
+            return;
+        }
+
+        let spread = |quanta: &[u64]| match (quanta.iter().min(), quanta.iter().max()) {
+            (Some(min), Some(max)) => (quantum * (max - min) as u32).as_secs_f64(),
+            _ => 0.0,
+        };
+
+        crate::report!(
+            "------------------------------------------\n\
+             GANG REPORT"
+        );
+
+        for (name, gang) in &self.gangs {
+            crate::report!(
+                "Gang:           {}\n\
+                 Members:        {}\n\
+                 Quanta Run:     {}\n\
+                 Start Skew:     {} seconds\n\
+                 Finish Skew:    {} seconds",
+                name,
+                gang.members,
+                gang.quanta,
+                spread(&gang.first_starts),
+                spread(&gang.finishes)
+            );
+        }
+
+        let fragmentation = if self.total_slot_quanta == 0 {
+            0.0
+        } else {
+            self.idle_slot_quanta as f64 * 100.0 / self.total_slot_quanta as f64
+        };
+
+        crate::report!(
+            "Fragmentation:  {} of {} slot-quanta idle while tasks waited ({:.1}%)\n\
+             ------------------------------------------",
+            self.idle_slot_quanta,
+            self.total_slot_quanta,
+            fragmentation
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use crate::registry::Registry;
+use crate::task::{ExitCode, State, Task};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use ulid::Ulid;
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", escape(text))
+}
+
+fn outcome(task: &Task) -> (&'static str, &'static str) {
+    match (task.state, task.exit_code) {
+        (State::Terminated, Some(ExitCode::Success)) => ("SUCCESS", "palegreen"),
+        (State::Terminated, _) => ("FAILURE", "salmon"),
+        _ => ("UNFINISHED", "khaki"),
+    }
+}
+
+fn node(dot: &mut String, indent: &str, task: &Task) {
+    let (outcome, color) = outcome(task);
+    let _ = writeln!(
+        dot,
+        "{0}\"{1}\" [label=\"{2}\\n{1}\\n{3}\", fillcolor={4}];",
+        indent,
+        task.get_id(),
+        escape(&task.name()),
+        outcome,
+        color
+    );
+}
+
+pub fn dot(tasks: &Registry, holders: &HashMap<Ulid, Ulid>) -> String {
+    let mut dot = String::from("digraph tasks {\n    node [shape=box, style=filled];\n");
+
+    let mut gangs: BTreeMap<&str, Vec<&Task>> = BTreeMap::new();
+    for task in tasks.iter() {
+        match task.get_spec().gang.as_deref() {
+            Some(gang) => gangs.entry(gang).or_default().push(task),
+            None => node(&mut dot, "    ", task),
+        }
+    }
+    for (gang, members) in gangs {
+        let _ = writeln!(
+            dot,
+            "    subgraph {} {{\n        label={};",
+            quote(&format!("cluster_{}", gang)),
+            quote(&format!("gang {}", gang))
+        );
+        for task in members {
+            node(&mut dot, "        ", task);
+        }
+        dot.push_str("    }\n");
+    }
+
+    for task in tasks.iter() {
+        let after = &task.get_spec().after;
+        for blocker in tasks.iter() {
+            if blocker.get_id() != task.get_id()
+                && after.iter().any(|reference| blocker.is_called(reference))
+            {
+                let _ = writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\";",
+                    blocker.get_id(),
+                    task.get_id()
+                );
+            }
+        }
+    }
+    let mut waits: Vec<(&Ulid, &Ulid)> = holders.iter().collect();
+    waits.sort();
+    for (waiter, holder) in waits {
+        let _ = writeln!(
+            dot,
+            "    \"{}\" -> \"{}\" [style=dashed, color=red, label=\"waits for\"];",
+            waiter, holder
+        );
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+pub fn save(tasks: &Registry, holders: &HashMap<Ulid, Ulid>, path: &Path) -> io::Result<()> {
+    fs::write(path, dot(tasks, holders))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Spec;
+
+    #[test]
+    fn gang_members_share_a_cluster_and_nodes_are_colored_by_outcome() {
+        let mut tasks = Registry::default();
+        let mut done = Task::new("/bin/echo", Some(vec!["say \"hi\"".to_string()]), 1);
+        done.set_state(State::Terminated);
+        done.exit_code = Some(ExitCode::Success);
+        let done = tasks.insert(done);
+        let member = tasks.insert(Task::from_spec(Spec {
+            name: Some("frontend".to_string()),
+            gang: Some("web".to_string()),
+            ..Spec::new("/bin/serve", None, 2)
+        }));
+
+        let dot = dot(&tasks, &HashMap::new());
+        assert!(dot.contains(&format!(
+            "    \"{0}\" [label=\"echo\\n{0}\\nSUCCESS\", fillcolor=palegreen];",
+            done
+        )));
+        assert!(dot.contains("    subgraph \"cluster_web\" {\n        label=\"gang web\";\n"));
+        assert!(dot.contains(&format!(
+            "        \"{0}\" [label=\"frontend\\n{0}\\nUNFINISHED\", fillcolor=khaki];",
+            member
+        )));
+    }
+
+    #[test]
+    fn dependencies_and_lock_waits_are_drawn_as_edges() {
+        let mut tasks = Registry::default();
+        let build = tasks.insert(Task::new("/bin/build", None, 1));
+        let test = tasks.insert(Task::from_spec(Spec {
+            after: vec!["build".to_string()],
+            ..Spec::new("/bin/test", None, 1)
+        }));
+        let holders = HashMap::from([(build, test)]);
+
+        let dot = dot(&tasks, &holders);
+        assert!(dot.contains(&format!("    \"{}\" -> \"{}\";\n", build, test)));
+        assert!(dot.contains(&format!(
+            "    \"{}\" -> \"{}\" [style=dashed, color=red, label=\"waits for\"];\n",
+            build, test
+        )));
+        assert_eq!(dot.matches("->").count(), 2);
+    }
+}
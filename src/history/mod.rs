@@ -0,0 +1,145 @@
+use crate::store::Record;
+use clap::ValueEnum;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, PartialEq, Copy, Clone, ValueEnum)]
+pub enum StateFilter {
+    Queued,
+    Terminated,
+    Succeeded,
+    Failed,
+}
+
+impl StateFilter {
+    fn matches(&self, record: &Record) -> bool {
+        let terminated = record.state == "TERMINATED";
+        match self {
+            StateFilter::Queued => !terminated,
+            StateFilter::Terminated => terminated,
+            StateFilter::Succeeded => terminated && record.exit_code == Some(0),
+            StateFilter::Failed => terminated && record.exit_code != Some(0),
+        }
+    }
+}
+
+pub fn parse_since(value: &str) -> Result<f64, String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_secs_f64();
+
+    if let Ok(timestamp) = value.parse::<f64>() {
+        return Ok(timestamp);
+    }
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("Invalid --since value: {}", value))?;
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("Invalid --since value: {}", value))?;
+
+    let seconds = match unit {
+        "s" => amount,
+        "m" => amount * 60,
+        "h" => amount * 60 * 60,
+        "d" => amount * 24 * 60 * 60,
+        "w" => amount * 7 * 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "Invalid --since unit: {} (expected s, m, h, d or w)",
+                unit
+            ))
+        }
+    };
+
+    Ok(now - seconds as f64)
+}
+
+pub fn filter(records: Vec<Record>, state: Option<StateFilter>) -> Vec<Record> {
+    records
+        .into_iter()
+        .filter(|r| state.is_none_or(|s| s.matches(r)))
+        .collect()
+}
+
+fn format_timestamp(seconds: f64) -> String {
+    let seconds = seconds as i64;
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+fn format_outcome(record: &Record) -> &'static str {
+    match (record.state.as_str(), record.exit_code) {
+        ("TERMINATED", Some(0)) => "SUCCESS",
+        ("TERMINATED", Some(2)) => "POISONED",
+        ("TERMINATED", _) => "FAILURE",
+        _ => "QUEUED",
+    }
+}
+
+pub fn print_table(records: &[Record]) {
+    if records.is_empty() {
+        println!("No matching tasks");
+        return;
+    }
+
+    println!(
+        "{:<26}  {:<16}  {:<19}  {:<8}  {:>8}  {:>12}  {:>14}  COMMAND",
+        "PID", "NAME", "SUBMITTED (UTC)", "OUTCOME", "PRIORITY", "DURATION (s)", "PEAK RSS (KiB)"
+    );
+
+    for record in records {
+        let mut command = record.path_to_binary.clone();
+        if let Some(args) = &record.args {
+            command.push(' ');
+            command.push_str(&crate::control::quote_words(args));
+        }
+
+        let duration = match record.finished_at {
+            Some(_) => format!("{:.3}", record.duration),
+            None => "-".to_string(),
+        };
+        let peak = record
+            .peak_resident_kib
+            .map_or("-".to_string(), |kib| kib.to_string());
+
+        println!(
+            "{:<26}  {:<16}  {:<19}  {:<8}  {:>8}  {:>12}  {:>14}  {}",
+            record.id,
+            record.name,
+            format_timestamp(record.submitted_at),
+            format_outcome(record),
+            record.priority,
+            duration,
+            peak,
+            command
+        );
+    }
+}
+
+pub fn print_json(records: &[Record]) -> Result<(), serde_json::Error> {
+    println!("{}", serde_json::to_string_pretty(records)?);
+    Ok(())
+}
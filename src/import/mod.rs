@@ -0,0 +1,225 @@
+use crate::executor::{MockExecutor, Outcome};
+use crate::nice::NiceTable;
+use crate::task::{ExitCode, Spec, Task};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const MAX_PROCESSES: usize = 50;
+const MAX_QUANTA: f64 = 20.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub pid: u32,
+    pub name: String,
+    pub nice: i64,
+    pub cpu: f64,
+    pub resident_kib: u64,
+}
+
+struct Columns {
+    pid: usize,
+    nice: Option<usize>,
+    percent: Option<usize>,
+    time: Option<usize>,
+    elapsed: Option<usize>,
+    rss: Option<usize>,
+    command: usize,
+}
+
+impl Columns {
+    fn from_header(tokens: &[&str]) -> Option<Self> {
+        let find = |names: &[&str]| tokens.iter().position(|token| names.contains(token));
+        Some(Self {
+            pid: find(&["PID"])?,
+            nice: find(&["NI", "NICE"]),
+            percent: find(&["%CPU"]),
+            time: find(&["TIME", "CPUTIME"]),
+            elapsed: find(&["ETIMES"]),
+            rss: find(&["RSS", "RSZ"]),
+            command: find(&["COMMAND", "Command", "COMM", "CMD", "ARGS"])?,
+        })
+    }
+}
+
+fn cpu_seconds(time: &str) -> Option<f64> {
+    let (days, clock) = match time.split_once('-') {
+        Some((days, clock)) => (days.parse::<f64>().ok()?, clock),
+        None => (0.0, time),
+    };
+    let seconds = clock.split(':').try_fold(0.0, |total, part| {
+        part.parse::<f64>().ok().map(|part| total * 60.0 + part)
+    })?;
+    Some(days * 86_400.0 + seconds)
+}
+
+pub fn parse(text: &str) -> Result<Vec<Sample>, String> {
+    let mut columns: Option<Columns> = None;
+    let mut samples: BTreeMap<u32, Sample> = BTreeMap::new();
+
+    for line in text.lines() {
+        let line = line.trim_start_matches('#');
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.first() == Some(&"Average:") {
+            continue;
+        }
+        if tokens.contains(&"PID") {
+            columns = Some(
+                Columns::from_header(&tokens)
+                    .ok_or_else(|| format!("header has no command column: {}", line.trim()))?,
+            );
+            continue;
+        }
+        let Some(columns) = &columns else { continue };
+        if tokens.len() <= columns.command {
+            continue;
+        }
+        let Ok(pid) = tokens[columns.pid].parse::<u32>() else {
+            continue;
+        };
+
+        let number = |index: Option<usize>| index.and_then(|i| tokens[i].parse::<f64>().ok());
+        let percent = number(columns.percent);
+        let time = columns.time.and_then(|i| cpu_seconds(tokens[i]));
+        let cpu = match (time, percent, number(columns.elapsed)) {
+            (Some(time), _, _) => time,
+            (None, Some(percent), Some(elapsed)) => percent / 100.0 * elapsed,
+            (None, percent, _) => percent.unwrap_or(0.0),
+        };
+        let command = tokens[columns.command];
+        let name = match command.strip_prefix('/') {
+            Some(path) => path.rsplit('/').next().unwrap_or(command),
+            None => command,
+        };
+
+        let sample = samples.entry(pid).or_insert_with(|| Sample {
+            pid,
+            name: name.to_string(),
+            nice: 0,
+            cpu: 0.0,
+            resident_kib: 0,
+        });
+        sample.cpu += cpu;
+        if let Some(nice) = number(columns.nice) {
+            sample.nice = nice as i64;
+        }
+        if let Some(rss) = number(columns.rss) {
+            sample.resident_kib = sample.resident_kib.max(rss as u64);
+        }
+    }
+
+    match columns {
+        None => Err("no header with a PID column found".to_string()),
+        Some(_) if samples.is_empty() => Err("no process rows found".to_string()),
+        Some(_) => Ok(samples.into_values().collect()),
+    }
+}
+
+#[cfg(all(unix, feature = "process"))]
+pub fn scan() -> Result<Vec<Sample>, String> {
+    use crate::executor::Pid;
+    use crate::procfs;
+
+    let entries = std::fs::read_dir("/proc").map_err(|e| format!("/proc: {}", e))?;
+    let samples: Vec<Sample> = entries
+        .filter_map(|entry| {
+            let raw: u32 = entry.ok()?.file_name().to_str()?.parse().ok()?;
+            let pid = Pid::from_raw(raw);
+            let stats = procfs::process_stats(pid)?;
+            Some(Sample {
+                pid: raw,
+                name: procfs::comm(pid)?,
+                nice: procfs::nice(pid).unwrap_or(0),
+                cpu: stats.cpu_time.as_secs_f64(),
+                resident_kib: stats.resident_kib.unwrap_or(0),
+            })
+        })
+        .collect();
+    if samples.is_empty() {
+        return Err("no processes found in /proc".to_string());
+    }
+    Ok(samples)
+}
+
+pub fn load(path: &Path) -> Result<Vec<Sample>, String> {
+    #[cfg(all(unix, feature = "process"))]
+    if path == Path::new("/proc") {
+        return scan();
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub fn workload(mut samples: Vec<Sample>, table: &NiceTable) -> (Vec<Task>, MockExecutor) {
+    samples.sort_by(|a, b| b.cpu.total_cmp(&a.cpu).then(a.pid.cmp(&b.pid)));
+    samples.truncate(MAX_PROCESSES);
+    let busiest = samples.first().map_or(0.0, |sample| sample.cpu);
+
+    let mut executor = MockExecutor::new();
+    let mut tasks = Vec::with_capacity(samples.len());
+    for sample in samples {
+        let path = format!("pid/{}", sample.pid);
+        let share = if busiest > 0.0 {
+            sample.cpu / busiest
+        } else {
+            0.0
+        };
+        let quanta = 1 + (share * (MAX_QUANTA - 1.0)).round() as u32;
+        let priority = table.priority(sample.nice);
+
+        executor = executor.script(path.as_str(), quanta, Outcome::Exit(ExitCode::Success));
+        if sample.resident_kib > 0 {
+            executor = executor.resident(path.as_str(), sample.resident_kib);
+        }
+        tasks.push(Task::from_spec(Spec {
+            name: Some(sample.name),
+            ..Spec::new(path, None, priority)
+        }));
+    }
+
+    (tasks, executor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PS: &str = "\
+  PID  NI %CPU   RSS     TIME COMMAND
+    1   0  0.0 11000 00:00:02 /sbin/init splash
+  812 -10  2.5 51200 00:01:00 pulseaudio
+ 4242  19 90.0 204800 01-00:00:00 cc1plus
+";
+
+    const PIDSTAT: &str = "\
+Linux 6.1.0 (host) \t10/14/2026 \t_x86_64_\t(8 CPU)
+
+10:00:01 AM   UID       PID    %usr %system  %guest   %wait    %CPU   CPU  Command
+10:00:02 AM  1000      1234   40.00   10.00    0.00    0.00   50.00     2  firefox
+10:00:02 AM  1000      5678    1.00    1.00    0.00    0.00    2.00     0  bash
+
+10:00:01 AM   UID       PID  minflt/s  majflt/s     VSZ     RSS   %MEM  Command
+10:00:02 AM  1000      1234    100.00      0.00 3000000  800000   9.80  firefox
+
+Average:      UID       PID    %usr %system  %guest   %wait    %CPU   CPU  Command
+Average:     1000      1234   40.00   10.00    0.00    0.00   50.00     -  firefox
+";
+
+    #[test]
+    fn ps_and_pidstat_samples_become_a_weighted_workload() {
+        let samples = parse(PS).unwrap();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].name, "init");
+        assert_eq!(samples[2].cpu, 86_400.0);
+
+        let (tasks, _) = workload(samples, &NiceTable::default());
+        let names: Vec<String> = tasks.iter().map(Task::name).collect();
+        assert_eq!(names, ["cc1plus", "pulseaudio", "init"]);
+        let priorities: Vec<u8> = tasks.iter().map(|task| task.priority).collect();
+        assert_eq!(priorities, [7, 2, 4]);
+
+        let firefox = parse(PIDSTAT).unwrap();
+        assert_eq!(firefox.len(), 2);
+        assert_eq!((firefox[0].cpu, firefox[0].resident_kib), (50.0, 800_000));
+        assert_eq!(firefox[1].name, "bash");
+    }
+}
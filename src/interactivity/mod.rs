@@ -0,0 +1,162 @@
+use crate::executor::Executor;
+use crate::task::Task;
+use std::collections::HashMap;
+use std::time::Duration;
+use ulid::Ulid;
+
+pub const MAX_BOOST: i8 = 4;
+
+const INTERACTIVE_RATIO: f64 = 0.5;
+const CPU_BOUND_RATIO: f64 = 0.9;
+const IO_BOUND_SYSCALLS: u64 = 50;
+
+#[derive(Default)]
+pub struct Interactivity {
+    last_cpu_time: HashMap<Ulid, Duration>,
+    last_syscalls: HashMap<Ulid, u64>,
+}
+
+impl Interactivity {
+    pub fn observe(&mut self, task: &mut Task, executor: &dyn Executor, quantum: Duration) {
+        let Some(cpu_time) = task.get_pid().and_then(|pid| executor.cpu_time(pid)) else {
+            return;
+        };
+
+        let previous = self
+            .last_cpu_time
+            .insert(task.get_id(), cpu_time)
+            .unwrap_or_default();
+        let ratio = cpu_time.saturating_sub(previous).div_duration_f64(quantum);
+
+        let syscalls = task.get_pid().and_then(|pid| executor.syscalls(pid));
+        let io_bound = syscalls.is_some_and(|total| {
+            let previous = self
+                .last_syscalls
+                .insert(task.get_id(), total)
+                .unwrap_or_default();
+            total.saturating_sub(previous) >= IO_BOUND_SYSCALLS
+        });
+
+        if ratio < INTERACTIVE_RATIO || io_bound {
+            task.boost = (task.boost + 1).min(MAX_BOOST);
+        } else if ratio >= CPU_BOUND_RATIO {
+            task.boost = (task.boost - 1).max(-MAX_BOOST);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::Pid;
+    use crate::task::{Spec, Status};
+    use std::io;
+
+    const QUANTUM: Duration = Duration::from_millis(100);
+
+    #[derive(Default)]
+    struct Counters {
+        cpu_time: Option<Duration>,
+        syscalls: Option<u64>,
+    }
+
+    impl Executor for Counters {
+        fn spawn(&mut self, _id: Ulid, _spec: &Spec) -> io::Result<Pid> {
+            unreachable!()
+        }
+
+        fn poll(&mut self, _pid: Pid) -> io::Result<Status> {
+            unreachable!()
+        }
+
+        fn stop(&mut self, _pid: Pid) -> io::Result<()> {
+            unreachable!()
+        }
+
+        fn cont(&mut self, _pid: Pid) -> io::Result<()> {
+            unreachable!()
+        }
+
+        fn kill(&mut self, _pid: Pid) {}
+
+        fn cpu_time(&self, _pid: Pid) -> Option<Duration> {
+            self.cpu_time
+        }
+
+        fn syscalls(&self, _pid: Pid) -> Option<u64> {
+            self.syscalls
+        }
+    }
+
+    struct Probe {
+        task: Task,
+        executor: Counters,
+        interactivity: Interactivity,
+    }
+
+    impl Probe {
+        fn new() -> Self {
+            Self {
+                task: Task::adopt(Pid::from_raw(1), Spec::new("task", None, 1)),
+                executor: Counters {
+                    cpu_time: Some(Duration::ZERO),
+                    syscalls: None,
+                },
+                interactivity: Interactivity::default(),
+            }
+        }
+
+        fn quantum(&mut self, cpu_ms: u64) -> i8 {
+            let used = Duration::from_millis(cpu_ms);
+            self.executor.cpu_time = self.executor.cpu_time.map(|total| total + used);
+            self.interactivity
+                .observe(&mut self.task, &self.executor, QUANTUM);
+            self.task.boost
+        }
+    }
+
+    #[test]
+    fn the_share_of_the_quantum_used_decides_the_boost() {
+        let mut probe = Probe::new();
+
+        assert_eq!(probe.quantum(49), 1);
+        assert_eq!(probe.quantum(50), 1);
+        assert_eq!(probe.quantum(89), 1);
+        assert_eq!(probe.quantum(90), 0);
+        assert_eq!(probe.quantum(100), -1);
+        assert_eq!(probe.quantum(0), 0);
+    }
+
+    #[test]
+    fn the_boost_moves_one_step_a_quantum_and_stops_at_the_limit() {
+        let mut probe = Probe::new();
+
+        let boosts: Vec<i8> = (0..6).map(|_| probe.quantum(10)).collect();
+        assert_eq!(boosts, [1, 2, 3, 4, MAX_BOOST, MAX_BOOST]);
+
+        let boosts: Vec<i8> = (0..10).map(|_| probe.quantum(100)).collect();
+        assert_eq!(boosts, [3, 2, 1, 0, -1, -2, -3, -4, -MAX_BOOST, -MAX_BOOST]);
+    }
+
+    #[test]
+    fn many_syscalls_in_a_quantum_count_as_interactive() {
+        let mut probe = Probe::new();
+        probe.executor.syscalls = Some(0);
+
+        probe.executor.syscalls = Some(IO_BOUND_SYSCALLS - 1);
+        assert_eq!(probe.quantum(100), -1);
+        probe.executor.syscalls = Some(2 * IO_BOUND_SYSCALLS - 1);
+        assert_eq!(probe.quantum(100), 0);
+        probe.executor.syscalls = Some(2 * IO_BOUND_SYSCALLS);
+        assert_eq!(probe.quantum(100), -1);
+    }
+
+    #[test]
+    fn tasks_without_a_cpu_time_keep_their_boost() {
+        let mut probe = Probe::new();
+        probe.executor = Counters::default();
+
+        assert_eq!(probe.quantum(0), 0);
+        assert_eq!(probe.quantum(100), 0);
+    }
+}
@@ -0,0 +1,67 @@
+pub mod affinity;
+#[cfg(all(unix, feature = "audit"))]
+pub mod audit;
+pub mod backfill;
+pub mod burst;
+pub mod checkpoint;
+pub mod clock;
+pub mod comparison;
+pub mod config;
+pub mod control;
+#[cfg(all(unix, feature = "dbus"))]
+pub mod dbus;
+pub mod deadline;
+pub mod debugger;
+pub mod dependency;
+pub mod diff;
+#[cfg(all(unix, feature = "process"))]
+pub mod doctor;
+pub mod executor;
+pub mod experiment;
+#[cfg(any(test, feature = "testing"))]
+pub mod ffi;
+pub mod findings;
+pub mod gang;
+pub mod graph;
+#[cfg(feature = "store")]
+pub mod history;
+#[cfg(any(test, feature = "testing"))]
+pub mod import;
+pub mod interactivity;
+pub mod limits;
+pub mod metrics;
+pub mod nice;
+pub mod otel;
+pub mod output;
+pub mod preemption;
+#[cfg(any(test, feature = "testing"))]
+pub mod preset;
+pub mod procfs;
+pub mod qos;
+pub mod registry;
+pub mod report;
+pub mod runqueue;
+pub mod scenario;
+pub mod scheduler;
+pub mod selector;
+pub mod simulator;
+pub mod sporadic;
+pub mod starvation;
+pub mod status;
+#[cfg(feature = "store")]
+pub mod store;
+#[cfg(any(test, feature = "testing"))]
+pub mod stress;
+pub mod summary;
+#[cfg(any(test, feature = "testing"))]
+pub mod sweep;
+pub mod syscalls;
+pub mod task;
+pub mod throttle;
+pub mod throughput;
+pub mod timeseries;
+pub mod trace;
+pub mod watchdog;
+pub mod webhook;
+pub mod window;
+pub mod worker;
@@ -0,0 +1,111 @@
+use crate::clock::Instant;
+use clap::Args;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Default, Clone, Copy, Args)]
+pub struct Limits {
+    #[arg(long, value_name = "N")]
+    pub max_queued: Option<usize>,
+
+    #[arg(long, value_name = "N")]
+    pub max_rate: Option<usize>,
+
+    #[arg(long, value_name = "N")]
+    pub max_per_owner: Option<usize>,
+}
+
+pub struct Admission {
+    limits: Limits,
+    recent: HashMap<String, VecDeque<Instant>>,
+}
+
+impl Admission {
+    pub fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            recent: HashMap::new(),
+        }
+    }
+
+    pub fn check(
+        &mut self,
+        owner: &str,
+        now: Instant,
+        queued: usize,
+        owned: usize,
+    ) -> Result<(), String> {
+        if let Some(max) = self.limits.max_queued {
+            if queued >= max {
+                return Err(format!(
+                    "Rejected: the run queue is full ({} of {} tasks)",
+                    queued, max
+                ));
+            }
+        }
+
+        if let Some(max) = self.limits.max_per_owner {
+            if owned >= max {
+                return Err(format!(
+                    "Rejected: {} already has {} unfinished tasks (limit {})",
+                    owner, owned, max
+                ));
+            }
+        }
+
+        if let Some(max) = self.limits.max_rate {
+            let recent = self.recent.entry(owner.to_string()).or_default();
+            while recent
+                .front()
+                .is_some_and(|&at| now.saturating_duration_since(at) >= RATE_WINDOW)
+            {
+                recent.pop_front();
+            }
+
+            if recent.len() >= max {
+                return Err(format!(
+                    "Rejected: {} is over {} submissions per second",
+                    owner, max
+                ));
+            }
+            recent.push_back(now);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_limit_rejects_with_its_own_reason() {
+        let mut admission = Admission::new(Limits {
+            max_queued: Some(10),
+            max_rate: Some(2),
+            max_per_owner: Some(3),
+        });
+        let start = Instant::now();
+
+        assert!(admission
+            .check("alice", start, 10, 0)
+            .unwrap_err()
+            .contains("run queue is full"));
+        assert!(admission
+            .check("alice", start, 0, 3)
+            .unwrap_err()
+            .contains("unfinished tasks"));
+
+        assert!(admission.check("alice", start, 0, 0).is_ok());
+        assert!(admission.check("alice", start, 0, 0).is_ok());
+        assert!(admission
+            .check("alice", start, 0, 0)
+            .unwrap_err()
+            .contains("submissions per second"));
+        assert!(admission.check("bob", start, 0, 0).is_ok());
+        assert!(admission.check("alice", start + RATE_WINDOW, 0, 0).is_ok());
+    }
+}
@@ -1,115 +1,1388 @@
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use rr::clock::{self, ManualClock};
+use rr::config::{self, Config};
+use rr::debugger::Breakpoint;
+use rr::executor::MockExecutor;
+#[cfg(unix)]
+use rr::executor::{AdoptedExecutor, Pid};
+use rr::experiment::Experiment;
+use rr::limits::Limits;
+use rr::nice::NiceTable;
+use rr::output::{self, ColorChoice, OutputMode};
+use rr::preset::Preset;
+use rr::scenario::Scenario;
+use rr::scheduler::Scheduler;
+use rr::simulator::{self, Simulator};
+use rr::store::Store;
+use rr::task::Task;
+use rr::trace::EventFormat;
+use rr::window::Window;
+use rr::{
+    checkpoint, control, diff, experiment, history, metrics, qos, scheduler, sporadic, starvation,
+    store, sweep, task, throttle,
+};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
-use std::thread;
-use std::time::{Duration, SystemTime};
-use task::Task;
-mod task;
+use std::time::{Duration, Instant};
 
-const TIME_QUANTUM: u64 = 150;
+#[derive(Parser)]
+#[command(
+    name = "psched",
+    about = "Process scheduler simulator",
+    args_conflicts_with_subcommands = true
+)]
+struct Args {
+    #[arg(long, global = true, env = "PSCHED_CONFIG", value_name = "FILE")]
+    config: Option<PathBuf>,
 
-fn dispatcher(tasks: &mut Vec<Task>, tx: &mpsc::Sender<task::Status>) {
-    for task in tasks.iter_mut() {
-        if task.state == task::State::Waiting {
-            task.state = task::State::Ready;
+    #[arg(long, global = true, env = "PSCHED_PROFILE", value_name = "NAME")]
+    profile: Option<String>,
+
+    #[arg(long, value_name = "CHECKPOINT")]
+    resume: Option<PathBuf>,
+
+    #[arg(
+        long,
+        env = "PSCHED_STRESS",
+        value_name = "N",
+        conflicts_with = "resume"
+    )]
+    stress: Option<usize>,
+
+    #[arg(long, env = "PSCHED_SCENARIO", value_name = "FILE", conflicts_with_all = ["resume", "stress"])]
+    scenario: Option<PathBuf>,
+
+    #[arg(long, env = "PSCHED_PRESET", value_enum, value_name = "NAME", conflicts_with_all = ["resume", "stress", "scenario"])]
+    preset: Option<Preset>,
+
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["resume", "stress", "preset", "scenario", "tasks", "stdin_tasks"])]
+    import: Option<PathBuf>,
+
+    #[arg(long, env = "PSCHED_CACHE_WARMTH", value_name = "PERCENT", value_parser = clap::value_parser!(u32).range(..100))]
+    cache_warmth: Option<u32>,
+
+    #[arg(long = "task", value_name = "TASK", allow_hyphen_values = true, value_parser = control::parse_task, conflicts_with_all = ["stress", "preset"])]
+    tasks: Vec<task::Spec>,
+
+    #[arg(long, conflicts_with_all = ["stress", "preset"])]
+    stdin_tasks: bool,
+
+    #[command(flatten)]
+    options: SchedulerOptions,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(clap::Args)]
+struct SchedulerOptions {
+    #[arg(
+        long,
+        env = "PSCHED_CONCURRENCY",
+        default_value = "1",
+        value_name = "N"
+    )]
+    concurrency: NonZeroUsize,
+
+    #[arg(long, env = "PSCHED_QUANTUM", value_name = "DURATION", default_value = "150ms", value_parser = simulator::parse_quantum)]
+    quantum: Duration,
+
+    #[arg(long, env = "PSCHED_MAX_QUANTA", value_name = "N")]
+    max_quanta: Option<u64>,
+
+    #[arg(long, env = "PSCHED_MAX_SIM_TIME", value_name = "DURATION", value_parser = clock::parse_duration)]
+    max_sim_time: Option<Duration>,
+
+    #[command(flatten)]
+    window: Window,
+
+    #[arg(
+        long,
+        env = "PSCHED_POLICY",
+        alias = "algorithm",
+        value_enum,
+        default_value = "priority"
+    )]
+    policy: scheduler::Policy,
+
+    #[arg(long, value_name = "LIBRARY", conflicts_with = "policy")]
+    policy_plugin: Option<PathBuf>,
+
+    #[arg(long, env = "PSCHED_GROUP_POLICY", value_enum, value_name = "POLICY")]
+    group_policy: Option<scheduler::Policy>,
+
+    #[cfg(feature = "scripting")]
+    #[arg(long, value_name = "SCRIPT", conflicts_with_all = ["policy", "policy_plugin"])]
+    policy_script: Option<PathBuf>,
+
+    #[arg(long, env = "PSCHED_SEED")]
+    seed: Option<u64>,
+
+    #[arg(long, env = "PSCHED_STARVATION_THRESHOLD", value_name = "MS", default_value_t = starvation::DEFAULT_THRESHOLD_MS)]
+    starvation_threshold: u64,
+
+    #[arg(long, env = "PSCHED_BACKFILL")]
+    backfill: bool,
+
+    #[arg(long, env = "PSCHED_QOS_WINDOW", value_name = "QUANTA", default_value_t = qos::DEFAULT_WINDOW)]
+    qos_window: u64,
+
+    #[arg(long, env = "PSCHED_QUOTA_PERIOD", value_name = "QUANTA", default_value_t = throttle::DEFAULT_PERIOD)]
+    quota_period: u64,
+
+    #[arg(long, env = "PSCHED_INTERACTIVITY_BOOST")]
+    interactivity_boost: bool,
+
+    #[arg(long, env = "PSCHED_DONATE_PRIORITY")]
+    donate_priority: bool,
+
+    #[arg(long, env = "PSCHED_SPORADIC_SERVER", value_name = "BUDGET/PERIOD", value_parser = sporadic::parse_server)]
+    sporadic_server: Option<(u32, u32)>,
+
+    #[arg(long, env = "PSCHED_MIGRATION_COST", value_name = "MS")]
+    migration_cost: Option<u64>,
+
+    #[arg(long, env = "PSCHED_TIME_SCALE", value_name = "FACTOR", default_value_t = 1.0, value_parser = clock::parse_scale)]
+    time_scale: f64,
+
+    #[arg(long, env = "PSCHED_WATCHDOG", value_name = "MULTIPLE", value_parser = clap::value_parser!(u32).range(1..))]
+    watchdog: Option<u32>,
+
+    #[arg(long, env = "PSCHED_STATUS_TIMEOUT", value_name = "MS", default_value_t = simulator::DEFAULT_STATUS_TIMEOUT_MS)]
+    status_timeout: u64,
+
+    #[arg(long)]
+    compare_kernel: bool,
+
+    #[arg(long)]
+    measure_preemption: bool,
+
+    #[cfg(target_os = "linux")]
+    #[arg(long)]
+    systemd: bool,
+
+    #[cfg(target_os = "linux")]
+    #[arg(long, conflicts_with = "systemd")]
+    count_syscalls: bool,
+
+    #[arg(long, env = "PSCHED_APPLY_NICE")]
+    apply_nice: bool,
+
+    #[arg(skip)]
+    nice: Box<NiceTable>,
+
+    #[arg(long, env = "PSCHED_EXPLAIN")]
+    explain: bool,
+
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    #[arg(long)]
+    debug: bool,
+
+    #[arg(long = "break", value_name = "BREAKPOINT")]
+    breakpoints: Vec<Breakpoint>,
+
+    #[arg(long, env = "PSCHED_FINDINGS")]
+    findings: bool,
+
+    #[arg(long, env = "PSCHED_TRACE", value_name = "FILE")]
+    trace: Option<PathBuf>,
+
+    #[arg(long, env = "PSCHED_EVENTS", value_enum, value_name = "FORMAT")]
+    events: Option<EventFormat>,
+
+    #[arg(long, env = "PSCHED_GRAPH", value_name = "FILE")]
+    graph: Option<PathBuf>,
+
+    #[arg(long, env = "PSCHED_REPORT", value_name = "FILE")]
+    report: Option<PathBuf>,
+
+    #[arg(long, env = "PSCHED_TIMESERIES", value_name = "FILE")]
+    timeseries: Option<PathBuf>,
+
+    #[arg(long, env = "PSCHED_EXPERIMENT", value_name = "NAME")]
+    experiment: Option<String>,
+
+    #[arg(long, env = "PSCHED_HOST_STATS")]
+    host_stats: bool,
+
+    #[arg(long, env = "PSCHED_THROUGHPUT")]
+    throughput: bool,
+
+    #[arg(
+        long,
+        env = "PSCHED_COLOR",
+        value_enum,
+        value_name = "WHEN",
+        default_value = "auto"
+    )]
+    color: ColorChoice,
+
+    #[arg(long, env = "PSCHED_QUIET")]
+    quiet: bool,
+
+    #[arg(
+        long,
+        env = "PSCHED_OUTPUT",
+        value_enum,
+        value_name = "MODE",
+        default_value = "normal"
+    )]
+    output: OutputMode,
+
+    #[arg(long, env = "PSCHED_OTLP", value_name = "URL")]
+    otlp: Option<String>,
+
+    #[arg(long, env = "PSCHED_METRICS", value_name = "ADDR")]
+    metrics: Option<String>,
+
+    #[arg(long, value_name = "PATH")]
+    control_fifo: Option<PathBuf>,
+
+    #[cfg(all(unix, feature = "audit"))]
+    #[arg(long, value_name = "SINK")]
+    audit: Option<rr::audit::Sink>,
+
+    #[cfg(feature = "async")]
+    #[arg(long = "async", conflicts_with_all = ["debug", "breakpoints"])]
+    async_runtime: bool,
+}
+
+fn watch_config(path: Option<&Path>, profile: Option<&str>) -> Option<config::Watch> {
+    let path = Config::locate(path)?;
+    match config::Watch::new(path.clone(), profile.map(str::to_string)) {
+        Ok(watch) => Some(watch),
+        Err(err) => {
+            println!("Failed to watch config {}: {}", path.display(), err);
+            std::process::exit(1);
         }
     }
+}
 
-    if let Some(task) = tasks
-        .iter_mut()
-        .filter(|t| t.state == task::State::Ready)
-        .min_by_key(|t| t.priority)
-    {
-        println!(
-            "Dispatcher selected PID: {} with priority: {}",
-            task.get_id(),
-            task.priority
+fn setting<V, T>(
+    matches: &ArgMatches,
+    id: &str,
+    value: Option<V>,
+    parse: impl FnOnce(V) -> Result<T, String>,
+) -> Option<T> {
+    let value = value.filter(|_| {
+        !matches!(
+            matches.value_source(id),
+            Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+        )
+    })?;
+    match parse(value) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            println!("Invalid {} in config: {}", id.replace('_', "-"), err);
+            std::process::exit(1);
+        }
+    }
+}
+
+impl SchedulerOptions {
+    fn configure(&mut self, matches: &ArgMatches, path: Option<&Path>, profile: Option<&str>) {
+        let config = match Config::find(path) {
+            Ok(Some(config)) => config,
+            Ok(None) => Config::default(),
+            Err(err) => {
+                println!("Failed to load config {}", err);
+                std::process::exit(1);
+            }
+        };
+        let profile = match config.profile(profile) {
+            Ok(profile) => profile,
+            Err(err) => {
+                println!("{}", err);
+                std::process::exit(1);
+            }
+        };
+
+        if let Some(policy) = setting(matches, "policy", profile.policy, |value| {
+            scheduler::Policy::from_str(&value, true)
+        }) {
+            self.policy = policy;
+        }
+        if let Some(quantum) = setting(matches, "quantum", profile.quantum, |value| {
+            simulator::parse_quantum(&value)
+        }) {
+            self.quantum = quantum;
+        }
+        if let Some(concurrency) = setting(matches, "concurrency", profile.concurrency, Ok) {
+            self.concurrency = concurrency;
+        }
+        if let Some(max_quanta) = setting(matches, "max_quanta", profile.max_quanta, Ok) {
+            self.max_quanta = Some(max_quanta);
+        }
+        if let Some(max_sim_time) =
+            setting(matches, "max_sim_time", profile.max_sim_time, |value| {
+                clock::parse_duration(&value)
+            })
+        {
+            self.max_sim_time = Some(max_sim_time);
+        }
+        if let Some(seed) = setting(matches, "seed", profile.seed, Ok) {
+            self.seed = Some(seed);
+        }
+        if let Some(threshold) = setting(
+            matches,
+            "starvation_threshold",
+            profile.starvation_threshold,
+            Ok,
+        ) {
+            self.starvation_threshold = threshold;
+        }
+        if let Some(backfill) = setting(matches, "backfill", profile.backfill, Ok) {
+            self.backfill = backfill;
+        }
+        if let Some(boost) = setting(
+            matches,
+            "interactivity_boost",
+            profile.interactivity_boost,
+            Ok,
+        ) {
+            self.interactivity_boost = boost;
+        }
+        if let Some(window) = setting(matches, "qos_window", profile.qos_window, Ok) {
+            self.qos_window = window;
+        }
+        if let Some(period) = setting(matches, "quota_period", profile.quota_period, Ok) {
+            self.quota_period = period;
+        }
+        if let Some(color) = setting(matches, "color", profile.color, |value| {
+            ColorChoice::from_str(&value, true)
+        }) {
+            self.color = color;
+        }
+        if let Some(quiet) = setting(matches, "quiet", profile.quiet, Ok) {
+            self.quiet = quiet;
+        }
+        if let Some(mode) = setting(matches, "output", profile.output, |value| {
+            OutputMode::from_str(&value, true)
+        }) {
+            self.output = mode;
+        }
+        if let Some(events) = setting(matches, "events", profile.events, |value| {
+            EventFormat::from_str(&value, true)
+        }) {
+            self.events = Some(events);
+        }
+        if let Some(throughput) = setting(matches, "throughput", profile.throughput, Ok) {
+            self.throughput = throughput;
+        }
+        if let Some(explain) = setting(matches, "explain", profile.explain, Ok) {
+            self.explain = explain;
+        }
+        if let Some(findings) = setting(matches, "findings", profile.findings, Ok) {
+            self.findings = findings;
+        }
+        if let Some(trace) = setting(matches, "trace", profile.trace, Ok) {
+            self.trace = Some(trace);
+        }
+        if let Some(graph) = setting(matches, "graph", profile.graph, Ok) {
+            self.graph = Some(graph);
+        }
+        if let Some(timeseries) = setting(matches, "timeseries", profile.timeseries, Ok) {
+            self.timeseries = Some(timeseries);
+        }
+        if let Some(apply_nice) = setting(matches, "apply_nice", profile.apply_nice, Ok) {
+            self.apply_nice = apply_nice;
+        }
+        if let Some(table) = profile.nice {
+            match NiceTable::from_config(&table) {
+                Ok(nice) => *self.nice = nice,
+                Err(err) => {
+                    println!("Invalid nice in config: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        let algorithm = std::env::var("PSCHED_ALGORITHM").ok().filter(|_| {
+            !matches!(
+                matches.value_source("policy"),
+                Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+            )
+        });
+        if let Some(algorithm) = algorithm {
+            match scheduler::Policy::from_str(&algorithm, true) {
+                Ok(policy) => self.policy = policy,
+                Err(err) => {
+                    println!("Invalid PSCHED_ALGORITHM: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        match self.output {
+            OutputMode::Normal => {}
+            OutputMode::Quiet => self.quiet = true,
+            OutputMode::Ndjson => self.events = self.events.or(Some(EventFormat::Ndjson)),
+        }
+        output::set_color(self.color);
+        output::set_summary_only(self.quiet);
+        if self.events.is_some() {
+            output::set_quiet(true);
+        }
+    }
+
+    fn scheduler(&self) -> Box<dyn Scheduler> {
+        let top = self.top_scheduler();
+        match self.group_policy {
+            Some(policy) => Box::new(scheduler::HierarchicalScheduler::new(
+                top,
+                scheduler::build(policy, self.seed),
+            )),
+            None => top,
+        }
+    }
+
+    fn top_scheduler(&self) -> Box<dyn Scheduler> {
+        #[cfg(feature = "scripting")]
+        if let Some(path) = &self.policy_script {
+            match scheduler::ScriptScheduler::load(path) {
+                Ok(script) => return Box::new(script),
+                Err(err) => {
+                    println!("Failed to load policy script {}: {}", path.display(), err);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        let Some(path) = &self.policy_plugin else {
+            return scheduler::build(self.policy, self.seed);
+        };
+
+        match scheduler::PluginScheduler::load(path) {
+            Ok(plugin) => {
+                println!(
+                    "Loaded policy plugin {} from {}",
+                    plugin.name(),
+                    path.display()
+                );
+                Box::new(plugin)
+            }
+            Err(err) => {
+                println!("Failed to load policy plugin {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    fn listen_fifo(&self, spawn: impl FnOnce(&Path) -> std::io::Result<()>) {
+        let Some(path) = &self.control_fifo else {
+            return;
+        };
+        if let Err(err) = spawn(path) {
+            println!("Failed to open control FIFO {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+    }
+
+    fn apply(&self, simulator: Simulator) -> Simulator {
+        #[cfg(unix)]
+        rr::status::dump_on_sigusr1();
+        let mut experiment = self.experiment.as_deref().map(|name| {
+            let command = std::env::args().collect::<Vec<_>>().join(" ");
+            match Experiment::start(Path::new(experiment::DEFAULT_ROOT), name, command) {
+                Ok(experiment) => {
+                    println!(
+                        "Writing experiment {} to {}",
+                        name,
+                        experiment.directory().display()
+                    );
+                    experiment
+                }
+                Err(err) => {
+                    println!("Failed to start experiment {}: {}", name, err);
+                    std::process::exit(1);
+                }
+            }
+        });
+        let mut artifact = |path: &Option<PathBuf>, file: &str| match &mut experiment {
+            Some(experiment) => Some(experiment.artifact(path.clone(), file)),
+            None => path.clone(),
+        };
+        let (trace, graph, report, timeseries) = (
+            artifact(&self.trace, "trace.jsonl"),
+            artifact(&self.graph, "graph.dot"),
+            artifact(&self.report, "report.json"),
+            artifact(&self.timeseries, "timeseries.csv"),
         );
-        task.run(mpsc::Sender::clone(tx));
+        let simulator = simulator
+            .concurrency(self.concurrency)
+            .quantum(self.quantum)
+            .max_quanta(self.max_quanta)
+            .max_sim_time(self.max_sim_time)
+            .measurement_window(self.window)
+            .scheduler(self.scheduler())
+            .starvation_threshold(Duration::from_millis(self.starvation_threshold))
+            .backfill(self.backfill)
+            .qos_window(self.qos_window)
+            .quota_period(self.quota_period)
+            .sporadic_server(self.sporadic_server)
+            .donate_priority(self.donate_priority)
+            .migration_cost(self.migration_cost.map(Duration::from_millis))
+            .time_scale(self.time_scale)
+            .watchdog(self.watchdog)
+            .status_timeout(Duration::from_millis(self.status_timeout))
+            .interactivity_boost(self.interactivity_boost)
+            .compare_kernel(self.compare_kernel)
+            .measure_preemption(self.measure_preemption)
+            .explain(self.explain)
+            .verbose(self.verbose)
+            .debug(self.debug)
+            .findings(self.findings)
+            .trace(trace)
+            .event_stream(self.events)
+            .graph(graph)
+            .report(report)
+            .timeseries(timeseries)
+            .experiment(experiment)
+            .host_stats(self.host_stats)
+            .throughput(self.throughput)
+            .otlp(self.otlp.clone());
+        let simulator = self
+            .breakpoints
+            .iter()
+            .fold(simulator, |simulator, &breakpoint| {
+                simulator.breakpoint(breakpoint)
+            });
+        let simulator = if self.apply_nice {
+            simulator.nice(NiceTable::clone(&self.nice))
+        } else {
+            simulator
+        };
+
+        let simulator = match self.metrics.as_deref().map(metrics::Exporter::serve) {
+            Some(Ok(exporter)) => simulator.metrics(exporter),
+            Some(Err(err)) => {
+                println!("Failed to serve metrics: {}", err);
+                std::process::exit(1);
+            }
+            None => simulator,
+        };
+
+        #[cfg(all(unix, feature = "audit"))]
+        let simulator = match self.audit.map(rr::audit::Audit::open) {
+            Some(Ok(audit)) => simulator.audit(audit),
+            Some(Err(err)) => {
+                println!("Failed to open the audit log: {}", err);
+                std::process::exit(1);
+            }
+            None => simulator,
+        };
+
+        #[cfg(target_os = "linux")]
+        let simulator = if self.count_syscalls {
+            simulator
+                .executor(rr::executor::ProcessExecutor::new().count_syscalls())
+                .count_syscalls(true)
+        } else {
+            simulator
+        };
+
+        #[cfg(target_os = "linux")]
+        if self.systemd {
+            match rr::executor::SystemdExecutor::connect() {
+                Ok(executor) => return simulator.executor(executor),
+                Err(err) => {
+                    println!("Failed to reach systemd: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        simulator
     }
 }
 
-fn main() {
-    if !cfg!(unix) {
-        println!("This program only runs on UNIX-like operating systems.");
+#[derive(Subcommand)]
+enum Command {
+    Daemon {
+        #[arg(long, default_value = control::DEFAULT_SOCKET_PATH)]
+        socket: PathBuf,
+
+        #[arg(long, default_value = store::DEFAULT_DB_PATH)]
+        db: PathBuf,
+
+        #[command(flatten)]
+        limits: Limits,
+
+        #[command(flatten)]
+        integrations: Integrations,
+
+        #[command(flatten)]
+        options: SchedulerOptions,
+    },
+    #[cfg(unix)]
+    Adopt {
+        #[arg(long = "pid", value_name = "PID", required = true)]
+        pids: Vec<u32>,
+
+        #[arg(long, default_value_t = control::DEFAULT_PRIORITY)]
+        priority: u8,
+
+        #[command(flatten)]
+        options: SchedulerOptions,
+    },
+    Submit {
+        #[arg(long, default_value = control::DEFAULT_SOCKET_PATH)]
+        socket: PathBuf,
+
+        #[arg(long)]
+        name: Option<String>,
+
+        #[arg(long = "meta", value_name = "KEY=VALUE", value_parser = control::parse_meta)]
+        metadata: Vec<(String, String)>,
+
+        #[arg(long, default_value_t = control::DEFAULT_PRIORITY)]
+        priority: u8,
+
+        #[arg(long, default_value_t = 1)]
+        width: usize,
+
+        #[arg(long, value_name = "MS")]
+        estimate: Option<u64>,
+
+        #[arg(long, value_name = "MS")]
+        quantum: Option<u64>,
+
+        #[arg(long, value_name = "MS")]
+        deadline: Option<u64>,
+
+        #[arg(long, value_name = "POLICY")]
+        on_miss: Option<task::MissPolicy>,
+
+        #[arg(long, value_name = "MS")]
+        period: Option<u64>,
+
+        #[arg(long, requires = "period")]
+        jobs: Option<u32>,
+
+        #[arg(long, value_name = "TASK")]
+        after: Vec<String>,
+
+        #[arg(long, value_name = "TASK")]
+        apart_from: Vec<String>,
+
+        #[arg(long, value_name = "TASK")]
+        near: Option<String>,
+
+        #[arg(long)]
+        gang: Option<String>,
+
+        #[arg(long)]
+        qos: Option<task::Qos>,
+
+        #[arg(long)]
+        share: Option<f64>,
+
+        #[arg(long, value_parser = throttle::parse_quota)]
+        cpu_quota: Option<f64>,
+
+        #[arg(long, value_name = "IMAGE")]
+        container: Option<String>,
+
+        #[arg(long, requires = "container")]
+        runtime: Option<task::Runtime>,
+
+        #[arg(long, requires = "container")]
+        cpus: Option<f64>,
+
+        #[arg(long)]
+        memory: Option<String>,
+
+        #[arg(long, conflicts_with = "container")]
+        host: Option<String>,
+
+        #[arg(long, value_name = "USER", conflicts_with_all = ["container", "host"])]
+        run_as: Option<String>,
+
+        #[arg(long, value_name = "mount,pid,net,seccomp", conflicts_with_all = ["container", "host"])]
+        sandbox: Option<task::Sandbox>,
+
+        path_to_binary: String,
+
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    Control {
+        #[arg(long, default_value = control::DEFAULT_SOCKET_PATH)]
+        socket: PathBuf,
+
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    History {
+        #[arg(long, default_value = store::DEFAULT_DB_PATH)]
+        db: PathBuf,
+
+        #[arg(long, value_parser = history::parse_since)]
+        since: Option<f64>,
+
+        #[arg(long, value_enum)]
+        state: Option<history::StateFilter>,
+
+        #[arg(long)]
+        json: bool,
+    },
+    Dashboard {
+        #[arg(long, default_value = "dashboard.json")]
+        out: PathBuf,
+    },
+    Sweep {
+        #[arg(long, num_args = 1..=3, value_name = "RANGE", required = true)]
+        quantum: Vec<String>,
+
+        #[arg(
+            long,
+            alias = "algorithm",
+            value_enum,
+            value_delimiter = ',',
+            default_value = "priority"
+        )]
+        policy: Vec<scheduler::Policy>,
+
+        #[arg(long, value_name = "FILE")]
+        workload: PathBuf,
+
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        repeat: u32,
+
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+    Diff {
+        before: PathBuf,
+        after: PathBuf,
+    },
+    #[cfg(unix)]
+    Doctor,
+}
+
+fn demo_tasks() -> Vec<Task> {
+    vec![
+        Task::new("/bad/path", None, 4),
+        Task::new("/bin/echo", Some(vec!["Howdy Y'all!".to_string()]), 2),
+        Task::new("/bin/ls", None, 5),
+        Task::new("/bin/cat", Some(vec!["src/main.rs".to_string()]), 1),
+        Task::new("/bin/ls", None, 3),
+    ]
+}
+
+#[cfg(feature = "async")]
+fn block_on(future: impl std::future::Future<Output = ()>) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to start the tokio runtime");
+    runtime.block_on(future);
+    runtime.shutdown_background();
+}
+
+fn run(
+    resume: Option<PathBuf>,
+    scenario: Option<PathBuf>,
+    specs: Vec<task::Spec>,
+    stdin_tasks: bool,
+    options: SchedulerOptions,
+) {
+    let scenario = scenario.map(|path| match Scenario::load(&path) {
+        Ok(scenario) => scenario,
+        Err(err) => {
+            println!("Failed to load scenario {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+    });
+
+    let mut tasks = match &resume {
+        Some(path) => match checkpoint::load(path) {
+            Ok(tasks) => {
+                println!("Resumed from checkpoint {}", path.display());
+                tasks
+            }
+            Err(err) => {
+                println!("Failed to load checkpoint {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        },
+        None if scenario.is_some() || !specs.is_empty() || stdin_tasks => Vec::new(),
+        None => demo_tasks(),
+    };
+    tasks.extend(specs.into_iter().map(Task::from_spec));
+    let with_scenario = |simulator: Simulator| {
+        match scenario {
+            Some(scenario) => simulator.scenario(scenario),
+            None => simulator,
+        }
+        .stream_tasks(stdin_tasks)
+    };
+
+    #[cfg(feature = "async")]
+    if options.async_runtime {
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = with_scenario(options.apply(Simulator::new(tasks, control_rx)));
+        block_on(async {
+            let (control_tx, control_rx) = tokio::sync::mpsc::unbounded_channel();
+            options.listen_fifo(|path| control::spawn_fifo_reader_async(path, control_tx.clone()));
+            if stdin_tasks {
+                control::spawn_task_reader_async(control_tx);
+            } else {
+                control::spawn_stdin_reader_async(control_tx);
+            }
+            simulator.run_async(control_rx).await;
+        });
+        return;
+    }
+
+    let (control_tx, control_rx) = mpsc::channel();
+    options.listen_fifo(|path| control::spawn_fifo_reader(path, control_tx.clone()));
+    if stdin_tasks {
+        control::spawn_task_reader(control_tx);
+    } else {
+        control::spawn_stdin_reader(control_tx);
+    }
+
+    with_scenario(options.apply(Simulator::new(tasks, control_rx))).run();
+}
+
+fn with_cache_warmth(executor: MockExecutor, cache_warmth: Option<u32>) -> MockExecutor {
+    match cache_warmth {
+        Some(penalty) => executor.cache_warmth(penalty),
+        None => executor,
+    }
+}
+
+fn stress(n: usize, options: SchedulerOptions, cache_warmth: Option<u32>) {
+    if options.debug || !options.breakpoints.is_empty() {
+        println!("--debug and --break cannot be combined with --stress");
         std::process::exit(1);
     }
+    rr::output::set_quiet(true);
 
-    let (tx, rx) = mpsc::channel();
+    let (tasks, executor) = rr::stress::workload(n);
+    let (_control_tx, control_rx) = mpsc::channel();
 
-    let mut tasks = vec![
-        Task::new("/bad/path".as_ref(), None, 4),
-        Task::new("/bin/echo".as_ref(), Some(Vec::from(["Howdy Y'all!"])), 2),
-        Task::new("/bin/ls".as_ref(), None, 5),
-        Task::new("/bin/cat".as_ref(), Some(Vec::from(["src/main.rs"])), 1),
-        Task::new("/bin/ls".as_ref(), None, 3),
-    ];
+    let started = Instant::now();
+    let mut simulator = options
+        .apply(Simulator::new(tasks, control_rx))
+        .executor(with_cache_warmth(executor, cache_warmth))
+        .clock(ManualClock::new())
+        .record_trace();
+    simulator.run();
 
-    for task in &mut tasks {
-        println!(
-            "Created PID: {} with priority: {}",
-            task.get_id(),
-            task.priority,
-        );
-        task.state = task::State::Ready;
-    }
-
-    loop {
-        let mut all_done = true;
-
-        dispatcher(&mut tasks, &tx);
-        thread::sleep(Duration::from_millis(TIME_QUANTUM));
-
-        for task in &mut tasks {
-            if task.state == task::State::Running {
-                match task.get_current_state() {
-                    Ok(task::Status::Running) => {
-                        task.pause();
-                    }
-                    Ok(task::Status::Terminated(task::ExitCode::Success)) => {
-                        task.state = task::State::Terminated;
-                        task.exit_code = Some(task::ExitCode::Success);
-                        let now = SystemTime::now();
-                        task.duration += now
-                            .duration_since(task.get_date_time_created())
-                            .unwrap()
-                            .as_secs_f64();
-                        task.print();
-                    }
-                    Ok(task::Status::Terminated(task::ExitCode::Failure)) => {
-                        task.state = task::State::Terminated;
-                        task.exit_code = Some(task::ExitCode::Failure);
-                        let now = SystemTime::now();
-                        task.duration += now
-                            .duration_since(task.get_date_time_created())
-                            .unwrap()
-                            .as_secs_f64();
-                        task.print();
-                    }
-                    Err(err) => {
-                        task.state = task::State::Terminated;
-                        task.exit_code = Some(task::ExitCode::Failure);
-                        let now = SystemTime::now();
-                        task.duration += now
-                            .duration_since(task.get_date_time_created())
-                            .unwrap()
-                            .as_secs_f64();
-                        task.print_with_error(&err);
-                    }
+    rr::stress::print_report(&simulator.stats(), started.elapsed());
+}
+
+fn preset(preset: Preset, options: SchedulerOptions, cache_warmth: Option<u32>) {
+    let quantum = options.quantum;
+    let (scenario, executor) = preset.workload(quantum);
+    let (control_tx, control_rx) = mpsc::channel();
+    control::spawn_stdin_reader(control_tx);
+
+    let mut simulator = options
+        .apply(Simulator::new(Vec::new(), control_rx))
+        .executor(with_cache_warmth(executor, cache_warmth))
+        .clock(ManualClock::new())
+        .scenario(scenario)
+        .record_trace();
+    simulator.run();
+
+    preset.print_report(simulator.tasks(), simulator.events(), quantum);
+}
+
+fn import(path: PathBuf, options: SchedulerOptions, cache_warmth: Option<u32>) {
+    let samples = match rr::import::load(&path) {
+        Ok(samples) => samples,
+        Err(e) => {
+            println!("Failed to import {}", e);
+            std::process::exit(1);
+        }
+    };
+    let (tasks, executor) = rr::import::workload(samples, &options.nice);
+    println!("Imported {} processes from {}", tasks.len(), path.display());
+    let (control_tx, control_rx) = mpsc::channel();
+    control::spawn_stdin_reader(control_tx);
+
+    options
+        .apply(Simulator::new(tasks, control_rx))
+        .executor(with_cache_warmth(executor, cache_warmth))
+        .clock(ManualClock::new())
+        .record_trace()
+        .run();
+}
+
+#[derive(clap::Args)]
+struct Integrations {
+    #[arg(long = "webhook", value_name = "URL")]
+    webhooks: Vec<String>,
+
+    #[cfg(all(unix, feature = "dbus"))]
+    #[arg(long, value_enum, value_name = "BUS")]
+    dbus: Option<rr::dbus::Bus>,
+}
+
+impl Integrations {
+    fn apply(
+        self,
+        simulator: Simulator,
+        send: impl Fn(control::Message) -> bool + Send + Sync + 'static,
+    ) -> Simulator {
+        let simulator = simulator.webhooks(self.webhooks);
+        #[cfg(all(unix, feature = "dbus"))]
+        if let Some(bus) = self.dbus {
+            return match rr::dbus::serve(bus, send) {
+                Ok(service) => {
+                    println!("Registered {} on the {} bus", rr::dbus::NAME, bus);
+                    simulator.dbus(service)
+                }
+                Err(err) => {
+                    println!(
+                        "Failed to register {} on the {} bus: {}",
+                        rr::dbus::NAME,
+                        bus,
+                        err
+                    );
+                    std::process::exit(1);
                 }
+            };
+        }
+        let _ = send;
+        simulator
+    }
+}
+
+fn daemon(
+    socket: PathBuf,
+    db: PathBuf,
+    limits: Limits,
+    integrations: Integrations,
+    options: SchedulerOptions,
+    watch: Option<config::Watch>,
+) {
+    let store = match Store::open(&db) {
+        Ok(store) => store,
+        Err(err) => {
+            println!("Failed to open database {}: {}", db.display(), err);
+            std::process::exit(1);
+        }
+    };
+
+    let tasks = match store.load_unfinished() {
+        Ok(tasks) => tasks,
+        Err(err) => {
+            println!("Failed to load queued tasks from {}: {}", db.display(), err);
+            std::process::exit(1);
+        }
+    };
+
+    #[cfg(feature = "async")]
+    if options.async_runtime {
+        let (_sync_tx, sync_rx) = mpsc::channel();
+        let (control_tx, control_rx) = tokio::sync::mpsc::unbounded_channel();
+        let bus_tx = control_tx.clone();
+        let simulator = options
+            .apply(Simulator::new(tasks, sync_rx))
+            .with_store(store)
+            .limits(limits);
+        let mut simulator = integrations
+            .apply(simulator, move |message| bus_tx.send(message).is_ok())
+            .watch_config(watch)
+            .daemon(true);
+        block_on(async {
+            options.listen_fifo(|path| control::spawn_fifo_reader_async(path, control_tx.clone()));
+            if let Err(err) = control::spawn_socket_listener_async(&socket, control_tx) {
+                println!("Failed to listen on {}: {}", socket.display(), err);
+                std::process::exit(1);
             }
 
-            if task.state != task::State::Terminated {
-                all_done = false;
+            println!(
+                "Daemon listening on {} with {} queued task(s)",
+                socket.display(),
+                simulator.stats().tasks
+            );
+            simulator.run_async(control_rx).await;
+        });
+        let _ = std::fs::remove_file(&socket);
+        return;
+    }
+
+    let (control_tx, control_rx) = mpsc::channel();
+    let bus_tx = control_tx.clone();
+    options.listen_fifo(|path| control::spawn_fifo_reader(path, control_tx.clone()));
+    if let Err(err) = control::spawn_socket_listener(&socket, control_tx) {
+        println!("Failed to listen on {}: {}", socket.display(), err);
+        std::process::exit(1);
+    }
+
+    println!(
+        "Daemon listening on {} with {} queued task(s)",
+        socket.display(),
+        tasks.len()
+    );
+
+    let simulator = options
+        .apply(Simulator::new(tasks, control_rx))
+        .with_store(store)
+        .limits(limits);
+    integrations
+        .apply(simulator, move |message| bus_tx.send(message).is_ok())
+        .watch_config(watch)
+        .daemon(true)
+        .run();
+
+    let _ = std::fs::remove_file(&socket);
+}
+
+#[cfg(unix)]
+fn adopt(pids: Vec<u32>, priority: u8, options: SchedulerOptions) {
+    let mut executor = AdoptedExecutor::new();
+    let mut tasks: Vec<Task> = Vec::new();
+
+    for &pid in &pids {
+        match executor.adopt(Pid::from_raw(pid), priority) {
+            Ok(task) => {
+                println!(
+                    "Adopted process {} ({}) as PID: {}",
+                    pid,
+                    task.get_path_to_binary().display(),
+                    task.get_id()
+                );
+                tasks.push(task);
             }
+            Err(err) => {
+                println!("Failed to adopt process {}: {}", pid, err);
+                for task in &mut tasks {
+                    task.kill(&mut executor);
+                }
+                std::process::exit(1);
+            }
+        }
+    }
+
+    AdoptedExecutor::release_on_signal(pids.into_iter().map(Pid::from_raw).collect());
+
+    let (control_tx, control_rx) = mpsc::channel();
+    control::spawn_stdin_reader(control_tx);
+
+    options
+        .apply(Simulator::new(tasks, control_rx))
+        .executor(executor)
+        .run();
+}
+
+fn history(db: PathBuf, since: Option<f64>, state: Option<history::StateFilter>, json: bool) {
+    if !db.exists() {
+        println!("No history database at {}", db.display());
+        std::process::exit(1);
+    }
+
+    let records = match Store::open(&db).and_then(|store| store.records_since(since.unwrap_or(0.0)))
+    {
+        Ok(records) => history::filter(records, state),
+        Err(err) => {
+            println!("Failed to read history from {}: {}", db.display(), err);
+            std::process::exit(1);
+        }
+    };
+
+    if json {
+        if let Err(err) = history::print_json(&records) {
+            println!("Failed to encode history: {}", err);
+            std::process::exit(1);
+        }
+    } else {
+        history::print_table(&records);
+    }
+}
+
+fn dashboard(out: &Path) {
+    let json = serde_json::to_string_pretty(&metrics::dashboard::dashboard())
+        .expect("the dashboard is plain JSON");
+
+    match std::fs::write(out, json + "\n") {
+        Ok(()) => println!("Dashboard written to {}", out.display()),
+        Err(err) => {
+            println!("Failed to write {}: {}", out.display(), err);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn doctor() {
+    let checks = rr::doctor::run();
+    rr::doctor::print(&checks);
+    if checks
+        .iter()
+        .any(|check| check.status == rr::doctor::Status::Failed)
+    {
+        std::process::exit(1);
+    }
+}
+
+fn sweep(
+    quantum: &str,
+    policies: &[scheduler::Policy],
+    workload: &Path,
+    seed: u64,
+    repeat: usize,
+    out: Option<&Path>,
+) {
+    let quanta = match sweep::parse_quanta(quantum) {
+        Ok(quanta) => quanta,
+        Err(err) => {
+            println!("Invalid --quantum: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let workload = match sweep::Workload::load(workload) {
+        Ok(workload) => workload,
+        Err(err) => {
+            println!("Failed to load workload {}: {}", workload.display(), err);
+            std::process::exit(1);
         }
+    };
 
-        if all_done {
-            break;
+    rr::output::set_quiet(true);
+    let rows = sweep::sweep(&workload, policies, &quanta, seed, repeat);
+    let csv = match repeat {
+        1 => sweep::csv(&rows),
+        repeat => sweep::summary_csv(&rows, repeat),
+    };
+    let Some(out) = out else {
+        print!("{}", csv);
+        return;
+    };
+    match std::fs::write(out, csv) {
+        Ok(()) => println!(
+            "Sweep of {} configurations and {} runs written to {}",
+            policies.len() * quanta.len(),
+            rows.len(),
+            out.display()
+        ),
+        Err(err) => {
+            println!("Failed to write {}: {}", out.display(), err);
+            std::process::exit(1);
         }
     }
+}
+
+fn send(socket: &Path, line: &str) {
+    if let Err(err) = control::send(socket, line) {
+        println!("Failed to reach daemon on {}: {}", socket.display(), err);
+        std::process::exit(1);
+    }
+}
 
-    for _ in 0..tasks.len() {
-        let _ = rx.recv().unwrap();
+fn main() {
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    let (config, profile) = (args.config.as_deref(), args.profile.as_deref());
+    let seed = match &mut args.command {
+        None => {
+            args.options.configure(&matches, config, profile);
+            args.options.seed
+        }
+        Some(Command::Daemon { options, .. }) => {
+            options.configure(matches.subcommand().unwrap().1, config, profile);
+            options.seed
+        }
+        #[cfg(unix)]
+        Some(Command::Adopt { options, .. }) => {
+            options.configure(matches.subcommand().unwrap().1, config, profile);
+            options.seed
+        }
+        Some(_) => None,
+    };
+    if let Some(seed) = seed {
+        task::seed_ids(seed);
     }
 
-    println!("All tasks completed!");
+    match args.command {
+        None => match (args.stress, args.preset, args.import) {
+            (Some(n), _, _) => stress(n, args.options, args.cache_warmth),
+            (None, Some(name), _) => preset(name, args.options, args.cache_warmth),
+            (None, None, Some(path)) => import(path, args.options, args.cache_warmth),
+            (None, None, None) if args.cache_warmth.is_some() => {
+                println!(
+                    "--cache-warmth only applies to --stress, --preset and --import workloads"
+                );
+                std::process::exit(1);
+            }
+            (None, None, None) => run(
+                args.resume,
+                args.scenario,
+                args.tasks,
+                args.stdin_tasks,
+                args.options,
+            ),
+        },
+        Some(Command::Daemon {
+            socket,
+            db,
+            limits,
+            integrations,
+            options,
+        }) => daemon(
+            socket,
+            db,
+            limits,
+            integrations,
+            options,
+            watch_config(args.config.as_deref(), args.profile.as_deref()),
+        ),
+        #[cfg(unix)]
+        Some(Command::Adopt {
+            pids,
+            priority,
+            options,
+        }) => adopt(pids, priority, options),
+        Some(Command::Submit {
+            socket,
+            name,
+            metadata,
+            priority,
+            width,
+            estimate,
+            quantum,
+            deadline,
+            on_miss,
+            period,
+            jobs,
+            after,
+            apart_from,
+            near,
+            gang,
+            qos,
+            share,
+            cpu_quota,
+            container,
+            runtime,
+            cpus,
+            memory,
+            host,
+            run_as,
+            sandbox,
+            path_to_binary,
+            args,
+        }) => {
+            let mut words = vec![
+                "submit".to_string(),
+                "--priority".to_string(),
+                priority.to_string(),
+                "--width".to_string(),
+                width.to_string(),
+            ];
+            if let Some(name) = name {
+                words.extend(["--name".to_string(), name]);
+            }
+            for (key, value) in metadata {
+                words.extend(["--meta".to_string(), format!("{}={}", key, value)]);
+            }
+            if let Some(estimate) = estimate {
+                words.extend(["--estimate".to_string(), estimate.to_string()]);
+            }
+            if let Some(quantum) = quantum {
+                words.extend(["--quantum".to_string(), quantum.to_string()]);
+            }
+            if let Some(deadline) = deadline {
+                words.extend(["--deadline".to_string(), deadline.to_string()]);
+            }
+            if let Some(on_miss) = on_miss {
+                words.extend(["--on-miss".to_string(), on_miss.to_string()]);
+            }
+            if let Some(period) = period {
+                words.extend(["--period".to_string(), period.to_string()]);
+            }
+            if let Some(jobs) = jobs {
+                words.extend(["--jobs".to_string(), jobs.to_string()]);
+            }
+            for after in after {
+                words.extend(["--after".to_string(), after]);
+            }
+            for apart_from in apart_from {
+                words.extend(["--apart-from".to_string(), apart_from]);
+            }
+            if let Some(near) = near {
+                words.extend(["--near".to_string(), near]);
+            }
+            if let Some(gang) = gang {
+                words.extend(["--gang".to_string(), gang]);
+            }
+            if let Some(qos) = qos {
+                words.extend(["--qos".to_string(), qos.to_string()]);
+            }
+            if let Some(share) = share {
+                words.extend(["--share".to_string(), share.to_string()]);
+            }
+            if let Some(cpu_quota) = cpu_quota {
+                words.extend(["--cpu-quota".to_string(), cpu_quota.to_string()]);
+            }
+            if let Some(container) = container {
+                words.extend(["--container".to_string(), container]);
+            }
+            if let Some(runtime) = runtime {
+                words.extend(["--runtime".to_string(), runtime.program().to_string()]);
+            }
+            if let Some(cpus) = cpus {
+                words.extend(["--cpus".to_string(), cpus.to_string()]);
+            }
+            if let Some(memory) = memory {
+                words.extend(["--memory".to_string(), memory]);
+            }
+            if let Some(host) = host {
+                words.extend(["--host".to_string(), host]);
+            }
+            if let Some(run_as) = run_as {
+                words.extend(["--run-as".to_string(), run_as]);
+            }
+            if let Some(sandbox) = sandbox {
+                words.extend(["--sandbox".to_string(), sandbox.to_string()]);
+            }
+            words.extend(["--".to_string(), path_to_binary]);
+            words.extend(args);
+            send(&socket, &control::quote_words(&words));
+        }
+        Some(Command::Control { socket, command }) => {
+            send(&socket, &control::quote_words(&command))
+        }
+        Some(Command::History {
+            db,
+            since,
+            state,
+            json,
+        }) => history(db, since, state, json),
+        Some(Command::Dashboard { out }) => dashboard(&out),
+        Some(Command::Sweep {
+            quantum,
+            policy,
+            workload,
+            seed,
+            repeat,
+            out,
+        }) => sweep(
+            &quantum.join(" "),
+            &policy,
+            &workload,
+            seed,
+            repeat as usize,
+            out.as_deref(),
+        ),
+        #[cfg(unix)]
+        Some(Command::Doctor) => doctor(),
+        Some(Command::Diff { before, after }) => {
+            if let Err(err) = diff::print(&before, &after) {
+                println!("{}", err);
+                std::process::exit(1);
+            }
+        }
+    }
 }
@@ -0,0 +1,83 @@
+use super::{
+    COMPLETIONS, CONCURRENCY, DISPATCHES, DISPATCH_SECONDS, QUANTA, RUN_QUEUE_LENGTH, TASKS,
+};
+use serde_json::{json, Value};
+
+const DATASOURCE: &str = "${DS_PROMETHEUS}";
+
+fn panel(id: u32, title: &str, x: u32, y: u32, targets: &[(&str, &str)]) -> Value {
+    let targets: Vec<Value> = targets
+        .iter()
+        .zip('A'..)
+        .map(|(&(expr, legend), ref_id)| {
+            json!({
+                "datasource": { "type": "prometheus", "uid": DATASOURCE },
+                "expr": expr,
+                "legendFormat": legend,
+                "refId": ref_id.to_string(),
+            })
+        })
+        .collect();
+
+    json!({
+        "id": id,
+        "type": "timeseries",
+        "title": title,
+        "datasource": { "type": "prometheus", "uid": DATASOURCE },
+        "gridPos": { "h": 8, "w": 12, "x": x, "y": y },
+        "targets": targets,
+    })
+}
+
+pub fn dashboard() -> Value {
+    let tasks = format!("sum by (state) ({})", TASKS);
+    let queue = RUN_QUEUE_LENGTH.to_string();
+    let slots = CONCURRENCY.to_string();
+    let dispatches = format!("rate({}[1m])", DISPATCHES);
+    let quanta = format!("rate({}[1m])", QUANTA);
+    let completions = format!("sum by (outcome) (rate({}[5m]))", COMPLETIONS);
+    let overhead = format!(
+        "rate({}[1m]) / clamp_min(rate({}[1m]), 1e-9)",
+        DISPATCH_SECONDS, QUANTA
+    );
+
+    json!({
+        "title": "psched",
+        "uid": "psched",
+        "schemaVersion": 39,
+        "time": { "from": "now-1h", "to": "now" },
+        "refresh": "10s",
+        "__inputs": [{
+            "name": "DS_PROMETHEUS",
+            "label": "Prometheus",
+            "type": "datasource",
+            "pluginId": "prometheus",
+            "pluginName": "Prometheus",
+        }],
+        "panels": [
+            panel(1, "Tasks by state", 0, 0, &[(&tasks, "{{state}}")]),
+            panel(
+                2,
+                "Run queue and slots",
+                12,
+                0,
+                &[(&queue, "ready"), (&slots, "slots")],
+            ),
+            panel(
+                3,
+                "Dispatches and quanta per second",
+                0,
+                8,
+                &[(&dispatches, "dispatches"), (&quanta, "quanta")],
+            ),
+            panel(4, "Completions per second", 12, 8, &[(&completions, "{{outcome}}")]),
+            panel(
+                5,
+                "Dispatcher seconds per quantum",
+                0,
+                16,
+                &[(&overhead, "overhead")],
+            ),
+        ],
+    })
+}
@@ -0,0 +1,353 @@
+use crate::output;
+use crate::registry::Registry;
+use crate::task::{BlockReason, ExitCode, State, Task, TaskSnapshot};
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use ulid::Ulid;
+
+pub mod dashboard;
+
+pub const TASKS: &str = "psched_tasks";
+pub const RUN_QUEUE_LENGTH: &str = "psched_run_queue_length";
+pub const CONCURRENCY: &str = "psched_concurrency";
+pub const QUANTA: &str = "psched_quanta_total";
+pub const DISPATCHES: &str = "psched_dispatches_total";
+pub const COMPLETIONS: &str = "psched_task_completions_total";
+pub const DISPATCH_SECONDS: &str = "psched_dispatch_seconds_total";
+
+const RANKED: usize = 3;
+
+const STATES: [State; 9] = [
+    State::New,
+    State::Ready,
+    State::Running,
+    State::Waiting,
+    State::Blocked(BlockReason::Io),
+    State::Blocked(BlockReason::Resource),
+    State::Blocked(BlockReason::Dependency),
+    State::Suspended,
+    State::Terminated,
+];
+
+pub struct Snapshot {
+    pub tasks: [usize; STATES.len()],
+    pub succeeded: usize,
+    pub failed: usize,
+    pub run_queue_length: usize,
+    pub concurrency: usize,
+    pub quanta: u64,
+    pub dispatches: u64,
+    pub dispatch_time: Duration,
+}
+
+impl Snapshot {
+    pub fn new(tasks: &Registry) -> Self {
+        let mut snapshot = Snapshot {
+            tasks: [0; STATES.len()],
+            succeeded: 0,
+            failed: 0,
+            run_queue_length: 0,
+            concurrency: 0,
+            quanta: 0,
+            dispatches: 0,
+            dispatch_time: Duration::ZERO,
+        };
+
+        for task in tasks.iter() {
+            if let Some(i) = STATES.iter().position(|s| *s == task.state) {
+                snapshot.tasks[i] += 1;
+            }
+            match (task.state, task.exit_code) {
+                (State::Terminated, Some(ExitCode::Success)) => snapshot.succeeded += 1,
+                (State::Terminated, _) => snapshot.failed += 1,
+                _ => {}
+            }
+        }
+
+        snapshot
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            "# HELP {} Tasks known to the scheduler by state.",
+            TASKS
+        );
+        let _ = writeln!(out, "# TYPE {} gauge", TASKS);
+        for (state, count) in STATES.iter().zip(self.tasks) {
+            let state = state.to_string().to_lowercase();
+            let _ = writeln!(out, "{}{{state=\"{}\"}} {}", TASKS, state, count);
+        }
+
+        let gauges = [
+            (
+                RUN_QUEUE_LENGTH,
+                "Ready tasks waiting for a slot.",
+                self.run_queue_length,
+            ),
+            (
+                CONCURRENCY,
+                "Slots available per quantum.",
+                self.concurrency,
+            ),
+        ];
+        for (name, help, value) in gauges {
+            let _ = writeln!(out, "# HELP {} {}\n# TYPE {} gauge", name, help, name);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+
+        let counters = [
+            (QUANTA, "Quanta elapsed.", self.quanta),
+            (DISPATCHES, "Dispatch decisions made.", self.dispatches),
+        ];
+        for (name, help, value) in counters {
+            let _ = writeln!(out, "# HELP {} {}\n# TYPE {} counter", name, help, name);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP {} Tasks that terminated, by outcome.\n# TYPE {} counter",
+            COMPLETIONS, COMPLETIONS
+        );
+        let _ = writeln!(
+            out,
+            "{}{{outcome=\"success\"}} {}",
+            COMPLETIONS, self.succeeded
+        );
+        let _ = writeln!(
+            out,
+            "{}{{outcome=\"failure\"}} {}",
+            COMPLETIONS, self.failed
+        );
+
+        let _ = writeln!(
+            out,
+            "# HELP {} Time spent choosing tasks.\n# TYPE {} counter",
+            DISPATCH_SECONDS, DISPATCH_SECONDS
+        );
+        let _ = writeln!(
+            out,
+            "{} {}",
+            DISPATCH_SECONDS,
+            self.dispatch_time.as_secs_f64()
+        );
+
+        out
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Ranking {
+    pub title: &'static str,
+    pub leaders: Vec<(String, String)>,
+}
+
+fn rank(
+    tasks: &Registry,
+    title: &'static str,
+    key: impl Fn(&Task) -> u64,
+    show: impl Fn(u64) -> String,
+) -> Ranking {
+    let mut ranked: Vec<(&Task, u64)> = tasks
+        .iter()
+        .map(|task| (task, key(task)))
+        .filter(|&(_, value)| value > 0)
+        .collect();
+    ranked.sort_by_key(|&(_, value)| std::cmp::Reverse(value));
+    ranked.truncate(RANKED);
+
+    Ranking {
+        title,
+        leaders: ranked
+            .into_iter()
+            .map(|(task, value)| (task.name(), show(value)))
+            .collect(),
+    }
+}
+
+pub fn rankings(
+    tasks: &Registry,
+    quantum: Duration,
+    migrations: impl Fn(Ulid) -> u64,
+) -> Vec<Ranking> {
+    let seconds = |micros: u64| format!("{:.2}s", micros as f64 / 1e6);
+    vec![
+        rank(
+            tasks,
+            "CPU Hogs",
+            |task| task.cpu_time.as_micros() as u64,
+            seconds,
+        ),
+        rank(
+            tasks,
+            "Longest Waits",
+            |task| (quantum * task.waited as u32).as_micros() as u64,
+            seconds,
+        ),
+        rank(
+            tasks,
+            "Most Preempted",
+            |task| task.preemptions.into(),
+            |n| n.to_string(),
+        ),
+        rank(
+            tasks,
+            "Most Migrated",
+            |task| migrations(task.get_id()),
+            |n| n.to_string(),
+        ),
+    ]
+}
+
+pub fn print_rankings(rankings: &[Ranking]) {
+    if rankings.iter().all(|ranking| ranking.leaders.len() < 2) {
+        return;
+    }
+    let fields: Vec<(&str, String)> = rankings
+        .iter()
+        .map(|ranking| {
+            let leaders: Vec<String> = ranking
+                .leaders
+                .iter()
+                .map(|(name, value)| format!("{} ({})", name, value))
+                .collect();
+            if leaders.is_empty() {
+                (ranking.title, "-".to_string())
+            } else {
+                (ranking.title, leaders.join(", "))
+            }
+        })
+        .collect();
+    crate::report!("{}", output::boxed(Some("RANKINGS"), &fields));
+}
+
+#[derive(Default)]
+struct Published {
+    metrics: String,
+    tasks: Vec<TaskSnapshot>,
+}
+
+pub struct Exporter {
+    latest: Arc<Mutex<Published>>,
+}
+
+fn serve_scrape(stream: TcpStream, latest: &Mutex<Published>) -> io::Result<()> {
+    let mut request = String::new();
+    BufReader::new(&stream).read_line(&mut request)?;
+    let mut stream = stream;
+
+    let (content_type, body) = match request.split_whitespace().nth(1) {
+        Some("/metrics") => (
+            "text/plain; version=0.0.4",
+            latest.lock().unwrap().metrics.clone(),
+        ),
+        Some("/tasks") => {
+            let tasks = latest.lock().unwrap().tasks.clone();
+            ("application/json", serde_json::to_string(&tasks)?)
+        }
+        _ => {
+            return write!(
+                stream,
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            )
+        }
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\n\
+         Content-Type: {}\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+impl Exporter {
+    pub fn serve(address: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let latest = Arc::new(Mutex::new(Published::default()));
+
+        let shared = Arc::clone(&latest);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = serve_scrape(stream, &shared);
+            }
+        });
+
+        Ok(Self { latest })
+    }
+
+    pub fn publish(&self, snapshot: &Snapshot, tasks: Vec<TaskSnapshot>) {
+        *self.latest.lock().unwrap() = Published {
+            metrics: snapshot.render(),
+            tasks,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_the_exposition_format() {
+        let mut tasks = Registry::default();
+        let mut done = Task::new("/bin/ls", None, 1);
+        done.set_state(State::Terminated);
+        done.exit_code = Some(ExitCode::Failure);
+        tasks.insert(done);
+        tasks.insert(Task::new("/bin/echo", None, 2));
+
+        let snapshot = Snapshot {
+            quanta: 4,
+            ..Snapshot::new(&tasks)
+        };
+        let text = snapshot.render();
+
+        assert!(text.contains("psched_tasks{state=\"new\"} 1\n"));
+        assert!(text.contains("psched_tasks{state=\"terminated\"} 1\n"));
+        assert!(text.contains("psched_task_completions_total{outcome=\"failure\"} 1\n"));
+        assert!(text.contains("# TYPE psched_quanta_total counter\npsched_quanta_total 4\n"));
+    }
+
+    #[test]
+    fn rankings_list_the_top_tasks_and_skip_idle_ones() {
+        let mut tasks = Registry::default();
+        for (name, cpu, waited, preemptions) in [
+            ("light", 100, 9, 0),
+            ("hog", 900, 1, 4),
+            ("idle", 0, 0, 0),
+            ("medium", 400, 5, 4),
+            ("small", 200, 2, 1),
+        ] {
+            let mut task = Task::new(name, None, 1);
+            task.cpu_time = Duration::from_millis(cpu);
+            task.waited = waited;
+            task.preemptions = preemptions;
+            tasks.insert(task);
+        }
+
+        let rankings = rankings(&tasks, Duration::from_millis(100), |_| 0);
+        let leaders = |n: usize| -> Vec<String> {
+            rankings[n]
+                .leaders
+                .iter()
+                .map(|(name, value)| format!("{} {}", name, value))
+                .collect()
+        };
+        assert_eq!(leaders(0), ["hog 0.90s", "medium 0.40s", "small 0.20s"]);
+        assert_eq!(leaders(1), ["light 0.90s", "medium 0.50s", "small 0.20s"]);
+        assert_eq!(leaders(2), ["hog 4", "medium 4", "small 1"]);
+        assert!(leaders(3).is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+
+pub const MIN: i32 = -20;
+pub const MAX: i32 = 19;
+const PRIORITIES: u8 = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NiceTable {
+    levels: BTreeMap<u8, i32>,
+}
+
+impl Default for NiceTable {
+    fn default() -> Self {
+        Self {
+            levels: (0..PRIORITIES)
+                .map(|priority| (priority, i32::from(priority) * 5 + MIN))
+                .collect(),
+        }
+    }
+}
+
+impl NiceTable {
+    pub fn from_config(table: &BTreeMap<String, i32>) -> Result<Self, String> {
+        let mut levels = BTreeMap::new();
+        for (priority, &nice) in table {
+            let priority: u8 = priority
+                .parse()
+                .map_err(|_| format!("{} is not a priority", priority))?;
+            if !(MIN..=MAX).contains(&nice) {
+                return Err(format!(
+                    "nice {} for priority {} is outside {}..={}",
+                    nice, priority, MIN, MAX
+                ));
+            }
+            levels.insert(priority, nice);
+        }
+        if levels.is_empty() {
+            return Err("the nice table is empty".to_string());
+        }
+        Ok(Self { levels })
+    }
+
+    pub fn nice(&self, priority: u8) -> i32 {
+        self.levels
+            .range(..=priority)
+            .next_back()
+            .or_else(|| self.levels.iter().next())
+            .map_or(0, |(_, &nice)| nice)
+    }
+
+    pub fn priority(&self, nice: i64) -> u8 {
+        self.levels
+            .iter()
+            .min_by_key(|&(_, &level)| (i64::from(level) - nice).abs())
+            .map_or(0, |(&priority, _)| priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priorities_map_through_the_table_in_both_directions() {
+        let table = NiceTable::default();
+        assert_eq!((table.nice(0), table.nice(3), table.nice(7)), (-20, -5, 15));
+        assert_eq!(
+            (table.priority(-10), table.priority(0), table.priority(19)),
+            (2, 4, 7)
+        );
+
+        let config: BTreeMap<String, i32> = [("1", -5), ("3", 0), ("6", 10)]
+            .map(|(priority, nice)| (priority.to_string(), nice))
+            .into();
+        let table = NiceTable::from_config(&config).unwrap();
+        assert_eq!(
+            [0, 1, 2, 3, 5, 6, 9].map(|priority| table.nice(priority)),
+            [-5, -5, -5, 0, 0, 10, 10]
+        );
+        assert_eq!((table.priority(-20), table.priority(4)), (1, 3));
+
+        let too_low: BTreeMap<String, i32> = [("0".to_string(), -21)].into();
+        assert!(NiceTable::from_config(&too_low).is_err());
+        assert!(NiceTable::from_config(&[("high".to_string(), 0)].into()).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+use crate::clock::{SystemTime, UNIX_EPOCH};
+use crate::registry::Registry;
+use crate::task::ExitCode;
+use crate::trace::{Event, Kind};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+use ulid::Ulid;
+
+const SERVICE_NAME: &str = "psched";
+const DEFAULT_PATH: &str = "/v1/traces";
+const STATUS_OK: u8 = 1;
+const STATUS_ERROR: u8 = 2;
+
+struct Timeline {
+    started: u128,
+    quantum: u128,
+}
+
+impl Timeline {
+    fn at(&self, quantum: u64) -> String {
+        (self.started + self.quantum * u128::from(quantum)).to_string()
+    }
+}
+
+fn attribute(key: &str, value: Value) -> Value {
+    let value = match value {
+        Value::String(s) => json!({ "stringValue": s }),
+        Value::Bool(b) => json!({ "boolValue": b }),
+        other => json!({ "intValue": other.to_string() }),
+    };
+    json!({ "key": key, "value": value })
+}
+
+fn span_id(id: u64) -> String {
+    format!("{:016x}", id)
+}
+
+struct TaskSpan {
+    start: u64,
+    end: Option<u64>,
+    exit_code: Option<ExitCode>,
+    events: Vec<Value>,
+    runs: Vec<(u64, Option<u64>, bool)>,
+}
+
+pub fn spans(
+    events: &[Event],
+    tasks: &Registry,
+    started: SystemTime,
+    quantum: Duration,
+    trace_id: u128,
+) -> Value {
+    let timeline = Timeline {
+        started: started
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos()),
+        quantum: quantum.as_nanos(),
+    };
+
+    let mut order: Vec<Ulid> = Vec::new();
+    let mut open: HashMap<Ulid, TaskSpan> = HashMap::new();
+    for event in events {
+        let span = open.entry(event.task).or_insert_with(|| {
+            order.push(event.task);
+            TaskSpan {
+                start: event.quantum,
+                end: None,
+                exit_code: None,
+                events: Vec::new(),
+                runs: Vec::new(),
+            }
+        });
+
+        let dispatched_at = event.quantum.saturating_sub(1);
+        let time = match event.kind {
+            Kind::Dispatched { .. } => dispatched_at,
+            _ => event.quantum,
+        };
+        span.events.push(json!({
+            "timeUnixNano": timeline.at(time),
+            "name": event.kind.to_string(),
+        }));
+
+        match event.kind {
+            Kind::Admitted | Kind::Resumed | Kind::Throttled | Kind::Unthrottled => {}
+            Kind::Dispatched { backfilled } => span.runs.push((dispatched_at, None, backfilled)),
+            Kind::Preempted | Kind::Suspended | Kind::Terminated { .. } => {
+                if let Some(run) = span.runs.last_mut().filter(|run| run.1.is_none()) {
+                    run.1 = Some(event.quantum);
+                }
+                if let Kind::Terminated { exit_code } = event.kind {
+                    span.end = Some(event.quantum);
+                    span.exit_code = exit_code;
+                }
+            }
+        }
+    }
+
+    let last = events.last().map_or(0, |event| event.quantum);
+    let trace_id = format!("{:032x}", trace_id);
+    let mut out = Vec::new();
+    for id in order {
+        let span = &open[&id];
+        let task = &tasks[id];
+        let task_span_id = span_id(id.0 as u64);
+        let status = match span.exit_code {
+            Some(ExitCode::Success) => STATUS_OK,
+            Some(ExitCode::Failure | ExitCode::Poisoned) => STATUS_ERROR,
+            None => 0,
+        };
+
+        let mut attributes = vec![
+            attribute("psched.task.id", Value::String(id.to_string())),
+            attribute("psched.task.priority", json!(task.priority)),
+            attribute("psched.task.width", json!(task.get_spec().width)),
+        ];
+        for (key, value) in &task.get_spec().metadata {
+            attributes.push(attribute(
+                &format!("psched.task.metadata.{}", key),
+                Value::String(value.clone()),
+            ));
+        }
+        out.push(json!({
+            "traceId": trace_id,
+            "spanId": task_span_id,
+            "name": task.name(),
+            "kind": 1,
+            "startTimeUnixNano": timeline.at(span.start),
+            "endTimeUnixNano": timeline.at(span.end.unwrap_or(last)),
+            "attributes": attributes,
+            "events": span.events,
+            "status": { "code": status },
+        }));
+
+        for (n, &(start, end, backfilled)) in span.runs.iter().enumerate() {
+            out.push(json!({
+                "traceId": trace_id,
+                "spanId": span_id(((id.0 >> 64) as u64) ^ (n as u64 + 1)),
+                "parentSpanId": task_span_id,
+                "name": "run",
+                "kind": 1,
+                "startTimeUnixNano": timeline.at(start),
+                "endTimeUnixNano": timeline.at(end.unwrap_or(last)),
+                "attributes": [
+                    attribute("psched.quantum", json!(start + 1)),
+                    attribute("psched.backfilled", Value::Bool(backfilled)),
+                ],
+            }));
+        }
+    }
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [attribute("service.name", Value::String(SERVICE_NAME.to_string()))],
+            },
+            "scopeSpans": [{
+                "scope": { "name": SERVICE_NAME },
+                "spans": out,
+            }],
+        }],
+    })
+}
+
+fn split_endpoint<'a>(endpoint: &'a str, default_path: &'a str) -> io::Result<(&'a str, &'a str)> {
+    let Some(rest) = endpoint.strip_prefix("http://") else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unsupported endpoint {} (expected http://)", endpoint),
+        ));
+    };
+
+    Ok(match rest.find('/') {
+        Some(slash) if slash + 1 < rest.len() => (&rest[..slash], &rest[slash..]),
+        Some(slash) => (&rest[..slash], default_path),
+        None => (rest, default_path),
+    })
+}
+
+pub fn export(endpoint: &str, body: &Value) -> io::Result<()> {
+    post(endpoint, DEFAULT_PATH, &body.to_string())
+}
+
+pub fn post(endpoint: &str, default_path: &str, body: &str) -> io::Result<()> {
+    let (host, path) = split_endpoint(endpoint, default_path)?;
+
+    let mut stream = TcpStream::connect(host)?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\n\
+         Host: {}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        path,
+        host,
+        body.len(),
+        body
+    )?;
+
+    let mut status = String::new();
+    BufReader::new(stream).read_line(&mut status)?;
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        _ => Err(io::Error::other(format!(
+            "server answered {}",
+            status.trim()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Task;
+
+    #[test]
+    fn each_run_interval_is_a_child_of_its_task() {
+        let mut tasks = Registry::default();
+        let id = tasks.insert(Task::new("/bin/ls", None, 2));
+        let event = |quantum, kind| Event {
+            quantum,
+            task: id,
+            kind,
+        };
+        let events = [
+            event(0, Kind::Admitted),
+            event(1, Kind::Dispatched { backfilled: false }),
+            event(1, Kind::Preempted),
+            event(2, Kind::Dispatched { backfilled: false }),
+            event(
+                2,
+                Kind::Terminated {
+                    exit_code: Some(ExitCode::Success),
+                },
+            ),
+        ];
+
+        let started = UNIX_EPOCH + Duration::from_secs(1);
+        let body = spans(&events, &tasks, started, Duration::from_millis(150), 7);
+        let spans = body["resourceSpans"][0]["scopeSpans"][0]["spans"]
+            .as_array()
+            .unwrap();
+
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[0]["traceId"], format!("{:032x}", 7));
+        assert_eq!(spans[0]["endTimeUnixNano"], "1300000000");
+        assert_eq!(spans[0]["events"].as_array().unwrap().len(), 5);
+        assert_eq!(spans[0]["status"]["code"], STATUS_OK);
+        for (run, (start, end)) in spans[1..]
+            .iter()
+            .zip([("1000000000", "1150000000"), ("1150000000", "1300000000")])
+        {
+            assert_eq!(run["parentSpanId"], spans[0]["spanId"]);
+            assert_eq!(run["startTimeUnixNano"], start);
+            assert_eq!(run["endTimeUnixNano"], end);
+        }
+    }
+
+    #[test]
+    fn endpoints_default_to_the_traces_path() {
+        assert_eq!(
+            split_endpoint("http://localhost:4318", DEFAULT_PATH).unwrap(),
+            ("localhost:4318", "/v1/traces")
+        );
+        assert_eq!(
+            split_endpoint("http://tempo:4318/otlp/v1/traces", DEFAULT_PATH).unwrap(),
+            ("tempo:4318", "/otlp/v1/traces")
+        );
+        assert!(split_endpoint("https://tempo:4318", DEFAULT_PATH).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const RULE: &str = "------------------------------------------";
+const LABEL_WIDTH: usize = 16;
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+static SUMMARY_ONLY: AtomicBool = AtomicBool::new(false);
+static COLOR: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, PartialEq, Copy, Clone, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Default, clap::ValueEnum)]
+pub enum OutputMode {
+    #[default]
+    Normal,
+    Quiet,
+    Ndjson,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Style {
+    Bold,
+    Dim,
+    Green,
+    Red,
+    Yellow,
+    Cyan,
+}
+
+impl Style {
+    fn code(self) -> &'static str {
+        match self {
+            Style::Bold => "1",
+            Style::Dim => "2",
+            Style::Green => "32",
+            Style::Red => "31",
+            Style::Yellow => "33",
+            Style::Cyan => "36",
+        }
+    }
+}
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+pub fn set_summary_only(summary_only: bool) {
+    SUMMARY_ONLY.store(summary_only, Ordering::Relaxed);
+}
+
+pub fn is_summary_only() -> bool {
+    SUMMARY_ONLY.load(Ordering::Relaxed)
+}
+
+pub fn set_color(choice: ColorChoice) {
+    let color = match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal()
+        }
+    };
+    COLOR.store(color, Ordering::Relaxed);
+}
+
+pub fn is_colored() -> bool {
+    COLOR.load(Ordering::Relaxed)
+}
+
+pub fn paint(text: impl Display, style: Style) -> String {
+    if is_colored() {
+        format!("\x1b[{}m{}\x1b[0m", style.code(), text)
+    } else {
+        text.to_string()
+    }
+}
+
+pub fn field(label: &str, value: impl Display) -> String {
+    let label = format!("{:<width$}", format!("{}:", label), width = LABEL_WIDTH);
+    format!("{}{}", paint(label, Style::Bold), value)
+}
+
+pub fn boxed(title: Option<&str>, fields: &[(&str, String)]) -> String {
+    let mut lines = vec![RULE.to_string()];
+    lines.extend(title.map(|title| paint(title, Style::Bold)));
+    lines.extend(fields.iter().map(|(label, value)| field(label, value)));
+    lines.push(RULE.to_string());
+    lines.join("\n")
+}
+
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if !$crate::output::is_quiet() && !$crate::output::is_summary_only() {
+            println!($($arg)*);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! report {
+    ($($arg:tt)*) => {
+        if !$crate::output::is_quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boxes_align_labels_and_color_only_when_enabled() {
+        let fields = [("PID", "1".to_string()), ("Exit Code", "0".to_string())];
+        set_color(ColorChoice::Never);
+        assert_eq!(
+            boxed(Some("INCOMPLETE"), &fields),
+            format!(
+                "{0}\nINCOMPLETE\nPID:            1\nExit Code:      0\n{0}",
+                RULE
+            )
+        );
+
+        set_color(ColorChoice::Always);
+        assert_eq!(paint("ok", Style::Green), "\x1b[32mok\x1b[0m");
+        set_color(ColorChoice::Never);
+        assert_eq!(paint("ok", Style::Green), "ok");
+    }
+}
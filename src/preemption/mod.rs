@@ -0,0 +1,104 @@
+use crate::registry::Registry;
+use crate::task::Task;
+use crate::window;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+use ulid::Ulid;
+
+#[derive(Default, Clone, Copy)]
+struct Latency {
+    stops: u32,
+    total: Duration,
+    max: Duration,
+}
+
+impl Latency {
+    fn add(&mut self, other: Latency) {
+        self.stops += other.stops;
+        self.total += other.total;
+        self.max = self.max.max(other.max);
+    }
+
+    fn mean(&self) -> Duration {
+        self.total / self.stops.max(1)
+    }
+}
+
+#[derive(Default)]
+pub struct PreemptionLatency {
+    latencies: HashMap<Ulid, Vec<(u64, Duration)>>,
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl PreemptionLatency {
+    pub fn observe(&mut self, task: &Task, quantum: u64) {
+        let Some(latency) = task.stop_latency else {
+            return;
+        };
+
+        self.latencies
+            .entry(task.get_id())
+            .or_default()
+            .push((quantum, latency));
+    }
+
+    pub fn print_report(&self, tasks: &Registry, quantum: Duration, window: &RangeInclusive<u64>) {
+        crate::report!(
+            "------------------------------------------\n\
+             PREEMPTION REPORT\n\
+             Measured:       {}",
+            window::describe(window)
+        );
+
+        let mut all = Latency::default();
+        for task in tasks.iter() {
+            let Some(samples) = self.latencies.get(&task.get_id()) else {
+                continue;
+            };
+            let (_, inside) = window::split(samples, window);
+            let mut latency = Latency::default();
+            for stop in inside {
+                latency.add(Latency {
+                    stops: 1,
+                    total: stop,
+                    max: stop,
+                });
+            }
+            if latency.stops == 0 {
+                continue;
+            }
+            all.add(latency);
+
+            crate::report!(
+                "PID:            {}\n\
+                 Stops:          {}\n\
+                 Mean Latency:   {:.3} ms\n\
+                 Max Latency:    {:.3} ms",
+                task.label(),
+                latency.stops,
+                millis(latency.mean()),
+                millis(latency.max)
+            );
+        }
+
+        if all.stops == 0 {
+            crate::report!("No preemptions were measured");
+        } else {
+            crate::report!(
+                "Total Stops:    {}\n\
+                 Mean Latency:   {:.3} ms ({:.1}% of the quantum)\n\
+                 Max Latency:    {:.3} ms",
+                all.stops,
+                millis(all.mean()),
+                all.mean().as_secs_f64() / quantum.as_secs_f64() * 100.0,
+                millis(all.max)
+            );
+        }
+
+        crate::report!("------------------------------------------");
+    }
+}
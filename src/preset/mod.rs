@@ -0,0 +1,258 @@
+use crate::burst::Program;
+use crate::control;
+use crate::executor::MockExecutor;
+use crate::registry::Registry;
+use crate::scenario::Scenario;
+use crate::trace::{Event, Kind};
+use clap::ValueEnum;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Preset {
+    #[value(name = "silberschatz-5.1")]
+    Silberschatz,
+    ConvoyEffect,
+    Starvation,
+}
+
+struct Job {
+    name: String,
+    arrival: u64,
+    burst: u32,
+    priority: u8,
+}
+
+fn job(name: impl Into<String>, arrival: u64, burst: u32, priority: u8) -> Job {
+    Job {
+        name: name.into(),
+        arrival,
+        burst,
+        priority,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Averages {
+    pub waiting: f64,
+    pub turnaround: f64,
+}
+
+struct Row {
+    name: String,
+    arrival: u64,
+    burst: u64,
+    priority: u8,
+    finish: Option<u64>,
+}
+
+impl Row {
+    fn turnaround(&self) -> Option<u64> {
+        self.finish.map(|finish| finish - self.arrival)
+    }
+
+    fn waiting(&self) -> Option<u64> {
+        self.turnaround().map(|turnaround| turnaround - self.burst)
+    }
+}
+
+impl Preset {
+    fn describe(self) -> &'static str {
+        match self {
+            Preset::Silberschatz => {
+                "priority scheduling example from Silberschatz, Operating System Concepts, ch. 5"
+            }
+            Preset::ConvoyEffect => "short jobs queued behind a long one in arrival order",
+            Preset::Starvation => "a low-priority job behind a stream of high-priority arrivals",
+        }
+    }
+
+    fn jobs(self) -> Vec<Job> {
+        match self {
+            Preset::Silberschatz => vec![
+                job("P1", 0, 10, 3),
+                job("P2", 0, 1, 1),
+                job("P3", 0, 2, 4),
+                job("P4", 0, 1, 5),
+                job("P5", 0, 5, 2),
+            ],
+            Preset::ConvoyEffect => {
+                vec![job("P1", 0, 24, 1), job("P2", 0, 3, 2), job("P3", 0, 3, 3)]
+            }
+            Preset::Starvation => {
+                let mut jobs = vec![job("batch", 0, 3, 7)];
+                jobs.extend((0..10).map(|i| job(format!("hot-{}", i), 2 * i, 2, 1)));
+                jobs
+            }
+        }
+    }
+
+    pub fn expected(self) -> Averages {
+        match self {
+            Preset::Silberschatz => Averages {
+                waiting: 8.2,
+                turnaround: 12.0,
+            },
+            Preset::ConvoyEffect => Averages {
+                waiting: 17.0,
+                turnaround: 27.0,
+            },
+            Preset::Starvation => Averages {
+                waiting: 20.0 / 11.0,
+                turnaround: 43.0 / 11.0,
+            },
+        }
+    }
+
+    pub fn workload(self, quantum: Duration) -> (Scenario, MockExecutor) {
+        let mut script = String::new();
+
+        for job in self.jobs() {
+            let burst = Program::cpu(quantum * job.burst);
+            script.push_str(&format!(
+                "at {}ns add {} --priority {} --burst {} -- {}\n",
+                (quantum * job.arrival as u32).as_nanos(),
+                job.name,
+                job.priority,
+                control::quote_words(&[burst.to_string()]),
+                job.name
+            ));
+        }
+
+        let scenario = script.parse().expect("preset scenarios are well formed");
+        (scenario, MockExecutor::new().quantum(quantum))
+    }
+
+    fn rows(self, tasks: &Registry, events: &[Event]) -> Vec<Row> {
+        self.jobs()
+            .into_iter()
+            .map(|job| {
+                let task = tasks
+                    .iter()
+                    .find(|task| task.get_path_to_binary().as_os_str() == job.name.as_str());
+                let events = events
+                    .iter()
+                    .filter(|event| task.is_some_and(|task| task.get_id() == event.task));
+
+                let mut row = Row {
+                    name: job.name,
+                    arrival: job.arrival,
+                    burst: 0,
+                    priority: job.priority,
+                    finish: None,
+                };
+                for event in events {
+                    match event.kind {
+                        Kind::Admitted => row.arrival = event.quantum,
+                        Kind::Dispatched { .. } => row.burst += 1,
+                        Kind::Terminated { .. } => row.finish = Some(event.quantum),
+                        Kind::Preempted
+                        | Kind::Suspended
+                        | Kind::Resumed
+                        | Kind::Throttled
+                        | Kind::Unthrottled => {}
+                    }
+                }
+                row
+            })
+            .collect()
+    }
+
+    pub fn averages(self, tasks: &Registry, events: &[Event]) -> Option<Averages> {
+        let rows = self.rows(tasks, events);
+        let n = rows.len() as f64;
+        let mut averages = Averages {
+            waiting: 0.0,
+            turnaround: 0.0,
+        };
+        for row in &rows {
+            averages.waiting += row.waiting()? as f64 / n;
+            averages.turnaround += row.turnaround()? as f64 / n;
+        }
+        Some(averages)
+    }
+
+    pub fn print_report(self, tasks: &Registry, events: &[Event], quantum: Duration) {
+        let name = self.to_possible_value().expect("presets are not skipped");
+        crate::report!(
+            "------------------------------------------\n\
+             PRESET REPORT\n\
+             Preset:         {} ({})\n\
+             Time Unit:      1 quantum ({} ms)",
+            name.get_name(),
+            self.describe(),
+            quantum.as_secs_f64() * 1000.0
+        );
+        crate::report!(
+            "{:<8}  {:>7}  {:>5}  {:>8}  {:>6}  {:>10}  {:>7}",
+            "TASK",
+            "ARRIVAL",
+            "BURST",
+            "PRIORITY",
+            "FINISH",
+            "TURNAROUND",
+            "WAITING"
+        );
+
+        let show = |value: Option<u64>| value.map_or("-".to_string(), |value| value.to_string());
+        for row in self.rows(tasks, events) {
+            crate::report!(
+                "{:<8}  {:>7}  {:>5}  {:>8}  {:>6}  {:>10}  {:>7}",
+                row.name,
+                row.arrival,
+                row.burst,
+                row.priority,
+                show(row.finish),
+                show(row.turnaround()),
+                show(row.waiting())
+            );
+        }
+
+        let expected = self.expected();
+        match self.averages(tasks, events) {
+            Some(averages) => crate::report!(
+                "Avg Waiting:    {:.2} (expected {:.2})\n\
+                 Avg Turnaround: {:.2} (expected {:.2})",
+                averages.waiting,
+                expected.waiting,
+                averages.turnaround,
+                expected.turnaround
+            ),
+            None => crate::report!("Not every task finished, so there are no averages to compare"),
+        }
+        crate::report!("------------------------------------------");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::simulator::{Simulator, DEFAULT_QUANTUM};
+    use std::sync::mpsc;
+
+    #[test]
+    fn presets_reproduce_the_textbook_averages() {
+        for preset in Preset::value_variants() {
+            let (scenario, executor) = preset.workload(DEFAULT_QUANTUM);
+            let (_control_tx, control_rx) = mpsc::channel();
+            let mut simulator = Simulator::new(Vec::new(), control_rx)
+                .clock(ManualClock::new())
+                .executor(executor)
+                .scenario(scenario)
+                .record_trace();
+            simulator.run();
+
+            let averages = preset
+                .averages(simulator.tasks(), simulator.events())
+                .unwrap();
+            let expected = preset.expected();
+            assert!(
+                (averages.waiting - expected.waiting).abs() < 1e-9,
+                "{:?}: {:?}",
+                preset,
+                averages
+            );
+            assert!((averages.turnaround - expected.turnaround).abs() < 1e-9);
+        }
+    }
+}
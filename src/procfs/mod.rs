@@ -0,0 +1,226 @@
+#[cfg(all(unix, feature = "process"))]
+use crate::executor::Pid;
+#[cfg(all(unix, feature = "process"))]
+use nix::unistd::{sysconf, SysconfVar};
+use std::time::Duration;
+
+#[cfg(all(unix, feature = "process"))]
+struct Stat {
+    state: char,
+    utime: u64,
+    stime: u64,
+    nice: i64,
+    processor: Option<u32>,
+}
+
+#[cfg(all(unix, feature = "process"))]
+fn read_stat(pid: Pid) -> Option<Stat> {
+    let contents = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let fields: Vec<&str> = contents[contents.rfind(')')? + 1..]
+        .split_whitespace()
+        .collect();
+
+    Some(Stat {
+        state: fields.first()?.chars().next()?,
+        utime: fields.get(11)?.parse().ok()?,
+        stime: fields.get(12)?.parse().ok()?,
+        nice: fields.get(16)?.parse().ok()?,
+        processor: fields.get(36).and_then(|field| field.parse().ok()),
+    })
+}
+
+#[cfg(all(unix, feature = "process"))]
+fn ticks_per_second() -> u64 {
+    match sysconf(SysconfVar::CLK_TCK) {
+        Ok(Some(ticks)) if ticks > 0 => ticks as u64,
+        _ => 100,
+    }
+}
+
+#[cfg(all(unix, feature = "process"))]
+fn stat_cpu_time(stat: &Stat) -> Duration {
+    let ticks = stat.utime + stat.stime;
+    Duration::from_secs_f64(ticks as f64 / ticks_per_second() as f64)
+}
+
+#[cfg(all(unix, feature = "process"))]
+pub fn cpu_time(pid: Pid) -> Option<Duration> {
+    read_stat(pid).as_ref().map(stat_cpu_time)
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct ProcessStats {
+    pub state: char,
+    pub cpu_time: Duration,
+    pub resident_kib: Option<u64>,
+    pub voluntary_switches: u64,
+    pub involuntary_switches: u64,
+    pub processor: Option<u32>,
+}
+
+#[cfg(all(unix, feature = "process"))]
+pub fn process_stats(pid: Pid) -> Option<ProcessStats> {
+    let stat = read_stat(pid)?;
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).unwrap_or_default();
+
+    Some(ProcessStats {
+        state: stat.state,
+        cpu_time: stat_cpu_time(&stat),
+        resident_kib: status_field(&status, "VmRSS:"),
+        voluntary_switches: status_field(&status, "voluntary_ctxt_switches:").unwrap_or(0),
+        involuntary_switches: status_field(&status, "nonvoluntary_ctxt_switches:").unwrap_or(0),
+        processor: stat.processor,
+    })
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct Schedstat {
+    pub on_cpu: Duration,
+    pub run_delay: Duration,
+    pub timeslices: u64,
+}
+
+#[cfg(all(unix, feature = "process"))]
+pub fn schedstat(pid: Pid) -> Option<Schedstat> {
+    let contents = std::fs::read_to_string(format!("/proc/{}/schedstat", pid)).ok()?;
+    let mut fields = contents.split_whitespace().map(str::parse::<u64>);
+
+    Some(Schedstat {
+        on_cpu: Duration::from_nanos(fields.next()?.ok()?),
+        run_delay: Duration::from_nanos(fields.next()?.ok()?),
+        timeslices: fields.next()?.ok()?,
+    })
+}
+
+#[cfg(all(unix, feature = "process"))]
+pub fn state(pid: Pid) -> Option<char> {
+    read_stat(pid).map(|stat| stat.state)
+}
+
+#[cfg(all(unix, feature = "process"))]
+pub fn nice(pid: Pid) -> Option<i64> {
+    read_stat(pid).map(|stat| stat.nice)
+}
+
+#[cfg(all(unix, feature = "process"))]
+pub fn comm(pid: Pid) -> Option<String> {
+    let contents = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    Some(contents.trim_end().to_string())
+}
+
+#[cfg(all(unix, feature = "process"))]
+pub fn tracer_pid(pid: Pid) -> Option<u32> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("TracerPid:"))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(all(unix, feature = "process"))]
+pub fn cmdline(pid: Pid) -> Option<Vec<String>> {
+    let contents = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let words: Vec<String> = contents
+        .split(|&b| b == 0)
+        .filter(|word| !word.is_empty())
+        .map(|word| String::from_utf8_lossy(word).into_owned())
+        .collect();
+    (!words.is_empty()).then_some(words)
+}
+
+pub struct Memory {
+    pub resident_kib: u64,
+    pub peak_kib: u64,
+}
+
+fn status_field(status: &str, name: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix(name))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+pub fn memory() -> Option<Memory> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+
+    Some(Memory {
+        resident_kib: status_field(&status, "VmRSS:")?,
+        peak_kib: status_field(&status, "VmHWM:")?,
+    })
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct CpuTimes {
+    pub busy: u64,
+    pub total: u64,
+}
+
+fn parse_cpu_times(stat: &str) -> Option<CpuTimes> {
+    let ticks: Vec<u64> = stat
+        .lines()
+        .find_map(|line| line.strip_prefix("cpu "))?
+        .split_whitespace()
+        .map_while(|field| field.parse().ok())
+        .collect();
+    let idle = ticks.get(3)? + ticks.get(4).unwrap_or(&0);
+    let total = ticks.iter().take(8).sum();
+    Some(CpuTimes {
+        busy: total - idle,
+        total,
+    })
+}
+
+pub fn cpu_times() -> Option<CpuTimes> {
+    parse_cpu_times(&std::fs::read_to_string("/proc/stat").ok()?)
+}
+
+pub struct HostMemory {
+    pub total_kib: u64,
+    pub available_kib: u64,
+}
+
+pub fn host_memory() -> Option<HostMemory> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+
+    Some(HostMemory {
+        total_kib: status_field(&meminfo, "MemTotal:")?,
+        available_kib: status_field(&meminfo, "MemAvailable:")?,
+    })
+}
+
+#[cfg(all(test, target_os = "linux", feature = "process"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_stats_of_a_live_process() {
+        let stats = process_stats(Pid::from_raw(std::process::id())).unwrap();
+
+        assert!("RSD".contains(stats.state));
+        assert!(stats.resident_kib.is_some_and(|kib| kib > 0));
+        assert!(stats.voluntary_switches + stats.involuntary_switches > 0);
+    }
+
+    #[test]
+    fn host_cpu_time_counts_iowait_as_idle() {
+        let stat = "cpu  100 5 20 800 50 3 2 0 0 0\ncpu0 50 2 10 400 25 1 1 0 0 0\n";
+
+        assert_eq!(
+            parse_cpu_times(stat),
+            Some(CpuTimes {
+                busy: 130,
+                total: 980
+            })
+        );
+    }
+
+    #[test]
+    fn reads_the_schedstat_of_a_live_process() {
+        let stats = schedstat(Pid::from_raw(std::process::id())).unwrap();
+
+        assert!(stats.timeslices > 0);
+    }
+}
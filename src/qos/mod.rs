@@ -0,0 +1,213 @@
+use crate::registry::Registry;
+use crate::task::{Qos, Task};
+use std::collections::{HashMap, VecDeque};
+use ulid::Ulid;
+
+pub const DEFAULT_WINDOW: u64 = 20;
+
+const TIERS: [Qos; 3] = [Qos::Guaranteed, Qos::Burstable, Qos::BestEffort];
+
+pub struct QosTracker {
+    window: u64,
+    history: HashMap<Ulid, VecDeque<u64>>,
+    tier_slot_quanta: HashMap<Qos, u64>,
+    total_slot_quanta: u64,
+    misses: u64,
+    used: bool,
+}
+
+impl QosTracker {
+    pub fn new(window: u64) -> Self {
+        Self {
+            window: window.max(1),
+            history: HashMap::new(),
+            tier_slot_quanta: HashMap::new(),
+            total_slot_quanta: 0,
+            misses: 0,
+            used: false,
+        }
+    }
+
+    pub fn window(&self) -> u64 {
+        self.window
+    }
+
+    pub fn set_window(&mut self, window: u64) {
+        self.window = window.max(1);
+    }
+
+    fn received(&self, task: &Task, quantum: u64) -> u64 {
+        self.history.get(&task.get_id()).map_or(0, |runs| {
+            runs.iter().filter(|&&q| q + self.window > quantum).count() as u64
+        })
+    }
+
+    fn deficit(&self, task: &Task, quantum: u64) -> f64 {
+        let share = task.get_spec().share.unwrap_or(0.0);
+        share * self.window as f64 - self.received(task, quantum) as f64
+    }
+
+    pub fn order(&mut self, ranking: Vec<Ulid>, tasks: &Registry, quantum: u64) -> Vec<Ulid> {
+        if ranking
+            .iter()
+            .any(|&i| tasks[i].get_spec().qos != Qos::Burstable)
+        {
+            self.used = true;
+        }
+
+        let (mut behind, rest): (Vec<Ulid>, Vec<Ulid>) = ranking.into_iter().partition(|&i| {
+            tasks[i].get_spec().qos == Qos::Guaranteed && self.deficit(&tasks[i], quantum) > 0.0
+        });
+        behind.sort_by(|&a, &b| {
+            self.deficit(&tasks[b], quantum)
+                .total_cmp(&self.deficit(&tasks[a], quantum))
+        });
+
+        let (best_effort, rest): (Vec<Ulid>, Vec<Ulid>) = rest
+            .into_iter()
+            .partition(|&i| tasks[i].get_spec().qos == Qos::BestEffort);
+
+        behind.into_iter().chain(rest).chain(best_effort).collect()
+    }
+
+    pub fn dispatched(&mut self, quantum: u64, task: &Task) {
+        let runs = self.history.entry(task.get_id()).or_default();
+        runs.push_back(quantum);
+        while runs.front().is_some_and(|&q| q + self.window <= quantum) {
+            runs.pop_front();
+        }
+
+        *self
+            .tier_slot_quanta
+            .entry(task.get_spec().qos)
+            .or_default() += task.get_spec().width as u64;
+    }
+
+    pub fn record_quantum(&mut self, slots: usize, waiting_behind: usize) {
+        self.total_slot_quanta += slots as u64;
+        self.misses += waiting_behind as u64;
+    }
+
+    pub fn behind(&self, ready: &[Ulid], tasks: &Registry, quantum: u64) -> Vec<Ulid> {
+        ready
+            .iter()
+            .copied()
+            .filter(|&i| {
+                tasks[i].get_spec().qos == Qos::Guaranteed && self.deficit(&tasks[i], quantum) > 0.0
+            })
+            .collect()
+    }
+
+    pub fn print_report(&self) {
+        if !self.used {
+            return;
+        }
+
+        crate::report!(
+            "------------------------------------------\n\
+             QOS REPORT\n\
+             Window:         {} quanta",
+            self.window
+        );
+
+        for tier in TIERS {
+            let used = self.tier_slot_quanta.get(&tier).copied().unwrap_or(0);
+            let utilization = if self.total_slot_quanta == 0 {
+                0.0
+            } else {
+                used as f64 * 100.0 / self.total_slot_quanta as f64
+            };
+            crate::report!(
+                "{:<15} {} slot-quanta ({:.1}%)",
+                format!("{}:", tier),
+                used,
+                utilization
+            );
+        }
+
+        crate::report!(
+            "Guarantee Misses: {}\n\
+             ------------------------------------------",
+            self.misses
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Spec;
+
+    fn add(tasks: &mut Registry, name: &str, qos: Qos, share: Option<f64>) -> Ulid {
+        tasks.insert(Task::from_spec(Spec {
+            qos,
+            share,
+            ..Spec::new(name, None, 1)
+        }))
+    }
+
+    #[test]
+    fn tiers_parse_from_their_names_and_back() {
+        for tier in TIERS {
+            assert_eq!(tier.to_string().parse::<Qos>(), Ok(tier));
+        }
+        assert_eq!(Qos::default(), Qos::Burstable);
+        assert!("gold"
+            .parse::<Qos>()
+            .unwrap_err()
+            .contains("Unknown QoS tier: gold"));
+    }
+
+    #[test]
+    fn guaranteed_tasks_behind_their_share_go_first_and_best_effort_goes_last() {
+        let mut tasks = Registry::default();
+        let idle = add(&mut tasks, "idle", Qos::BestEffort, None);
+        let plain = add(&mut tasks, "plain", Qos::Burstable, None);
+        let small = add(&mut tasks, "small", Qos::Guaranteed, Some(0.1));
+        let large = add(&mut tasks, "large", Qos::Guaranteed, Some(0.5));
+        let mut qos = QosTracker::new(10);
+
+        let order = qos.order(vec![idle, plain, small, large], &tasks, 1);
+        assert_eq!(order, [large, small, plain, idle]);
+        assert_eq!(
+            qos.behind(&[idle, plain, small, large], &tasks, 1),
+            [small, large]
+        );
+    }
+
+    #[test]
+    fn a_guaranteed_task_that_has_its_share_is_ranked_like_any_other() {
+        let mut tasks = Registry::default();
+        let plain = add(&mut tasks, "plain", Qos::Burstable, None);
+        let met = add(&mut tasks, "met", Qos::Guaranteed, Some(0.2));
+        let unshared = add(&mut tasks, "unshared", Qos::Guaranteed, None);
+        let mut qos = QosTracker::new(10);
+        qos.dispatched(1, &tasks[met]);
+        qos.dispatched(2, &tasks[met]);
+
+        assert!(qos.behind(&[met, unshared], &tasks, 3).is_empty());
+        assert_eq!(
+            qos.order(vec![plain, met, unshared], &tasks, 3),
+            [plain, met, unshared]
+        );
+    }
+
+    #[test]
+    fn dispatches_older_than_the_window_stop_counting() {
+        let mut tasks = Registry::default();
+        let task = add(&mut tasks, "task", Qos::Guaranteed, Some(0.25));
+        let mut qos = QosTracker::new(4);
+        qos.dispatched(1, &tasks[task]);
+
+        assert!(qos.behind(&[task], &tasks, 4).is_empty());
+        assert_eq!(qos.behind(&[task], &tasks, 5), [task]);
+    }
+
+    #[test]
+    fn the_window_is_at_least_one_quantum() {
+        assert_eq!(QosTracker::new(0).window(), 1);
+        let mut qos = QosTracker::new(5);
+        qos.set_window(0);
+        assert_eq!(qos.window(), 1);
+    }
+}
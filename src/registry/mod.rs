@@ -0,0 +1,155 @@
+use crate::task::Task;
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+use ulid::Ulid;
+
+#[derive(Default)]
+pub struct Registry {
+    tasks: Vec<Task>,
+    order: Vec<Ulid>,
+    index: HashMap<Ulid, usize>,
+}
+
+impl Registry {
+    pub fn insert(&mut self, task: Task) -> Ulid {
+        let id = task.get_id();
+        match self.index.get(&id) {
+            Some(&slot) => self.tasks[slot] = task,
+            None => {
+                self.index.insert(id, self.tasks.len());
+                self.tasks.push(task);
+                self.order.push(id);
+            }
+        }
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn contains(&self, id: Ulid) -> bool {
+        self.index.contains_key(&id)
+    }
+
+    /// Where the task sits in insertion order.
+    pub fn position(&self, id: Ulid) -> usize {
+        self.index[&id]
+    }
+
+    pub fn ids(&self) -> &[Ulid] {
+        &self.order
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Task> {
+        self.tasks.iter()
+    }
+
+    pub fn resolve(&self, reference: &str) -> Result<Ulid, String> {
+        if let Ok(id) = reference.parse() {
+            if self.contains(id) {
+                return Ok(id);
+            }
+        }
+
+        let prefix = reference.to_ascii_uppercase();
+        let matches: Vec<&Task> = self
+            .iter()
+            .filter(|task| {
+                task.name() == reference || task.get_id().to_string().starts_with(&prefix)
+            })
+            .collect();
+        match matches.as_slice() {
+            [] => Err(format!("No task matches: {}", reference)),
+            [task] => Ok(task.get_id()),
+            _ => Err(format!(
+                "{} is ambiguous, it matches: {}",
+                reference,
+                matches
+                    .iter()
+                    .map(|task| task.label())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Task> {
+        self.tasks.iter_mut()
+    }
+}
+
+impl Index<Ulid> for Registry {
+    type Output = Task;
+
+    fn index(&self, id: Ulid) -> &Task {
+        &self.tasks[self.index[&id]]
+    }
+}
+
+impl IndexMut<Ulid> for Registry {
+    fn index_mut(&mut self, id: Ulid) -> &mut Task {
+        let slot = *self.index.get(&id).expect("unknown task id");
+        &mut self.tasks[slot]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Spec;
+
+    #[test]
+    fn both_iterators_walk_tasks_in_insertion_order() {
+        let mut tasks = Registry::default();
+        let ids: Vec<Ulid> = (1..=20)
+            .map(|priority| tasks.insert(Task::new("/bin/true", None, priority)))
+            .collect();
+
+        let walked: Vec<Ulid> = tasks.iter_mut().map(|task| task.get_id()).collect();
+        assert_eq!(walked, ids);
+        let walked: Vec<Ulid> = tasks.iter().map(Task::get_id).collect();
+        assert_eq!(walked, ids);
+    }
+
+    #[test]
+    fn tasks_resolve_by_id_prefix_or_name() {
+        let mut tasks = Registry::default();
+        let web = tasks.insert(Task::from_spec(Spec {
+            name: Some("web".to_string()),
+            ..Spec::new("/usr/bin/python3", None, 1)
+        }));
+        let first = tasks.insert(Task::new("/bin/echo", None, 2));
+        let second = tasks.insert(Task::new("/bin/echo", None, 3));
+
+        assert_eq!(tasks.resolve(&web.to_string()), Ok(web));
+        assert_eq!(tasks.resolve("web"), Ok(web));
+        let unique = |id: Ulid| {
+            let id = id.to_string();
+            let others: Vec<String> = tasks.ids().iter().map(Ulid::to_string).collect();
+            (1..=id.len())
+                .map(|len| id[..len].to_ascii_lowercase())
+                .find(|prefix| {
+                    others
+                        .iter()
+                        .filter(|other| other.to_ascii_lowercase().starts_with(prefix))
+                        .count()
+                        == 1
+                })
+                .unwrap()
+        };
+        assert_eq!(tasks.resolve(&unique(second)), Ok(second));
+
+        let ambiguous = tasks.resolve("echo").unwrap_err();
+        assert!(ambiguous.starts_with("echo is ambiguous"), "{}", ambiguous);
+        assert!(ambiguous.contains(&first.to_string()) && ambiguous.contains(&second.to_string()));
+        assert_eq!(
+            tasks.resolve("nope"),
+            Err("No task matches: nope".to_string())
+        );
+    }
+}
@@ -0,0 +1,468 @@
+use crate::findings::Finding;
+use crate::registry::Registry;
+use crate::summary::RunSummary;
+use crate::trace::{Event, Kind};
+use crate::window;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+use ulid::Ulid;
+
+const LABEL_WIDTH: u64 = 160;
+const ROW_HEIGHT: u64 = 24;
+const MAX_CHART_WIDTH: u64 = 960;
+const THROTTLED_COLOR: &str = "#bab0ac";
+const COLORS: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+];
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Format {
+    Markdown,
+    Html,
+    Json,
+}
+
+impl Format {
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("html" | "htm") => Format::Html,
+            Some("json") => Format::Json,
+            _ => Format::Markdown,
+        }
+    }
+}
+
+pub struct Run<'a> {
+    pub configuration: Vec<(&'static str, String)>,
+    pub summary: RunSummary,
+    pub tasks: &'a Registry,
+    pub events: &'a [Event],
+    pub findings: Vec<Finding>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Export {
+    pub configuration: BTreeMap<String, String>,
+    pub summary: RunSummary,
+}
+
+impl Export {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        serde_json::from_str(&text)
+            .map_err(|err| format!("{} is not a JSON run report: {}", path.display(), err))
+    }
+}
+
+enum Block {
+    Table(Vec<&'static str>, Vec<Vec<String>>),
+    List(Vec<String>),
+    Svg(String),
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.2}s", duration.as_secs_f64())
+}
+
+pub fn intervals(events: &[Event]) -> HashMap<Ulid, Vec<(u64, u64)>> {
+    let mut started: HashMap<Ulid, u64> = HashMap::new();
+    let mut intervals: HashMap<Ulid, Vec<(u64, u64)>> = HashMap::new();
+    for event in events {
+        match event.kind {
+            Kind::Dispatched { .. } => {
+                started.insert(event.task, event.quantum);
+            }
+            Kind::Preempted | Kind::Suspended | Kind::Terminated { .. } => {
+                if let Some(start) = started.remove(&event.task) {
+                    intervals
+                        .entry(event.task)
+                        .or_default()
+                        .push((start, event.quantum + 1));
+                }
+            }
+            Kind::Admitted | Kind::Resumed | Kind::Throttled | Kind::Unthrottled => {}
+        }
+    }
+    intervals
+}
+
+fn throttled(events: &[Event]) -> HashMap<Ulid, Vec<(u64, u64)>> {
+    let last = events.last().map_or(0, |event| event.quantum + 1);
+    let mut since: HashMap<Ulid, u64> = HashMap::new();
+    let mut throttled: HashMap<Ulid, Vec<(u64, u64)>> = HashMap::new();
+    for event in events {
+        match event.kind {
+            Kind::Throttled => {
+                since.insert(event.task, event.quantum);
+            }
+            Kind::Unthrottled | Kind::Terminated { .. } => {
+                if let Some(start) = since.remove(&event.task) {
+                    throttled
+                        .entry(event.task)
+                        .or_default()
+                        .push((start, event.quantum));
+                }
+            }
+            _ => {}
+        }
+    }
+    for (task, start) in since {
+        throttled.entry(task).or_default().push((start, last));
+    }
+    throttled
+}
+
+pub fn gantt(tasks: &Registry, events: &[Event]) -> String {
+    let intervals = intervals(events);
+    let throttled = throttled(events);
+    let first = events.first().map_or(0, |event| event.quantum);
+    let last = events.last().map_or(0, |event| event.quantum + 1);
+    let span = (last - first).max(1);
+    let scale = (MAX_CHART_WIDTH / span).clamp(1, 24);
+    let rows: Vec<_> = tasks
+        .iter()
+        .filter(|task| {
+            intervals.contains_key(&task.get_id()) || throttled.contains_key(&task.get_id())
+        })
+        .collect();
+    let width = LABEL_WIDTH + span * scale + 20;
+    let height = (rows.len() as u64 + 1) * ROW_HEIGHT + 10;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+        width, height
+    );
+    for (row, task) in rows.iter().enumerate() {
+        let y = row as u64 * ROW_HEIGHT;
+        let _ = writeln!(
+            svg,
+            "<text x=\"4\" y=\"{}\">{}</text>",
+            y + 16,
+            escape(&task.name())
+        );
+        for &(start, end) in throttled.get(&task.get_id()).into_iter().flatten() {
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"><title>{} throttled quanta {} to {}</title></rect>",
+                LABEL_WIDTH + (start - first) * scale,
+                y + 8,
+                (end - start) * scale,
+                ROW_HEIGHT - 16,
+                THROTTLED_COLOR,
+                escape(&task.name()),
+                start,
+                end - 1
+            );
+        }
+        for &(start, end) in intervals.get(&task.get_id()).into_iter().flatten() {
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"><title>{} quanta {} to {}</title></rect>",
+                LABEL_WIDTH + (start - first) * scale,
+                y + 4,
+                (end - start) * scale,
+                ROW_HEIGHT - 8,
+                COLORS[row % COLORS.len()],
+                escape(&task.name()),
+                start,
+                end - 1
+            );
+        }
+    }
+
+    let axis = rows.len() as u64 * ROW_HEIGHT;
+    let step = span.div_ceil(10).max(1);
+    let _ = writeln!(
+        svg,
+        "<line x1=\"{0}\" y1=\"{1}\" x2=\"{2}\" y2=\"{1}\" stroke=\"#888\"/>",
+        LABEL_WIDTH,
+        axis + 2,
+        LABEL_WIDTH + span * scale
+    );
+    for quantum in (first..=last).step_by(step as usize) {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+            LABEL_WIDTH + (quantum - first) * scale,
+            axis + 16,
+            quantum
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn sections(run: &Run) -> Vec<(&'static str, Vec<Block>)> {
+    let summary = &run.summary;
+    let tasks = summary
+        .iter()
+        .map(|result| {
+            vec![
+                result.id.to_string(),
+                result.name.clone(),
+                result.priority.to_string(),
+                result.state.to_string(),
+                result
+                    .exit_code
+                    .map_or("-".to_string(), |exit_code| exit_code.to_string()),
+                seconds(result.cpu_time),
+                seconds(result.waited),
+                result.runs.to_string(),
+                format!("{:.2}s", result.duration),
+            ]
+        })
+        .collect();
+
+    let findings = if run.findings.is_empty() {
+        vec!["No known pathologies were found".to_string()]
+    } else {
+        run.findings
+            .iter()
+            .map(|finding| {
+                format!(
+                    "{}, {}: {}",
+                    finding.pathology,
+                    window::describe(&finding.quanta),
+                    finding.detail
+                )
+            })
+            .collect()
+    };
+
+    vec![
+        (
+            "Configuration",
+            vec![Block::Table(
+                vec!["Setting", "Value"],
+                run.configuration
+                    .iter()
+                    .map(|(setting, value)| vec![setting.to_string(), value.clone()])
+                    .collect(),
+            )],
+        ),
+        (
+            "Tasks",
+            vec![Block::Table(
+                vec![
+                    "ID",
+                    "Name",
+                    "Priority",
+                    "State",
+                    "Exit Code",
+                    "CPU",
+                    "Waited",
+                    "Runs",
+                    "Duration",
+                ],
+                tasks,
+            )],
+        ),
+        (
+            "Statistics",
+            vec![Block::List(vec![
+                format!("Tasks: {}", summary.tasks.len()),
+                format!("Succeeded: {}", summary.succeeded()),
+                format!("Failed: {}", summary.failed()),
+                format!("Poisoned: {}", summary.poisoned()),
+                format!("Unfinished: {}", summary.unfinished()),
+                format!("Quanta: {}", summary.quanta),
+                format!("Simulated Time: {}", seconds(summary.elapsed)),
+                format!("CPU Time: {}", seconds(summary.cpu_time())),
+                format!("Mean Wait: {}", seconds(summary.mean_wait())),
+                format!("Mean Duration: {:.2}s", summary.mean_duration()),
+            ])],
+        ),
+        (
+            "Gantt Chart",
+            vec![Block::Svg(gantt(run.tasks, run.events))],
+        ),
+        ("Findings", vec![Block::List(findings)]),
+    ]
+}
+
+fn markdown(run: &Run) -> String {
+    let mut out = "# psched run report\n".to_string();
+    for (title, blocks) in sections(run) {
+        let _ = write!(out, "\n## {}\n\n", title);
+        for block in blocks {
+            match block {
+                Block::Table(header, rows) => {
+                    let _ = writeln!(out, "| {} |", header.join(" | "));
+                    let _ = writeln!(out, "|{}", "---|".repeat(header.len()));
+                    for row in rows {
+                        let cells: Vec<String> =
+                            row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+                        let _ = writeln!(out, "| {} |", cells.join(" | "));
+                    }
+                }
+                Block::List(items) => {
+                    for item in items {
+                        let _ = writeln!(out, "- {}", item);
+                    }
+                }
+                Block::Svg(svg) => {
+                    let _ = writeln!(out, "{}", svg);
+                }
+            }
+        }
+    }
+    out
+}
+
+fn html(run: &Run) -> String {
+    let mut out = "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+                   <title>psched run report</title>\n\
+                   <style>body{font-family:sans-serif}table{border-collapse:collapse}\
+                   td,th{border:1px solid #ccc;padding:2px 8px;text-align:left}</style>\n\
+                   </head>\n<body>\n<h1>psched run report</h1>\n"
+        .to_string();
+    for (title, blocks) in sections(run) {
+        let _ = writeln!(out, "<h2>{}</h2>", title);
+        for block in blocks {
+            match block {
+                Block::Table(header, rows) => {
+                    out.push_str("<table>\n<tr>");
+                    for cell in header {
+                        let _ = write!(out, "<th>{}</th>", cell);
+                    }
+                    out.push_str("</tr>\n");
+                    for row in rows {
+                        out.push_str("<tr>");
+                        for cell in row {
+                            let _ = write!(out, "<td>{}</td>", escape(&cell));
+                        }
+                        out.push_str("</tr>\n");
+                    }
+                    out.push_str("</table>\n");
+                }
+                Block::List(items) => {
+                    out.push_str("<ul>\n");
+                    for item in items {
+                        let _ = writeln!(out, "<li>{}</li>", escape(&item));
+                    }
+                    out.push_str("</ul>\n");
+                }
+                Block::Svg(svg) => {
+                    let _ = writeln!(out, "{}", svg);
+                }
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+pub fn render(run: &Run, format: Format) -> String {
+    match format {
+        Format::Markdown => markdown(run),
+        Format::Html => html(run),
+        Format::Json => {
+            let export = Export {
+                configuration: run
+                    .configuration
+                    .iter()
+                    .map(|(setting, value)| (setting.to_string(), value.clone()))
+                    .collect(),
+                summary: run.summary.clone(),
+            };
+            serde_json::to_string_pretty(&export).unwrap_or_default() + "\n"
+        }
+    }
+}
+
+pub fn save(run: &Run, path: &Path) -> io::Result<()> {
+    fs::write(path, render(run, Format::for_path(path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{ExitCode, State, Task};
+    use crate::trace::Trace;
+
+    #[test]
+    fn reports_render_tasks_statistics_and_a_gantt_chart() {
+        let mut tasks = Registry::default();
+        let mut done = Task::new("/bin/echo", None, 1);
+        done.set_state(State::Terminated);
+        done.exit_code = Some(ExitCode::Success);
+        let done = tasks.insert(done);
+        let queued = tasks.insert(Task::new("/bin/a<b", None, 2));
+
+        let mut trace = Trace::default();
+        trace.record(0, done, Kind::Admitted);
+        trace.record(0, queued, Kind::Admitted);
+        trace.record(1, done, Kind::Dispatched { backfilled: false });
+        trace.record(1, done, Kind::Preempted);
+        trace.record(2, done, Kind::Dispatched { backfilled: false });
+        trace.record(
+            3,
+            done,
+            Kind::Terminated {
+                exit_code: Some(ExitCode::Success),
+            },
+        );
+        assert_eq!(intervals(trace.events())[&done], [(1, 2), (2, 4)]);
+
+        let run = Run {
+            configuration: vec![("Policy", "priority".to_string())],
+            summary: RunSummary::new(tasks.iter(), 3, Duration::from_millis(100), false),
+            tasks: &tasks,
+            events: trace.events(),
+            findings: Vec::new(),
+        };
+        let markdown = render(&run, Format::Markdown);
+        assert!(markdown.contains("| Policy | priority |"));
+        assert!(markdown.contains(&format!(
+            "| {} | echo | 1 | TERMINATED | 0 (SUCCESS) |",
+            done
+        )));
+        assert!(markdown.contains("- Succeeded: 1\n- Failed: 0"));
+        assert!(markdown.contains("<svg") && markdown.contains("echo quanta 2 to 3"));
+        assert!(markdown.contains("- No known pathologies were found"));
+
+        let html = render(&run, Format::Html);
+        assert!(html.contains("<td>a&lt;b</td>"));
+        assert_eq!(Format::for_path(Path::new("run.html")), Format::Html);
+        assert_eq!(Format::for_path(Path::new("run.md")), Format::Markdown);
+
+        let export: Export = serde_json::from_str(&render(&run, Format::Json)).unwrap();
+        assert_eq!(export.configuration["Policy"], "priority");
+        assert_eq!(export.summary, run.summary);
+    }
+
+    #[test]
+    fn throttled_stretches_are_drawn_in_the_gantt_chart() {
+        let mut tasks = Registry::default();
+        let capped = tasks.insert(Task::new("/bin/capped", None, 1));
+
+        let mut trace = Trace::default();
+        trace.record(0, capped, Kind::Admitted);
+        trace.record(1, capped, Kind::Dispatched { backfilled: false });
+        trace.record(1, capped, Kind::Preempted);
+        trace.record(2, capped, Kind::Throttled);
+        trace.record(5, capped, Kind::Unthrottled);
+        trace.record(5, capped, Kind::Dispatched { backfilled: false });
+        trace.record(5, capped, Kind::Preempted);
+        trace.record(6, capped, Kind::Throttled);
+        assert_eq!(throttled(trace.events())[&capped], [(2, 5), (6, 7)]);
+
+        let svg = gantt(&tasks, trace.events());
+        assert!(svg.contains("capped throttled quanta 2 to 4"));
+        assert!(svg.contains("capped throttled quanta 6 to 6"));
+        assert!(svg.contains("capped quanta 5 to 5"));
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::{BTreeSet, HashMap};
+use ulid::Ulid;
+
+#[derive(Default)]
+pub struct RunQueue {
+    entries: BTreeSet<(u8, u64, Ulid)>,
+    keys: HashMap<Ulid, (u8, u64)>,
+}
+
+impl RunQueue {
+    /// Queues a task, or moves it if it is already queued. Equal keys are ordered by `arrival`,
+    /// which the caller keeps fixed for a task so it does not lose its place when requeued.
+    pub fn push(&mut self, id: Ulid, key: u8, arrival: u64) {
+        self.remove(id);
+        self.keys.insert(id, (key, arrival));
+        self.entries.insert((key, arrival, id));
+    }
+
+    pub fn remove(&mut self, id: Ulid) -> bool {
+        match self.keys.remove(&id) {
+            Some((key, arrival)) => self.entries.remove(&(key, arrival, id)),
+            None => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (u8, u64, Ulid)> + '_ {
+        self.entries.iter().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Ulid> + '_ {
+        self.entries.iter().map(|&(_, _, id)| id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(n: usize) -> Vec<Ulid> {
+        (0..n).map(|i| Ulid::from_parts(0, i as u128)).collect()
+    }
+
+    #[test]
+    fn lower_keys_come_first_and_ties_keep_arrival_order() {
+        let id = ids(4);
+        let mut queue = RunQueue::default();
+        queue.push(id[0], 3, 0);
+        queue.push(id[1], 1, 1);
+        queue.push(id[2], 3, 2);
+        queue.push(id[3], 1, 3);
+
+        assert_eq!(
+            queue.iter().collect::<Vec<_>>(),
+            [id[1], id[3], id[0], id[2]]
+        );
+
+        queue.push(id[3], 1, 3);
+        queue.push(id[1], 1, 1);
+        assert_eq!(
+            queue.iter().collect::<Vec<_>>(),
+            [id[1], id[3], id[0], id[2]]
+        );
+    }
+
+    #[test]
+    fn pushing_a_queued_task_again_moves_it() {
+        let id = ids(2);
+        let mut queue = RunQueue::default();
+        queue.push(id[0], 2, 0);
+        queue.push(id[1], 2, 1);
+        queue.push(id[1], 0, 1);
+
+        assert_eq!(queue.len(), 2);
+        assert_eq!(
+            queue.entries().collect::<Vec<_>>(),
+            [(0, 1, id[1]), (2, 0, id[0])]
+        );
+    }
+
+    #[test]
+    fn removed_tasks_leave_nothing_behind() {
+        let id = ids(3);
+        let mut queue = RunQueue::default();
+        for (arrival, &task) in id.iter().enumerate() {
+            queue.push(task, 1, arrival as u64);
+        }
+
+        assert!(queue.remove(id[1]));
+        assert!(!queue.remove(id[1]));
+        assert_eq!(queue.iter().collect::<Vec<_>>(), [id[0], id[2]]);
+        assert!(!queue.keys.contains_key(&id[1]));
+
+        queue.remove(id[0]);
+        queue.remove(id[2]);
+        assert!(queue.is_empty());
+        assert!(queue.keys.is_empty());
+    }
+}
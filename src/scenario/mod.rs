@@ -0,0 +1,245 @@
+use crate::clock;
+use crate::control::{self, Command};
+use crate::task::Spec;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+use ulid::Ulid;
+
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    Add(String, Box<Spec>),
+    Renice(String, u8),
+    Kill(String),
+    Control(Command),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Step {
+    pub at: Duration,
+    pub text: String,
+    pub action: Action,
+}
+
+#[derive(Debug, Default)]
+pub struct Scenario {
+    steps: VecDeque<Step>,
+    tasks: HashMap<String, Ulid>,
+}
+
+fn task_name<'a>(
+    words: &mut impl Iterator<Item = &'a str>,
+    action: &str,
+) -> Result<String, String> {
+    words
+        .next()
+        .map(str::to_string)
+        .ok_or_else(|| format!("{} needs a task name", action))
+}
+
+fn parse_step(line: &str) -> Result<Step, String> {
+    let words = control::split_words(line)?;
+    let mut words = words.iter().map(String::as_str);
+
+    if words.next() != Some("at") {
+        return Err("expected `at TIME ACTION`".to_string());
+    }
+    let at = clock::parse_duration(words.next().ok_or("missing time")?)?;
+
+    let rest: Vec<&str> = words.clone().collect();
+    let text = control::quote_words(&rest);
+
+    let action = match words.next().ok_or("missing action")? {
+        "add" => {
+            let task = task_name(&mut words, "add")?;
+            let mut spec = control::parse_submit(words)?;
+            spec.name.get_or_insert_with(|| task.clone());
+            return Ok(Step {
+                at,
+                text,
+                action: Action::Add(task, Box::new(spec)),
+            });
+        }
+        "renice" => {
+            let task = task_name(&mut words, "renice")?;
+            let priority = words
+                .next()
+                .ok_or("renice needs a priority")?
+                .parse()
+                .map_err(|_| format!("invalid priority in: {}", text))?;
+            Action::Renice(task, priority)
+        }
+        "kill" => Action::Kill(task_name(&mut words, "kill")?),
+        "checkpoint" | "shutdown" => {
+            return Ok(Step {
+                at,
+                action: Action::Control(text.parse()?),
+                text,
+            })
+        }
+        other => return Err(format!("unknown action: {}", other)),
+    };
+
+    if words.next().is_some() {
+        return Err(format!("too many arguments: {}", text));
+    }
+    Ok(Step { at, text, action })
+}
+
+impl FromStr for Scenario {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = Vec::new();
+        for (number, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let step = parse_step(line).map_err(|err| format!("line {}: {}", number + 1, err))?;
+            steps.push((number + 1, step));
+        }
+        steps.sort_by_key(|(_, step)| step.at);
+
+        let mut added = HashMap::new();
+        for (number, step) in &steps {
+            match &step.action {
+                Action::Add(name, _) => {
+                    if let Some(first) = added.insert(name, number) {
+                        return Err(format!(
+                            "line {}: task {} is already added on line {}",
+                            number, name, first
+                        ));
+                    }
+                }
+                Action::Renice(name, _) | Action::Kill(name) if !added.contains_key(name) => {
+                    return Err(format!(
+                        "line {}: task {} is not added before this step",
+                        number, name
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            steps: steps.into_iter().map(|(_, step)| step).collect(),
+            tasks: HashMap::new(),
+        })
+    }
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        fs::read_to_string(path)
+            .map_err(|err| err.to_string())?
+            .parse()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn due(&mut self, now: Duration) -> Vec<Step> {
+        let mut due = Vec::new();
+        while self.steps.front().is_some_and(|step| step.at <= now) {
+            due.extend(self.steps.pop_front());
+        }
+        due
+    }
+
+    pub fn added(&mut self, name: String, id: Ulid) {
+        self.tasks.insert(name, id);
+    }
+
+    pub fn task(&self, name: &str) -> Option<Ulid> {
+        self.tasks.get(name).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::executor::MockExecutor;
+    use crate::simulator::Simulator;
+    use crate::trace::Kind;
+    use std::num::NonZeroUsize;
+
+    #[test]
+    fn steps_are_ordered_by_time_and_names_are_checked() {
+        let mut scenario: Scenario = "
+            # a periodic job and a late high-priority one
+            at 8s kill batch
+            at 0s add batch --priority 4 --meta run=baseline -- /bin/sleep 10
+            at 2s add urgent /bin/echo 'right away'
+            at 5s renice batch 1
+        "
+        .parse()
+        .unwrap();
+
+        let first = scenario.due(Duration::ZERO);
+        assert_eq!(first.len(), 1);
+        assert!(matches!(&first[0].action, Action::Add(name, spec)
+            if name == "batch" && spec.priority == 4
+                && spec.metadata.get("run").is_some_and(|run| run == "baseline")));
+        assert_eq!(
+            scenario
+                .due(Duration::from_secs(5))
+                .iter()
+                .map(|step| step.text.as_str())
+                .collect::<Vec<_>>(),
+            ["add urgent /bin/echo 'right away'", "renice batch 1"]
+        );
+        assert!(!scenario.is_done());
+
+        let err = "at 1s kill ghost".parse::<Scenario>().unwrap_err();
+        assert_eq!(err, "line 1: task ghost is not added before this step");
+        let err = "at 1s add a /bin/true\nat 2s add a /bin/true"
+            .parse::<Scenario>()
+            .unwrap_err();
+        assert_eq!(err, "line 2: task a is already added on line 1");
+        assert!("at 1s pause".parse::<Scenario>().is_err());
+    }
+
+    #[test]
+    fn tasks_in_a_workload_file_can_share_a_critical_section() {
+        let path = std::env::temp_dir().join(format!("psched-{}.scenario", Ulid::new()));
+        fs::write(
+            &path,
+            "at 0s add first --burst 'acquire db; cpu 30ms; release db' psched-worker\n\
+             at 0s add second --burst 'acquire db; cpu 10ms; release db' psched-worker\n",
+        )
+        .unwrap();
+        let scenario = Scenario::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let quantum = Duration::from_millis(10);
+        crate::output::set_quiet(true);
+        let (_control_tx, control_rx) = std::sync::mpsc::channel();
+        let mut simulator = Simulator::new(Vec::new(), control_rx)
+            .scenario(scenario)
+            .quantum(quantum)
+            .concurrency(NonZeroUsize::new(2).unwrap())
+            .clock(ManualClock::new())
+            .executor(MockExecutor::new().quantum(quantum))
+            .record_trace();
+        simulator.run();
+
+        let terminated: Vec<(u64, String)> = simulator
+            .events()
+            .iter()
+            .filter(|event| matches!(event.kind, Kind::Terminated { .. }))
+            .map(|event| (event.quantum, simulator.tasks()[event.task].name()))
+            .collect();
+        assert_eq!(
+            terminated,
+            [(3, "first".to_string()), (4, "second".to_string())]
+        );
+        assert!("at 0s add bad --burst 'release db' psched-worker"
+            .parse::<Scenario>()
+            .is_err());
+    }
+}
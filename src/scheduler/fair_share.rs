@@ -0,0 +1,68 @@
+use super::Scheduler;
+use crate::task::Task;
+use std::collections::HashMap;
+use std::time::Duration;
+use ulid::Ulid;
+
+#[derive(Default)]
+pub struct FairShareScheduler {
+    usage: HashMap<String, Duration>,
+    seen: HashMap<Ulid, Duration>,
+}
+
+impl FairShareScheduler {
+    fn account(&mut self, task: &Task) {
+        let seen = self.seen.entry(task.get_id()).or_default();
+        let used = task.cpu_time.saturating_sub(*seen);
+        *seen = task.cpu_time;
+
+        *self
+            .usage
+            .entry(task.get_spec().account().to_string())
+            .or_default() += used;
+    }
+}
+
+impl Scheduler for FairShareScheduler {
+    fn name(&self) -> &str {
+        "fair-share"
+    }
+
+    fn rank(&mut self, ready: &[&Task]) -> Vec<usize> {
+        for task in ready {
+            self.account(task);
+        }
+
+        let mut order: Vec<usize> = (0..ready.len()).collect();
+        order.sort_by_key(|&i| {
+            let task = ready[i];
+            (
+                self.usage[task.get_spec().account()],
+                task.effective_priority(),
+            )
+        });
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Spec;
+
+    fn task(run_as: &str, priority: u8) -> Task {
+        Task::from_spec(Spec {
+            run_as: Some(run_as.to_string()),
+            ..Spec::new("/bin/true", None, priority)
+        })
+    }
+
+    #[test]
+    fn the_account_with_less_cpu_goes_first() {
+        let (mut busy, idle, busy_urgent) = (task("alice", 2), task("bob", 5), task("alice", 1));
+        busy.cpu_time = Duration::from_millis(300);
+        let mut scheduler = FairShareScheduler::default();
+
+        assert_eq!(scheduler.rank(&[&busy, &idle, &busy_urgent]), vec![1, 2, 0]);
+    }
+}
@@ -0,0 +1,124 @@
+use super::Scheduler;
+use crate::task::Task;
+use std::collections::BTreeMap;
+
+#[derive(Default)]
+struct Decisions {
+    first: u64,
+    picks: BTreeMap<String, u64>,
+}
+
+pub struct HierarchicalScheduler {
+    name: String,
+    top: Box<dyn Scheduler>,
+    group: Box<dyn Scheduler>,
+    decisions: BTreeMap<String, Decisions>,
+}
+
+impl HierarchicalScheduler {
+    pub fn new(top: Box<dyn Scheduler>, group: Box<dyn Scheduler>) -> Self {
+        Self {
+            name: format!("{} > {}", top.name(), group.name()),
+            top,
+            group,
+            decisions: BTreeMap::new(),
+        }
+    }
+}
+
+impl Scheduler for HierarchicalScheduler {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn begin_quantum(&mut self, quantum: u64) {
+        self.top.begin_quantum(quantum);
+        self.group.begin_quantum(quantum);
+    }
+
+    fn rank(&mut self, ready: &[&Task]) -> Vec<usize> {
+        let mut groups: Vec<(&str, Vec<usize>)> = Vec::new();
+        for i in self.top.rank(ready) {
+            let account = ready[i].get_spec().account();
+            match groups.iter_mut().find(|(group, _)| *group == account) {
+                Some((_, members)) => members.push(i),
+                None => groups.push((account, vec![i])),
+            }
+        }
+
+        let mut order = Vec::with_capacity(ready.len());
+        for (position, (group, members)) in groups.into_iter().enumerate() {
+            let tasks: Vec<&Task> = members.iter().map(|&i| ready[i]).collect();
+            let ranked = self.group.rank(&tasks);
+
+            let decisions = self.decisions.entry(group.to_string()).or_default();
+            if position == 0 {
+                decisions.first += 1;
+            }
+            if let Some(&pick) = ranked.first() {
+                *decisions.picks.entry(tasks[pick].label()).or_default() += 1;
+            }
+            order.extend(ranked.into_iter().map(|p| members[p]));
+        }
+        order
+    }
+
+    fn print_report(&self) {
+        crate::report!(
+            "------------------------------------------\n\
+             HIERARCHY REPORT\n\
+             Top Level:      {} across accounts\n\
+             Group Level:    {} within each account",
+            self.top.name(),
+            self.group.name()
+        );
+        for (account, decisions) in &self.decisions {
+            crate::report!(
+                "Account:        {}\n\
+                 Ranked First:   {} quanta",
+                account,
+                decisions.first
+            );
+            for (task, picks) in &decisions.picks {
+                crate::report!("  {} picked first in {} quanta", task, picks);
+            }
+        }
+        self.top.print_report();
+        self.group.print_report();
+        crate::report!("------------------------------------------");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::{build, Policy};
+    use crate::task::Spec;
+    use std::time::Duration;
+
+    fn task(run_as: &str, priority: u8) -> Task {
+        Task::from_spec(Spec {
+            run_as: Some(run_as.to_string()),
+            ..Spec::new("/bin/true", None, priority)
+        })
+    }
+
+    #[test]
+    fn the_top_level_picks_the_account_and_the_group_level_picks_the_task() {
+        let (mut busy, idle_low, idle_high) = (task("alice", 1), task("bob", 5), task("bob", 2));
+        busy.cpu_time = Duration::from_millis(300);
+        let mut scheduler = HierarchicalScheduler::new(
+            build(Policy::FairShare, None),
+            build(Policy::Priority, None),
+        );
+
+        assert_eq!(scheduler.name(), "fair-share > priority");
+        assert_eq!(
+            scheduler.rank(&[&busy, &idle_low, &idle_high]),
+            vec![2, 1, 0]
+        );
+        assert_eq!(scheduler.decisions["bob"].first, 1);
+        assert_eq!(scheduler.decisions["alice"].first, 0);
+        assert_eq!(scheduler.decisions["bob"].picks[&idle_high.label()], 1);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::task::Task;
+use clap::ValueEnum;
+use fair_share::FairShareScheduler;
+pub use hierarchy::HierarchicalScheduler;
+#[cfg(feature = "plugins")]
+pub use plugin::{PluginScheduler, PschedTask};
+use priority::PriorityScheduler;
+use random::RandomScheduler;
+#[cfg(feature = "scripting")]
+pub use script::ScriptScheduler;
+mod fair_share;
+mod hierarchy;
+#[cfg(feature = "plugins")]
+mod plugin;
+mod priority;
+mod random;
+#[cfg(feature = "scripting")]
+mod script;
+
+pub trait Scheduler {
+    fn name(&self) -> &str;
+
+    fn begin_quantum(&mut self, _quantum: u64) {}
+
+    fn rank(&mut self, ready: &[&Task]) -> Vec<usize>;
+
+    fn follows_run_queue(&self) -> bool {
+        false
+    }
+
+    fn print_report(&self) {}
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, ValueEnum)]
+pub enum Policy {
+    Priority,
+    Random,
+    FairShare,
+}
+
+pub fn build(policy: Policy, seed: Option<u64>) -> Box<dyn Scheduler> {
+    match policy {
+        Policy::Priority => Box::new(PriorityScheduler),
+        Policy::Random => {
+            let seed = seed.unwrap_or_else(rand::random);
+            crate::say!("Random scheduler seeded with {}", seed);
+            Box::new(RandomScheduler::new(seed))
+        }
+        Policy::FairShare => Box::new(FairShareScheduler::default()),
+    }
+}
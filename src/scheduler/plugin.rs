@@ -0,0 +1,111 @@
+use super::Scheduler;
+use crate::task::Task;
+use libloading::Library;
+use std::ffi::{c_char, c_int, CStr};
+use std::path::Path;
+
+#[repr(C)]
+pub struct PschedTask {
+    pub priority: u8,
+    pub effective_priority: u8,
+    pub width: usize,
+    pub cpu_time_ms: u64,
+    pub remaining_estimate_ms: i64,
+}
+
+type NameFn = unsafe extern "C" fn() -> *const c_char;
+type RankFn = unsafe extern "C" fn(*const PschedTask, usize, *mut usize) -> c_int;
+
+pub struct PluginScheduler {
+    name: String,
+    rank: RankFn,
+    _library: Library,
+}
+
+fn describe(err: libloading::Error) -> String {
+    match std::error::Error::source(&err) {
+        Some(source) => source.to_string(),
+        None => err.to_string(),
+    }
+}
+
+impl PluginScheduler {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let library = unsafe { Library::new(path) }.map_err(describe)?;
+
+        let (name, rank) = unsafe {
+            let name = library
+                .get::<NameFn>(b"psched_policy_name\0")
+                .map_err(describe)?;
+            let rank = library
+                .get::<RankFn>(b"psched_policy_rank\0")
+                .map_err(describe)?;
+
+            let name = match name() {
+                ptr if ptr.is_null() => path.display().to_string(),
+                ptr => CStr::from_ptr(ptr).to_string_lossy().into_owned(),
+            };
+            (name, *rank)
+        };
+
+        Ok(Self {
+            name,
+            rank,
+            _library: library,
+        })
+    }
+}
+
+fn view(task: &Task) -> PschedTask {
+    PschedTask {
+        priority: task.priority,
+        effective_priority: task.effective_priority(),
+        width: task.get_spec().width,
+        cpu_time_ms: task.cpu_time.as_millis() as u64,
+        remaining_estimate_ms: task
+            .remaining_estimate()
+            .map_or(-1, |remaining| remaining.as_millis() as i64),
+    }
+}
+
+fn is_permutation(order: &[usize]) -> bool {
+    let mut seen = vec![false; order.len()];
+    order
+        .iter()
+        .all(|&i| i < seen.len() && !std::mem::replace(&mut seen[i], true))
+}
+
+impl Scheduler for PluginScheduler {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn rank(&mut self, ready: &[&Task]) -> Vec<usize> {
+        let tasks: Vec<PschedTask> = ready.iter().map(|task| view(task)).collect();
+        let mut order: Vec<usize> = (0..ready.len()).collect();
+
+        let status = unsafe { (self.rank)(tasks.as_ptr(), tasks.len(), order.as_mut_ptr()) };
+        if status != 0 || !is_permutation(&order) {
+            crate::say!(
+                "Policy plugin {} returned an invalid ranking; using arrival order",
+                self.name
+            );
+            return (0..ready.len()).collect();
+        }
+
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_permutations_are_accepted() {
+        assert!(is_permutation(&[2, 0, 1]));
+        assert!(is_permutation(&[]));
+        assert!(!is_permutation(&[0, 0, 1]));
+        assert!(!is_permutation(&[0, 3, 1]));
+    }
+}
@@ -0,0 +1,20 @@
+use super::Scheduler;
+use crate::task::Task;
+
+pub struct PriorityScheduler;
+
+impl Scheduler for PriorityScheduler {
+    fn name(&self) -> &str {
+        "priority"
+    }
+
+    fn rank(&mut self, ready: &[&Task]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..ready.len()).collect();
+        order.sort_by_key(|&i| ready[i].effective_priority());
+        order
+    }
+
+    fn follows_run_queue(&self) -> bool {
+        true
+    }
+}
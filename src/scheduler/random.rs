@@ -0,0 +1,29 @@
+use super::Scheduler;
+use crate::task::Task;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+pub struct RandomScheduler {
+    rng: ChaCha8Rng,
+}
+
+impl RandomScheduler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Scheduler for RandomScheduler {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn rank(&mut self, ready: &[&Task]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..ready.len()).collect();
+        order.shuffle(&mut self.rng);
+        order
+    }
+}
@@ -0,0 +1,165 @@
+use super::Scheduler;
+use crate::task::Task;
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use std::path::Path;
+
+pub struct ScriptScheduler {
+    name: String,
+    engine: Engine,
+    ast: AST,
+    quantum: u64,
+}
+
+impl ScriptScheduler {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let engine = Engine::new();
+        let ast = engine
+            .compile_file(path.to_path_buf())
+            .map_err(|e| e.to_string())?;
+
+        if !ast.iter_functions().any(|f| f.name == "pick") {
+            return Err("the script does not define fn pick(tasks)".to_string());
+        }
+
+        let name = path.file_stem().map_or_else(
+            || path.display().to_string(),
+            |s| s.to_string_lossy().into_owned(),
+        );
+
+        Ok(Self {
+            name,
+            engine,
+            ast,
+            quantum: 0,
+        })
+    }
+
+    fn candidate(&self, task: &Task) -> Dynamic {
+        let mut map = Map::new();
+        map.insert("id".into(), task.get_id().to_string().into());
+        map.insert("priority".into(), i64::from(task.priority).into());
+        map.insert(
+            "effective_priority".into(),
+            i64::from(task.effective_priority()).into(),
+        );
+        map.insert(
+            "waited".into(),
+            (self.quantum.saturating_sub(task.enqueued_at) as i64).into(),
+        );
+        map.insert(
+            "remaining".into(),
+            task.remaining_estimate()
+                .map_or(Dynamic::UNIT, |r| (r.as_millis() as i64).into()),
+        );
+        map.into()
+    }
+}
+
+fn position(ready: &[&Task], id: &Dynamic) -> Option<usize> {
+    let id = id.clone().into_string().ok()?;
+    ready.iter().position(|t| t.get_id().to_string() == id)
+}
+
+fn order(ready: &[&Task], picked: Dynamic) -> Result<Vec<usize>, String> {
+    let picked: Vec<Dynamic> = if picked.is_unit() {
+        Vec::new()
+    } else if picked.is_array() {
+        picked.cast::<Array>()
+    } else {
+        vec![picked]
+    };
+
+    let mut order = Vec::with_capacity(ready.len());
+    for id in &picked {
+        match position(ready, id) {
+            Some(i) if !order.contains(&i) => order.push(i),
+            _ => return Err(format!("{} is not a ready task", id)),
+        }
+    }
+    let rest: Vec<usize> = (0..ready.len()).filter(|i| !order.contains(i)).collect();
+    order.extend(rest);
+
+    Ok(order)
+}
+
+impl Scheduler for ScriptScheduler {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn begin_quantum(&mut self, quantum: u64) {
+        self.quantum = quantum;
+    }
+
+    fn rank(&mut self, ready: &[&Task]) -> Vec<usize> {
+        let tasks: Array = ready.iter().map(|task| self.candidate(task)).collect();
+
+        let result = self
+            .engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &self.ast, "pick", (tasks,))
+            .map_err(|e| e.to_string())
+            .and_then(|picked| order(ready, picked));
+
+        match result {
+            Ok(order) => order,
+            Err(err) => {
+                crate::say!(
+                    "Policy script {} failed: {}; using arrival order",
+                    self.name,
+                    err
+                );
+                (0..ready.len()).collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranked(script: &str, ready: &[&Task]) -> Vec<usize> {
+        let engine = Engine::new();
+        let mut scheduler = ScriptScheduler {
+            name: "test".to_string(),
+            ast: engine.compile(script).unwrap(),
+            engine,
+            quantum: 5,
+        };
+        scheduler.rank(ready)
+    }
+
+    #[test]
+    fn the_picked_task_goes_first() {
+        let (mut a, b, mut c) = (
+            Task::new("a", None, 1),
+            Task::new("b", None, 7),
+            Task::new("c", None, 3),
+        );
+        a.enqueued_at = 4;
+        c.enqueued_at = 4;
+        let ready = [&a, &b, &c];
+
+        let longest_wait = "fn pick(tasks) {
+            let best = tasks[0];
+            for t in tasks { if t.waited > best.waited { best = t; } }
+            best.id
+        }";
+        assert_eq!(ranked(longest_wait, &ready), vec![1, 0, 2]);
+
+        let largest_priority_value = "fn pick(tasks) {
+            tasks.sort(|x, y| y.priority - x.priority);
+            tasks.map(|t| t.id)
+        }";
+        assert_eq!(ranked(largest_priority_value, &ready), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn an_unknown_task_falls_back_to_arrival_order() {
+        let (a, b) = (Task::new("a", None, 1), Task::new("b", None, 2));
+        let ready = [&a, &b];
+
+        assert_eq!(ranked("fn pick(tasks) { \"nobody\" }", &ready), vec![0, 1]);
+        assert_eq!(ranked("fn pick(tasks) { () }", &ready), vec![0, 1]);
+    }
+}
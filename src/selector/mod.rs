@@ -0,0 +1,350 @@
+use crate::task::{State, Task};
+use std::iter::Peekable;
+use std::str::FromStr;
+use std::vec::IntoIter;
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+    Op(Op),
+    Word(String),
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn holds(self, ordering: std::cmp::Ordering) -> bool {
+        match self {
+            Op::Eq => ordering.is_eq(),
+            Op::Ne => ordering.is_ne(),
+            Op::Lt => ordering.is_lt(),
+            Op::Le => ordering.is_le(),
+            Op::Gt => ordering.is_gt(),
+            Op::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Field {
+    State,
+    Priority,
+    Effective,
+    Runs,
+    Waited,
+    Cpu,
+    Width,
+    Name,
+    Id,
+    Gang,
+    Qos,
+    Meta(String),
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "state" => Field::State,
+            "priority" => Field::Priority,
+            "effective" => Field::Effective,
+            "runs" => Field::Runs,
+            "waited" => Field::Waited,
+            "cpu" => Field::Cpu,
+            "width" => Field::Width,
+            "name" => Field::Name,
+            "id" => Field::Id,
+            "gang" => Field::Gang,
+            "qos" => Field::Qos,
+            _ => match s.strip_prefix("meta.") {
+                Some(key) if !key.is_empty() => Field::Meta(key.to_string()),
+                _ => return Err(format!("Unknown filter field: {}", s)),
+            },
+        })
+    }
+}
+
+impl Field {
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Field::Priority
+                | Field::Effective
+                | Field::Runs
+                | Field::Waited
+                | Field::Cpu
+                | Field::Width
+        )
+    }
+
+    fn number(&self, task: &Task) -> f64 {
+        match self {
+            Field::Priority => task.priority.into(),
+            Field::Effective => task.effective_priority().into(),
+            Field::Runs => task.runs.into(),
+            Field::Waited => task.waited as f64,
+            Field::Cpu => task.cpu_time.as_secs_f64(),
+            Field::Width => task.get_spec().width as f64,
+            _ => f64::NAN,
+        }
+    }
+
+    fn text(&self, task: &Task) -> Option<String> {
+        match self {
+            Field::State => Some(task.state.to_string()),
+            Field::Name => Some(task.name()),
+            Field::Id => Some(task.get_id().to_string()),
+            Field::Gang => task.get_spec().gang.clone(),
+            Field::Qos => Some(task.get_spec().qos.to_string()),
+            Field::Meta(key) => task.get_spec().metadata.get(key).cloned(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Value {
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Field, Op, Value),
+}
+
+impl Expr {
+    fn matches(&self, task: &Task) -> bool {
+        match self {
+            Expr::And(a, b) => a.matches(task) && b.matches(task),
+            Expr::Or(a, b) => a.matches(task) || b.matches(task),
+            Expr::Not(expr) => !expr.matches(task),
+            Expr::Compare(field, op, Value::Number(value)) => field
+                .number(task)
+                .partial_cmp(value)
+                .is_some_and(|ordering| op.holds(ordering)),
+            Expr::Compare(field, op, Value::Text(value)) => match field.text(task) {
+                Some(text) if *field == Field::State || *field == Field::Id => {
+                    let text = match text.split_once(':') {
+                        Some((state, _)) if *field == Field::State && !value.contains(':') => {
+                            state.to_string()
+                        }
+                        _ => text,
+                    };
+                    op.holds(text.to_ascii_uppercase().cmp(&value.to_ascii_uppercase()))
+                }
+                Some(text) => op.holds(text.as_str().cmp(value)),
+                None => *op == Op::Ne,
+            },
+        }
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '\'' || c == '"' {
+            chars.next();
+            let word: String = chars.by_ref().take_while(|&next| next != c).collect();
+            tokens.push(Token::Word(word));
+            continue;
+        }
+        if c.is_alphanumeric() || "_.-:/".contains(c) {
+            let mut word = String::new();
+            while let Some(&next) = chars.peek() {
+                if !(next.is_alphanumeric() || "_.-:/".contains(next)) {
+                    break;
+                }
+                word.push(next);
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+            continue;
+        }
+
+        chars.next();
+        let next = chars.peek().copied();
+        let (token, pair) = match (c, next) {
+            ('&', Some('&')) => (Token::And, true),
+            ('|', Some('|')) => (Token::Or, true),
+            ('=', Some('=')) => (Token::Op(Op::Eq), true),
+            ('!', Some('=')) => (Token::Op(Op::Ne), true),
+            ('<', Some('=')) => (Token::Op(Op::Le), true),
+            ('>', Some('=')) => (Token::Op(Op::Ge), true),
+            ('<', _) => (Token::Op(Op::Lt), false),
+            ('>', _) => (Token::Op(Op::Gt), false),
+            ('!', _) => (Token::Not, false),
+            ('(', _) => (Token::Open, false),
+            (')', _) => (Token::Close, false),
+            _ => return Err(format!("Unexpected character in filter: {}", c)),
+        };
+        if pair {
+            chars.next();
+        }
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Peekable<IntoIter<Token>>,
+}
+
+impl Parser {
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.tokens.next_if_eq(&Token::Or).is_some() {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        while self.tokens.next_if_eq(&Token::And).is_some() {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.tokens.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::Open) => {
+                let expr = self.or()?;
+                match self.tokens.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("Missing `)` in filter".to_string()),
+                }
+            }
+            Some(Token::Word(field)) => self.compare(field.parse()?),
+            Some(token) => Err(format!("Unexpected {:?} in filter", token)),
+            None => Err("Incomplete filter".to_string()),
+        }
+    }
+
+    fn compare(&mut self, field: Field) -> Result<Expr, String> {
+        let Some(Token::Op(op)) = self.tokens.next() else {
+            return Err("Expected a comparison like `priority>3` in filter".to_string());
+        };
+        let Some(Token::Word(value)) = self.tokens.next() else {
+            return Err("Missing value after the comparison in filter".to_string());
+        };
+
+        if field.is_numeric() {
+            let number = value
+                .parse()
+                .map_err(|_| format!("Expected a number in filter: {}", value))?;
+            return Ok(Expr::Compare(field, op, Value::Number(number)));
+        }
+        if field == Field::State && !value.eq_ignore_ascii_case("blocked") {
+            value.parse::<State>()?;
+        }
+        if field == Field::State && !matches!(op, Op::Eq | Op::Ne) {
+            return Err("state can only be compared with == or !=".to_string());
+        }
+        Ok(Expr::Compare(field, op, Value::Text(value)))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Selector {
+    text: String,
+    expr: Expr,
+}
+
+impl FromStr for Selector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?.into_iter().peekable(),
+        };
+        let expr = parser.or()?;
+        if let Some(token) = parser.tokens.next() {
+            return Err(format!("Unexpected {:?} in filter", token));
+        }
+        Ok(Self {
+            text: s.trim().to_string(),
+            expr,
+        })
+    }
+}
+
+impl std::fmt::Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl Selector {
+    pub fn matches(&self, task: &Task) -> bool {
+        self.expr.matches(task)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::Spec;
+
+    #[test]
+    fn filters_combine_comparisons_on_task_fields() {
+        let mut waiting = Task::from_spec(Spec {
+            metadata: [("run".to_string(), "baseline".to_string())].into(),
+            ..Spec::new("/bin/sleep", None, 5)
+        });
+        waiting.set_state(State::Waiting);
+        let mut ready = Task::new("/bin/echo", None, 2);
+        ready.set_state(State::Ready);
+
+        let selector: Selector = "state==Waiting && priority>3".parse().unwrap();
+        assert!(selector.matches(&waiting));
+        assert!(!selector.matches(&ready));
+
+        let selector: Selector = "!(name == sleep) || meta.run == 'baseline'"
+            .parse()
+            .unwrap();
+        assert!(selector.matches(&waiting) && selector.matches(&ready));
+
+        let selector: Selector = "meta.run != baseline && priority <= 2".parse().unwrap();
+        assert!(!selector.matches(&waiting) && selector.matches(&ready));
+
+        waiting.set_state(State::Blocked(crate::task::BlockReason::Io));
+        assert!("state==blocked"
+            .parse::<Selector>()
+            .unwrap()
+            .matches(&waiting));
+        assert!(!"state==blocked:dependency"
+            .parse::<Selector>()
+            .unwrap()
+            .matches(&waiting));
+
+        assert!("state==asleep".parse::<Selector>().is_err());
+        assert!("priority>high".parse::<Selector>().is_err());
+        assert!("colour==red".parse::<Selector>().is_err());
+        assert!("state==ready &&".parse::<Selector>().is_err());
+        assert!("(runs>1".parse::<Selector>().is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use super::Simulator;
+use crate::clock::{self, Clock, RealClock};
+use crate::control::{Message, Reply};
+use crate::summary::RunSummary;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::time::Instant;
+
+impl Simulator {
+    async fn next_message_async(&mut self, control: &mut UnboundedReceiver<Message>) {
+        match control.recv().await {
+            Some(message) => self.handle(message),
+            None if self.paused_at.is_some() => self.resume(&Reply::Stdout),
+            None => self.shutdown = true,
+        }
+    }
+
+    async fn wait_for_work_async(&mut self, control: &mut UnboundedReceiver<Message>) {
+        while !self.shutdown && (self.paused_at.is_some() || (self.daemon && self.idle())) {
+            self.next_message_async(control).await;
+        }
+    }
+
+    async fn wait_quantum_async(&mut self, control: &mut UnboundedReceiver<Message>) {
+        let mut remaining = self.slice.div_f64(self.time_scale);
+
+        while !self.shutdown && !remaining.is_zero() {
+            if self.paused_at.is_some() {
+                self.next_message_async(control).await;
+                continue;
+            }
+
+            // tokio's timers have millisecond resolution, so anything below a
+            // millisecond is slept on the thread instead.
+            let timer = Duration::from_millis(remaining.as_millis() as u64);
+            if timer.is_zero() {
+                RealClock.sleep_until(clock::Instant::now() + remaining);
+                break;
+            }
+
+            let started = Instant::now();
+            tokio::select! {
+                message = control.recv() => match message {
+                    Some(message) => self.handle(message),
+                    None => tokio::time::sleep(timer).await,
+                },
+                _ = tokio::time::sleep(timer) => {}
+            }
+            remaining = remaining.saturating_sub(started.elapsed());
+        }
+    }
+
+    /// Runs the same loop as `run`, with the quantum timer and control messages multiplexed by
+    /// tokio. Children are still polled through the executor at the end of each quantum; the
+    /// polls never block, so they are not moved onto tokio tasks.
+    pub async fn run_async(&mut self, mut control: UnboundedReceiver<Message>) -> RunSummary {
+        self.reject_oversized();
+
+        loop {
+            self.wait_for_work_async(&mut control).await;
+
+            if self.shutdown {
+                self.stop();
+                return self.summary();
+            }
+
+            self.start_quantum();
+            self.wait_quantum_async(&mut control).await;
+            if self.end_quantum() {
+                break;
+            }
+        }
+
+        self.complete();
+        self.summary()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::control::Command;
+    use crate::executor::{MockExecutor, Outcome};
+    use crate::simulator::DEFAULT_QUANTUM;
+    use crate::task::{ExitCode, Task};
+    use std::sync::mpsc;
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            Task::new("short", None, 2),
+            Task::new("long", None, 1),
+            Task::new("broken", None, 3),
+        ]
+    }
+
+    fn executor() -> MockExecutor {
+        MockExecutor::default()
+            .script("short", 1, Outcome::Exit(ExitCode::Success))
+            .script("long", 3, Outcome::Exit(ExitCode::Success))
+            .script("broken", 2, Outcome::Exit(ExitCode::Failure))
+    }
+
+    fn simulator(tasks: Vec<Task>, executor: &MockExecutor) -> Simulator {
+        let (_control_tx, control_rx) = mpsc::channel();
+        Simulator::new(tasks, control_rx)
+            .clock(ManualClock::new())
+            .executor(executor.clone())
+            .record_trace()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn async_run_matches_the_blocking_run() {
+        let sync_executor = executor();
+        let mut blocking = simulator(tasks(), &sync_executor);
+        blocking.run();
+
+        let async_executor = executor();
+        let mut asynchronous = simulator(tasks(), &async_executor);
+        let (_control_tx, control_rx) = tokio::sync::mpsc::unbounded_channel();
+        let started = Instant::now();
+        asynchronous.run_async(control_rx).await;
+
+        let name = |id| {
+            blocking.tasks[id]
+                .get_path_to_binary()
+                .display()
+                .to_string()
+        };
+        let async_name = |id| {
+            asynchronous.tasks[id]
+                .get_path_to_binary()
+                .display()
+                .to_string()
+        };
+        assert_eq!(
+            blocking.trace.as_ref().unwrap().render(name),
+            asynchronous.trace.as_ref().unwrap().render(async_name)
+        );
+        assert_eq!(
+            started.elapsed(),
+            DEFAULT_QUANTUM * asynchronous.quanta as u32
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn control_messages_are_handled_between_timers() {
+        let executor = executor();
+        let mut simulator = simulator(tasks(), &executor);
+        let (control_tx, control_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (reply_tx, mut reply_rx) = tokio::sync::mpsc::unbounded_channel();
+        control_tx
+            .send(Message {
+                command: Command::Shutdown,
+                reply: Reply::Async(reply_tx),
+            })
+            .unwrap();
+
+        simulator.run_async(control_rx).await;
+
+        assert_eq!(reply_rx.recv().await.as_deref(), Some("Shutting down"));
+        assert_eq!(simulator.quanta, 1);
+    }
+}
@@ -0,0 +1,3349 @@
+use crate::affinity::{self, Affinity};
+#[cfg(all(unix, feature = "audit"))]
+use crate::audit::{Action, Audit};
+use crate::backfill;
+use crate::checkpoint;
+use crate::clock::{self, Clock, Instant, RealClock, ScaledClock, SystemTime};
+use crate::comparison::KernelComparison;
+use crate::config;
+use crate::control::{Command, Message, Reply, Selection, TaskRef};
+use crate::deadline::DeadlineTracker;
+use crate::debugger::{self, Breakpoint, Debugger};
+use crate::dependency;
+use crate::executor::{DefaultExecutor, Executor};
+use crate::experiment::Experiment;
+use crate::findings;
+use crate::gang::{self, GangTracker};
+use crate::graph;
+use crate::interactivity::Interactivity;
+use crate::limits::{Admission, Limits};
+use crate::metrics::{self, Exporter};
+use crate::nice::NiceTable;
+use crate::otel;
+use crate::output::{self, Style};
+use crate::preemption::PreemptionLatency;
+use crate::qos::{self, QosTracker};
+use crate::registry::Registry;
+use crate::report;
+use crate::runqueue::RunQueue;
+use crate::scenario::{self, Scenario};
+use crate::scheduler::{self, Scheduler};
+use crate::selector::Selector;
+use crate::sporadic::SporadicServer;
+use crate::starvation::{self, StarvationDetector};
+use crate::status;
+#[cfg(feature = "store")]
+use crate::store::Store;
+use crate::summary::{RunSummary, TaskResult};
+use crate::syscalls::SyscallCounter;
+use crate::task::{self, Task, TaskSnapshot};
+use crate::throttle::{self, Throttle};
+use crate::throughput::Throughput;
+use crate::timeseries::TimeSeries;
+use crate::trace::{self, Trace};
+use crate::watchdog::Watchdog;
+use crate::webhook::Webhooks;
+use crate::window::Window;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+use ulid::Ulid;
+
+pub const DEFAULT_QUANTUM: Duration = Duration::from_millis(150);
+pub const DEFAULT_STATUS_TIMEOUT_MS: u64 = 5_000;
+
+pub fn parse_quantum(value: &str) -> Result<Duration, String> {
+    let quantum = clock::parse_duration(value)?;
+    if quantum.is_zero() {
+        return Err(format!("The quantum must be longer than zero: {}", value));
+    }
+    Ok(quantum)
+}
+
+pub struct Stats {
+    pub tasks: usize,
+    pub quanta: u64,
+    pub decisions: u64,
+    pub dispatch_time: Duration,
+    pub trace_events: usize,
+    pub migrations: u64,
+    pub migration_cost: Duration,
+}
+
+fn guarded<T>(action: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(action)).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string())
+    })
+}
+
+fn panicked(message: &str) -> String {
+    format!("a panic while handling it: {}", message)
+}
+
+fn is_special(spec: &task::Spec) -> bool {
+    spec.qos != task::Qos::Burstable
+        || spec.gang.is_some()
+        || spec.cpu_quota.is_some()
+        || !spec.after.is_empty()
+        || !spec.apart_from.is_empty()
+}
+
+pub struct Simulator {
+    tasks: Registry,
+    run_queue: RunQueue,
+    running: BTreeSet<Ulid>,
+    waiting: Vec<Ulid>,
+    suspended: BTreeSet<Ulid>,
+    frozen: BTreeSet<Ulid>,
+    special: usize,
+    status_tx: mpsc::Sender<(Ulid, task::Status)>,
+    status_rx: mpsc::Receiver<(Ulid, task::Status)>,
+    control_rx: mpsc::Receiver<Message>,
+    clock: Box<dyn Clock>,
+    executor: Box<dyn Executor>,
+    #[cfg(feature = "store")]
+    store: Option<Store>,
+    #[cfg(all(unix, feature = "audit"))]
+    audit: Option<Audit>,
+    #[cfg(all(unix, feature = "dbus"))]
+    dbus: Option<crate::dbus::Service>,
+    daemon: bool,
+    input_open: bool,
+    config: Option<config::Watch>,
+    concurrency: NonZeroUsize,
+    quantum: Duration,
+    slice: Duration,
+    slices: HashMap<Ulid, Duration>,
+    max_quanta: Option<u64>,
+    max_sim_time: Option<Duration>,
+    window: Window,
+    scenario: Option<Scenario>,
+    scheduler: Box<dyn Scheduler>,
+    starvation: StarvationDetector,
+    backfill: bool,
+    in_order_starts: usize,
+    backfilled_starts: usize,
+    gangs: GangTracker,
+    deadlines: DeadlineTracker,
+    affinity: Affinity,
+    qos: QosTracker,
+    throttle: Throttle,
+    server: Option<SporadicServer>,
+    watchdog: Option<Watchdog>,
+    webhooks: Option<Webhooks>,
+    experiment: Option<Experiment>,
+    nice: Option<NiceTable>,
+    time_scale: f64,
+    interactivity: Option<Interactivity>,
+    donate: bool,
+    admission: Admission,
+    kernel_comparison: Option<KernelComparison>,
+    syscalls: Option<SyscallCounter>,
+    preemption: Option<PreemptionLatency>,
+    explain: bool,
+    verbose: u8,
+    debugger: Option<Debugger>,
+    findings: bool,
+    trace: Option<Trace>,
+    trace_path: Option<PathBuf>,
+    event_stream: Option<trace::EventFormat>,
+    graph_path: Option<PathBuf>,
+    report_path: Option<PathBuf>,
+    timeseries: Option<TimeSeries>,
+    timeseries_path: Option<PathBuf>,
+    throughput: Option<Throughput>,
+    otlp_endpoint: Option<String>,
+    metrics: Option<Exporter>,
+    started_at: SystemTime,
+    elapsed: Duration,
+    quanta: u64,
+    decisions: u64,
+    dispatch_time: Duration,
+    paused_at: Option<Instant>,
+    shutdown: bool,
+    unreported: BTreeSet<Ulid>,
+    status_timeout: Duration,
+}
+
+impl Simulator {
+    pub fn new(tasks: Vec<Task>, control_rx: mpsc::Receiver<Message>) -> Self {
+        let (status_tx, status_rx) = mpsc::channel();
+
+        let mut simulator = Self {
+            tasks: Registry::default(),
+            run_queue: RunQueue::default(),
+            running: BTreeSet::new(),
+            waiting: Vec::new(),
+            suspended: BTreeSet::new(),
+            frozen: BTreeSet::new(),
+            special: 0,
+            status_tx,
+            status_rx,
+            control_rx,
+            clock: Box::new(RealClock),
+            executor: Box::new(DefaultExecutor::new()),
+            #[cfg(feature = "store")]
+            store: None,
+            #[cfg(all(unix, feature = "audit"))]
+            audit: None,
+            #[cfg(all(unix, feature = "dbus"))]
+            dbus: None,
+            daemon: false,
+            input_open: false,
+            config: None,
+            concurrency: NonZeroUsize::MIN,
+            quantum: DEFAULT_QUANTUM,
+            slice: DEFAULT_QUANTUM,
+            slices: HashMap::new(),
+            max_quanta: None,
+            max_sim_time: None,
+            window: Window::default(),
+            scenario: None,
+            scheduler: scheduler::build(scheduler::Policy::Priority, None),
+            starvation: StarvationDetector::new(Duration::from_millis(
+                starvation::DEFAULT_THRESHOLD_MS,
+            )),
+            backfill: false,
+            in_order_starts: 0,
+            backfilled_starts: 0,
+            gangs: GangTracker::default(),
+            deadlines: DeadlineTracker::default(),
+            affinity: Affinity::default(),
+            qos: QosTracker::new(qos::DEFAULT_WINDOW),
+            throttle: Throttle::new(throttle::DEFAULT_PERIOD),
+            server: None,
+            watchdog: None,
+            webhooks: None,
+            experiment: None,
+            nice: None,
+            time_scale: 1.0,
+            interactivity: None,
+            donate: false,
+            admission: Admission::new(Limits::default()),
+            kernel_comparison: None,
+            syscalls: None,
+            preemption: None,
+            explain: false,
+            verbose: 0,
+            debugger: None,
+            findings: false,
+            trace: None,
+            trace_path: None,
+            event_stream: None,
+            graph_path: None,
+            report_path: None,
+            timeseries: None,
+            timeseries_path: None,
+            throughput: None,
+            otlp_endpoint: None,
+            metrics: None,
+            started_at: SystemTime::now(),
+            elapsed: Duration::ZERO,
+            quanta: 0,
+            decisions: 0,
+            dispatch_time: Duration::ZERO,
+            paused_at: None,
+            shutdown: false,
+            unreported: BTreeSet::new(),
+            status_timeout: Duration::from_millis(DEFAULT_STATUS_TIMEOUT_MS),
+        };
+
+        for task in tasks {
+            simulator.admit(task);
+        }
+
+        simulator
+    }
+
+    #[cfg(all(unix, feature = "audit"))]
+    pub fn audit(mut self, audit: Audit) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    #[cfg(all(unix, feature = "dbus"))]
+    pub fn dbus(mut self, service: crate::dbus::Service) -> Self {
+        self.dbus = Some(service);
+        self
+    }
+
+    #[cfg(feature = "store")]
+    pub fn with_store(mut self, store: Store) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        // The initial tasks were admitted against the default clock.
+        let now = self.clock.now();
+        for task in self.tasks.iter_mut() {
+            task.restart_clock(now);
+        }
+        self
+    }
+
+    pub fn executor(mut self, executor: impl Executor + 'static) -> Self {
+        if executor.tracks_cores() {
+            self.affinity.track_cores();
+        }
+        self.executor = Box::new(executor);
+        self
+    }
+
+    pub fn daemon(mut self, daemon: bool) -> Self {
+        self.daemon = daemon;
+        self
+    }
+
+    pub fn watch_config(mut self, watch: Option<config::Watch>) -> Self {
+        self.config = watch;
+        self
+    }
+
+    pub fn stream_tasks(mut self, enabled: bool) -> Self {
+        self.input_open = enabled;
+        self
+    }
+
+    pub fn concurrency(mut self, concurrency: NonZeroUsize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn quantum(mut self, quantum: Duration) -> Self {
+        self.quantum = quantum;
+        self
+    }
+
+    pub fn max_quanta(mut self, max_quanta: Option<u64>) -> Self {
+        self.max_quanta = max_quanta;
+        self
+    }
+
+    pub fn max_sim_time(mut self, max_sim_time: Option<Duration>) -> Self {
+        self.max_sim_time = max_sim_time;
+        self
+    }
+
+    pub fn measurement_window(mut self, window: Window) -> Self {
+        self.window = window;
+        self
+    }
+
+    pub fn scenario(mut self, scenario: Scenario) -> Self {
+        self.scenario = Some(scenario);
+        self
+    }
+
+    pub fn scheduler(mut self, scheduler: Box<dyn Scheduler>) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    pub fn starvation_threshold(mut self, threshold: Duration) -> Self {
+        self.starvation.set_threshold(threshold);
+        self
+    }
+
+    pub fn backfill(mut self, backfill: bool) -> Self {
+        self.backfill = backfill;
+        self
+    }
+
+    pub fn qos_window(mut self, window: u64) -> Self {
+        self.qos = QosTracker::new(window);
+        self
+    }
+
+    pub fn quota_period(mut self, period: u64) -> Self {
+        self.throttle = Throttle::new(period);
+        self
+    }
+
+    pub fn sporadic_server(mut self, server: Option<(u32, u32)>) -> Self {
+        self.server = server.map(|(budget, period)| SporadicServer::new(budget, period));
+        self
+    }
+
+    pub fn migration_cost(mut self, cost: Option<Duration>) -> Self {
+        if let Some(cost) = cost {
+            self.affinity.migration_cost(cost);
+        }
+        self
+    }
+
+    pub fn webhooks(mut self, urls: Vec<String>) -> Self {
+        self.webhooks = (!urls.is_empty()).then(|| Webhooks::spawn(urls));
+        self
+    }
+
+    pub fn experiment(mut self, experiment: Option<Experiment>) -> Self {
+        self.experiment = experiment;
+        self
+    }
+
+    pub fn nice(mut self, table: NiceTable) -> Self {
+        self.nice = Some(table);
+        self
+    }
+
+    pub fn time_scale(mut self, scale: f64) -> Self {
+        if scale != 1.0 {
+            let clock = std::mem::replace(&mut self.clock, Box::new(RealClock));
+            self.clock = Box::new(ScaledClock::new(clock, scale));
+            self.time_scale = scale;
+        }
+        self
+    }
+
+    pub fn watchdog(mut self, multiple: Option<u32>) -> Self {
+        self.watchdog = multiple.map(Watchdog::spawn);
+        self
+    }
+
+    pub fn status_timeout(mut self, timeout: Duration) -> Self {
+        self.status_timeout = timeout;
+        self
+    }
+
+    pub fn donate_priority(mut self, enabled: bool) -> Self {
+        self.donate = enabled;
+        self
+    }
+
+    pub fn interactivity_boost(mut self, enabled: bool) -> Self {
+        self.interactivity = enabled.then(Interactivity::default);
+        self
+    }
+
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.admission = Admission::new(limits);
+        self
+    }
+
+    pub fn compare_kernel(mut self, enabled: bool) -> Self {
+        self.kernel_comparison = enabled.then(KernelComparison::default);
+        self
+    }
+
+    pub fn count_syscalls(mut self, enabled: bool) -> Self {
+        self.syscalls = enabled.then(SyscallCounter::default);
+        self
+    }
+
+    pub fn measure_preemption(mut self, enabled: bool) -> Self {
+        self.preemption = enabled.then(PreemptionLatency::default);
+        self
+    }
+
+    pub fn verbose(mut self, level: u8) -> Self {
+        self.verbose = level;
+        self
+    }
+
+    pub fn explain(mut self, explain: bool) -> Self {
+        self.explain = explain;
+        self
+    }
+
+    pub fn debug(mut self, enabled: bool) -> Self {
+        if enabled {
+            self.debugger = Some(Debugger::default());
+        }
+        self
+    }
+
+    pub fn breakpoint(mut self, breakpoint: Breakpoint) -> Self {
+        self.debugger
+            .get_or_insert_with(|| Debugger::new(debugger::Mode::Continue))
+            .add(breakpoint);
+        self
+    }
+
+    pub fn findings(mut self, enabled: bool) -> Self {
+        if enabled && self.trace.is_none() {
+            self.start_trace();
+        }
+        self.findings = enabled;
+        self
+    }
+
+    pub fn trace(mut self, path: Option<PathBuf>) -> Self {
+        if path.is_some() {
+            self.start_trace();
+        }
+        self.trace_path = path;
+        self
+    }
+
+    pub fn event_stream(mut self, format: Option<trace::EventFormat>) -> Self {
+        self.event_stream = format;
+        let admitted: Vec<Ulid> = self
+            .tasks
+            .iter()
+            .filter(|task| task.state != task::State::Terminated)
+            .map(Task::get_id)
+            .collect();
+        for id in admitted {
+            self.stream(id, trace::Kind::Admitted);
+        }
+        self
+    }
+
+    pub fn graph(mut self, path: Option<PathBuf>) -> Self {
+        self.graph_path = path;
+        self
+    }
+
+    pub fn report(mut self, path: Option<PathBuf>) -> Self {
+        if path.is_some() && self.trace.is_none() {
+            self.start_trace();
+        }
+        self.report_path = path;
+        self
+    }
+
+    pub fn timeseries(mut self, path: Option<PathBuf>) -> Self {
+        self.timeseries = path.as_ref().map(|_| TimeSeries::default());
+        self.timeseries_path = path;
+        self
+    }
+
+    pub fn host_stats(mut self, enabled: bool) -> Self {
+        if enabled {
+            self.timeseries = self.timeseries.map(TimeSeries::with_host);
+        }
+        self
+    }
+
+    pub fn throughput(mut self, enabled: bool) -> Self {
+        self.throughput = enabled.then(|| {
+            let mut throughput = Throughput::default();
+            for task in self.tasks.iter() {
+                if task.state != task::State::Terminated {
+                    throughput.admitted(task.get_id(), self.quanta);
+                }
+            }
+            throughput
+        });
+        self
+    }
+
+    pub fn otlp(mut self, endpoint: Option<String>) -> Self {
+        if endpoint.is_some() && self.trace.is_none() {
+            self.start_trace();
+        }
+        self.otlp_endpoint = endpoint;
+        self
+    }
+
+    pub fn metrics(mut self, exporter: Exporter) -> Self {
+        self.metrics = Some(exporter);
+        self
+    }
+
+    pub fn record_trace(mut self) -> Self {
+        self.start_trace();
+        self
+    }
+
+    fn start_trace(&mut self) {
+        let mut trace = Trace::default();
+        for task in self.tasks.iter() {
+            if task.state != task::State::Terminated {
+                trace.record(self.quanta, task.get_id(), trace::Kind::Admitted);
+            }
+        }
+        self.trace = Some(trace);
+    }
+
+    fn stream(&self, task: Ulid, kind: trace::Kind) {
+        if self.event_stream == Some(trace::EventFormat::Ndjson) {
+            let event = trace::Event {
+                quantum: self.quanta,
+                task,
+                kind,
+            };
+            let metadata = &self.tasks[task].get_spec().metadata;
+            println!("{}", event.to_ndjson(self.quantum, metadata));
+        }
+    }
+
+    fn record(&mut self, id: Ulid, kind: trace::Kind) {
+        if kind == trace::Kind::Preempted {
+            self.tasks[id].preemptions += 1;
+        }
+        self.stream(id, kind);
+        #[cfg(all(unix, feature = "dbus"))]
+        if let Some(dbus) = &self.dbus {
+            dbus.state_changed(&id.to_string(), &self.tasks[id].name(), &kind.to_string());
+        }
+        if let Some(trace) = &mut self.trace {
+            trace.record(self.quanta, id, kind);
+        }
+    }
+
+    fn save_trace(&self) {
+        let (Some(trace), Some(path)) = (&self.trace, &self.trace_path) else {
+            return;
+        };
+
+        match trace.save(path) {
+            Ok(()) => crate::report!("Trace written to {}", path.display()),
+            Err(err) => eprintln!("Failed to write trace {}: {}", path.display(), err),
+        }
+    }
+
+    fn save_graph(&self) {
+        let Some(path) = &self.graph_path else {
+            return;
+        };
+
+        let unfinished: Vec<Ulid> = self
+            .tasks
+            .iter()
+            .filter(|task| task.state != task::State::Terminated)
+            .map(Task::get_id)
+            .collect();
+        match graph::save(&self.tasks, &self.lock_holders(&unfinished), path) {
+            Ok(()) => crate::report!("Graph written to {}", path.display()),
+            Err(err) => eprintln!("Failed to write graph {}: {}", path.display(), err),
+        }
+    }
+
+    fn save_report(&self) {
+        let Some(path) = &self.report_path else {
+            return;
+        };
+
+        let onoff = |enabled: bool| if enabled { "on" } else { "off" }.to_string();
+        let run = report::Run {
+            configuration: vec![
+                ("Policy", self.scheduler.name().to_string()),
+                ("Concurrency", self.concurrency.to_string()),
+                (
+                    "Quantum",
+                    format!("{} ms", self.quantum.as_secs_f64() * 1000.0),
+                ),
+                (
+                    "Starvation Threshold",
+                    format!("{} seconds", self.starvation.threshold().as_secs_f64()),
+                ),
+                ("Backfill", onoff(self.backfill)),
+                ("Priority Donation", onoff(self.donate)),
+            ],
+            summary: self.summary(),
+            tasks: &self.tasks,
+            events: self.events(),
+            findings: findings::analyze(
+                self.events(),
+                &self.tasks,
+                self.quantum,
+                self.starvation.threshold(),
+            ),
+        };
+        match report::save(&run, path) {
+            Ok(()) => crate::report!("Report written to {}", path.display()),
+            Err(err) => eprintln!("Failed to write report {}: {}", path.display(), err),
+        }
+    }
+
+    fn save_timeseries(&self) {
+        let (Some(series), Some(path)) = (&self.timeseries, &self.timeseries_path) else {
+            return;
+        };
+
+        match series.save(path) {
+            Ok(()) => crate::report!("Time series written to {}", path.display()),
+            Err(err) => eprintln!("Failed to write time series {}: {}", path.display(), err),
+        }
+    }
+
+    fn save_experiment(&self) {
+        let Some(experiment) = &self.experiment else {
+            return;
+        };
+
+        match experiment.finish(&self.summary()) {
+            Ok(index) => crate::report!(
+                "Experiment run {} added to {}",
+                experiment.directory().display(),
+                index.display()
+            ),
+            Err(err) => eprintln!("Failed to update the experiment index: {}", err),
+        }
+    }
+
+    fn export_spans(&self) {
+        let (Some(trace), Some(endpoint)) = (&self.trace, &self.otlp_endpoint) else {
+            return;
+        };
+
+        let body = otel::spans(
+            trace.events(),
+            &self.tasks,
+            self.started_at,
+            self.quantum,
+            task::new_id().into(),
+        );
+        match otel::export(endpoint, &body) {
+            Ok(()) => crate::report!("Spans exported to {}", endpoint),
+            Err(err) => eprintln!("Failed to export spans to {}: {}", endpoint, err),
+        }
+    }
+
+    fn admit(&mut self, mut task: Task) {
+        if task.state != task::State::Terminated && dependency::leads_back(&self.tasks, &task) {
+            crate::say!(
+                "Rejected PID: {} whose --after dependencies lead back to it",
+                task.label()
+            );
+            task.set_state(task::State::Terminated);
+            task.exit_code = Some(task::ExitCode::Failure);
+        }
+
+        if task.state == task::State::Terminated {
+            task.print();
+        } else {
+            crate::say!(
+                "Created PID: {} with priority: {}",
+                output::paint(task.label(), Style::Bold),
+                task.priority,
+            );
+            task.set_state(task::State::Ready);
+            if !task.has_started() {
+                self.unreported.insert(task.get_id());
+            }
+        }
+
+        self.gangs.admitted(&task);
+        task.start_clock(self.clock.now());
+
+        let ready = task.state == task::State::Ready;
+        let id = self.tasks.insert(task);
+        if ready {
+            if is_special(self.tasks[id].get_spec()) {
+                self.special += 1;
+            }
+            self.record(id, trace::Kind::Admitted);
+            if let Some(throughput) = &mut self.throughput {
+                throughput.admitted(id, self.quanta);
+            }
+            self.deadlines.admitted(&self.tasks[id], self.elapsed);
+            self.affinity.admitted(&self.tasks[id]);
+            self.enqueue(id);
+        }
+    }
+
+    pub fn add_task(&mut self, task: Task) -> Ulid {
+        let id = task.get_id();
+        self.admit(task);
+        id
+    }
+
+    pub fn tasks(&self) -> &Registry {
+        &self.tasks
+    }
+
+    pub fn events(&self) -> &[trace::Event] {
+        self.trace.as_ref().map_or(&[], Trace::events)
+    }
+
+    fn enqueue(&mut self, id: Ulid) {
+        let task = &mut self.tasks[id];
+        task.enqueued_at = self.quanta + 1;
+        if task.state == task::State::Waiting {
+            self.waiting.push(id);
+        }
+        let key = task.effective_priority();
+        self.run_queue.push(id, key, self.tasks.position(id) as u64);
+        self.starvation.enqueued(id, self.quanta + 1);
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            tasks: self.tasks.len(),
+            quanta: self.quanta,
+            decisions: self.decisions,
+            dispatch_time: self.dispatch_time,
+            trace_events: self.trace.as_ref().map_or(0, Trace::len),
+            migrations: self.affinity.migrations().0,
+            migration_cost: self.affinity.penalty(),
+        }
+    }
+
+    fn submit(&mut self, spec: task::Spec, reply: &Reply) -> Option<Ulid> {
+        let needed = spec.width
+            + spec
+                .gang
+                .as_deref()
+                .map_or(0, |g| gang::gang_width(g, &self.tasks));
+        if needed > self.concurrency.get() {
+            reply.send(format!(
+                "Task needs {} slots but concurrency is {}",
+                needed, self.concurrency
+            ));
+            return None;
+        }
+
+        let owned = self
+            .tasks
+            .iter()
+            .filter(|t| t.state != task::State::Terminated && t.get_spec().owner == spec.owner)
+            .count();
+        let owner = spec.owner.as_deref().unwrap_or("local");
+        if let Err(reason) =
+            self.admission
+                .check(owner, self.clock.now(), self.run_queue.len(), owned)
+        {
+            reply.send(reason);
+            return None;
+        }
+
+        let task = Task::from_spec(spec);
+
+        #[cfg(feature = "store")]
+        if let Some(store) = &self.store {
+            if let Err(err) = store.insert(&task) {
+                reply.send(format!("Failed to persist task: {}", err));
+                return None;
+            }
+        }
+
+        #[cfg(all(unix, feature = "audit"))]
+        if let Some(audit) = &self.audit {
+            audit.record(Action::Submit, &task);
+        }
+
+        reply.send(format!("Submitted PID: {}", task.get_id()));
+        let id = task.get_id();
+        self.admit(task);
+        Some(id)
+    }
+
+    fn renice(&mut self, id: Ulid, priority: u8, reply: &Reply) {
+        if !self.tasks.contains(id) || self.tasks[id].state == task::State::Terminated {
+            reply.send(format!("No unfinished task with PID: {}", id));
+            return;
+        }
+
+        let task = &mut self.tasks[id];
+        task.priority = priority;
+        let key = task.effective_priority();
+        if self.run_queue.remove(id) {
+            self.run_queue.push(id, key, self.tasks.position(id) as u64);
+        }
+        self.set_nice(id);
+        reply.send(format!("Reniced PID: {} to priority: {}", id, priority));
+    }
+
+    fn set_nice(&mut self, id: Ulid) {
+        let Some(table) = &self.nice else {
+            return;
+        };
+        let task = &mut self.tasks[id];
+        let spec = task.get_spec();
+        let (Some(pid), None, None) = (task.get_pid(), &spec.container, &spec.host) else {
+            return;
+        };
+        let nice = table.nice(task.priority);
+        match self.executor.set_nice(pid, nice) {
+            Ok(()) => task.nice = Some(nice),
+            Err(err) => eprintln!(
+                "Failed to set nice {} for PID: {}: {}",
+                nice,
+                task.label(),
+                err
+            ),
+        }
+    }
+
+    fn kill(&mut self, id: Ulid, reply: &Reply) {
+        if !self.tasks.contains(id) || self.tasks[id].state == task::State::Terminated {
+            reply.send(format!("No unfinished task with PID: {}", id));
+            return;
+        }
+
+        let task = &mut self.tasks[id];
+        #[cfg(all(unix, feature = "audit"))]
+        if let (Some(audit), Some(_)) = (&self.audit, task.get_pid()) {
+            audit.record(Action::Kill, task);
+        }
+        if task.has_started() {
+            task.kill(self.executor.as_mut());
+            task.duration += task.elapsed(self.clock.as_ref());
+        } else {
+            self.unreported.remove(&id);
+        }
+        task.set_state(task::State::Terminated);
+        task.exit_code = Some(task::ExitCode::Failure);
+
+        self.running.remove(&id);
+        self.run_queue.remove(id);
+        self.suspended.remove(&id);
+        self.frozen.remove(&id);
+        self.starvation.dequeued(id, self.quanta + 1);
+        reply.send(format!("Killed PID: {}", id));
+        self.finish(id);
+    }
+
+    fn suspend(&mut self, id: Ulid, reply: &Reply) {
+        if !self.tasks.contains(id) || self.tasks[id].state == task::State::Terminated {
+            reply.send(format!("No unfinished task with PID: {}", id));
+            return;
+        }
+        if self.suspended.contains(&id) {
+            reply.send(format!("PID: {} is already suspended", id));
+            return;
+        }
+
+        let task = &mut self.tasks[id];
+        if self.running.contains(&id) && task.state == task::State::Running {
+            if let Err(err) = task.freeze(self.executor.as_mut()) {
+                reply.send(format!("Failed to suspend PID: {}: {}", id, err));
+                return;
+            }
+        }
+        self.suspended.insert(id);
+        self.frozen.remove(&id);
+        self.running.remove(&id);
+        task.set_state(task::State::Suspended);
+        self.run_queue.remove(id);
+        self.starvation.dequeued(id, self.quanta + 1);
+        self.record(id, trace::Kind::Suspended);
+        reply.send(format!("Suspended PID: {}", id));
+    }
+
+    fn resume_task(&mut self, id: Ulid, reply: &Reply) {
+        if !self.tasks.contains(id) || !self.suspended.remove(&id) {
+            reply.send(format!("PID: {} is not suspended", id));
+            return;
+        }
+
+        let task = &mut self.tasks[id];
+        task.set_state(if task.has_started() {
+            task::State::Waiting
+        } else {
+            task::State::Ready
+        });
+        self.record(id, trace::Kind::Resumed);
+        self.enqueue(id);
+        reply.send(format!("Resumed PID: {}", id));
+    }
+
+    fn finish(&mut self, id: Ulid) {
+        let exit_code = self.tasks[id].exit_code;
+        self.record(id, trace::Kind::Terminated { exit_code });
+        if let Some(throughput) = &mut self.throughput {
+            throughput.completed(id, self.quanta);
+        }
+        if let Some(series) = &mut self.timeseries {
+            series.completed();
+        }
+
+        let task = &self.tasks[id];
+        if is_special(task.get_spec()) {
+            self.special -= 1;
+        }
+        self.gangs.finished(self.quanta, task);
+        self.deadlines.finished(task, self.elapsed);
+        self.throttle.finish(task, self.quanta);
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.notify(&TaskResult::from_task(task, self.quantum));
+        }
+        if self
+            .debugger
+            .as_mut()
+            .is_some_and(|debugger| debugger.terminated(id))
+        {
+            crate::say!("Debugger: PID: {} terminated", id);
+        }
+
+        #[cfg(all(unix, feature = "audit"))]
+        if let Some(audit) = &self.audit {
+            audit.record(Action::Terminate, task);
+        }
+
+        #[cfg(feature = "store")]
+        if let Some(store) = &self.store {
+            if let Err(err) = store.record_outcome(task) {
+                eprintln!("Failed to persist outcome of {}: {}", task.get_id(), err);
+            }
+        }
+    }
+
+    fn dispatcher(&mut self) {
+        for id in std::mem::take(&mut self.waiting) {
+            let task = &mut self.tasks[id];
+            if task.state == task::State::Waiting {
+                task.set_state(task::State::Ready);
+            }
+        }
+
+        self.quanta += 1;
+        self.scheduler.begin_quantum(self.quanta);
+        self.executor.tick(self.quanta);
+        if self.donate {
+            self.donate_priorities();
+        }
+        if self.verbose > 0 {
+            crate::say!("{}", self.queue_view());
+        }
+
+        let plan = if self.scheduler.follows_run_queue()
+            && self.special == 0
+            && self.server.is_none()
+            && !self.backfill
+            && !self.explain
+            && !self.breaking()
+            && !self.executor.blocks()
+        {
+            self.plan_in_queue_order()
+        } else {
+            self.plan_scheduled()
+        };
+
+        if !plan.is_empty() && self.breaking() {
+            self.debug_break(&plan);
+            if self.shutdown {
+                return;
+            }
+        }
+        let dispatched: Vec<Ulid> = plan.iter().map(|&(id, _)| id).collect();
+        self.dispatch(plan);
+        self.affinity
+            .place(&self.tasks, &dispatched, self.concurrency.get());
+        if self.executor.tracks_cores() {
+            for id in dispatched {
+                if let (Some(pid), Some(core)) = (self.tasks[id].get_pid(), self.affinity.core(id))
+                {
+                    self.executor.placed(pid, core);
+                }
+            }
+        }
+        self.check_breakpoints();
+        self.sample_load();
+    }
+
+    fn sample_load(&mut self) {
+        let Some(series) = &mut self.timeseries else {
+            return;
+        };
+        let used: usize = self
+            .running
+            .iter()
+            .map(|&id| self.tasks[id].get_spec().width)
+            .sum();
+        series.record(
+            self.quanta,
+            self.quantum,
+            self.run_queue.len(),
+            self.running.len(),
+            used as f64 / self.concurrency.get() as f64,
+        );
+    }
+
+    fn check_breakpoints(&mut self) {
+        let Some(debugger) = &mut self.debugger else {
+            return;
+        };
+        for hit in debugger.observe(&self.tasks) {
+            crate::say!("{}", hit);
+        }
+    }
+
+    fn breaking(&self) -> bool {
+        self.debugger.as_ref().is_some_and(Debugger::breaks)
+    }
+
+    fn debug_break(&mut self, plan: &[(Ulid, bool)]) {
+        let next: Vec<String> = plan.iter().map(|(id, _)| id.to_string()).collect();
+        crate::say!(
+            "Debugger stopped before quantum {}, next: {}\nCommands: {}",
+            self.quanta,
+            next.join(", "),
+            debugger::COMMANDS
+        );
+
+        let stopped_at = self.clock.now();
+        if let Some(debugger) = &mut self.debugger {
+            debugger.stopped = true;
+        }
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.suspend();
+        }
+        while !self.shutdown && self.debugger.as_ref().is_some_and(|d| d.stopped) {
+            self.next_message();
+        }
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.resume();
+        }
+
+        let stopped_for = self.clock.now().saturating_duration_since(stopped_at);
+        for task in self.tasks.iter_mut() {
+            if task.state != task::State::Terminated {
+                task.add_paused_time(stopped_for);
+            }
+        }
+    }
+
+    fn set_debug_mode(&mut self, mode: debugger::Mode, reply: &Reply) {
+        if self.debugger.is_none() {
+            reply.send("Debugging is not enabled, start with --debug");
+            return;
+        }
+        if let debugger::Mode::Until(id) = mode {
+            if !self.tasks.contains(id) || self.tasks[id].state == task::State::Terminated {
+                reply.send(format!("No unfinished task with PID: {}", id));
+                return;
+            }
+        }
+
+        if let Some(debugger) = &mut self.debugger {
+            debugger.mode = mode;
+            debugger.stopped = false;
+        }
+        reply.send(match mode {
+            debugger::Mode::Step => "Stepping to the next dispatch".to_string(),
+            debugger::Mode::Continue => "Continuing".to_string(),
+            debugger::Mode::Until(id) => format!("Continuing until PID: {} terminates", id),
+        });
+    }
+
+    fn set_breakpoint(&mut self, breakpoint: Option<Breakpoint>, reply: &Reply) {
+        if let Some(id) = breakpoint.and_then(|b| b.task) {
+            if !self.tasks.contains(id) {
+                reply.send(format!("No task with PID: {}", id));
+                return;
+            }
+        }
+        let Some(debugger) = &mut self.debugger else {
+            reply.send("Debugging is not enabled, start with --debug");
+            return;
+        };
+
+        match breakpoint {
+            Some(breakpoint) => {
+                let number = debugger.add(breakpoint);
+                reply.send(format!("Breakpoint {}: {}", number, breakpoint));
+            }
+            None => reply.send(debugger.list()),
+        }
+    }
+
+    fn delete_breakpoint(&mut self, number: usize, reply: &Reply) {
+        if self
+            .debugger
+            .as_mut()
+            .is_some_and(|debugger| debugger.delete(number))
+        {
+            reply.send(format!("Deleted breakpoint {}", number));
+        } else {
+            reply.send(format!("No breakpoint {}", number));
+        }
+    }
+
+    pub fn snapshots(&self) -> Vec<TaskSnapshot> {
+        self.tasks
+            .iter()
+            .map(|task| task.snapshot(self.quanta))
+            .collect()
+    }
+
+    pub fn snapshot(&self, id: Ulid) -> Option<TaskSnapshot> {
+        self.tasks
+            .contains(id)
+            .then(|| self.tasks[id].snapshot(self.quanta))
+    }
+
+    pub fn status(&self) -> String {
+        status::table(self.snapshots().iter(), self.quantum)
+    }
+
+    fn list(&self, selector: Option<Selector>, reply: &Reply) {
+        let selected: Vec<TaskSnapshot> = self
+            .tasks
+            .iter()
+            .filter(|task| selector.as_ref().is_none_or(|s| s.matches(task)))
+            .map(|task| task.snapshot(self.quanta))
+            .collect();
+        reply.send(status::table(selected.iter(), self.quantum));
+    }
+
+    fn inspect(&self, id: Ulid, reply: &Reply) {
+        if !self.tasks.contains(id) {
+            reply.send(format!("No task with PID: {}", id));
+            return;
+        }
+        reply.send(debugger::inspect(&self.tasks[id]));
+    }
+
+    fn plan_scheduled(&mut self) -> Vec<(Ulid, bool)> {
+        let queued: Vec<Ulid> = self.run_queue.iter().collect();
+        let mut ready = Vec::with_capacity(queued.len());
+        for id in queued {
+            let was_throttled = self.throttle.throttled(id);
+            let throttled = self.throttle.is_throttled(&self.tasks[id], self.quanta);
+            match (was_throttled, throttled) {
+                (false, true) => self.record(id, trace::Kind::Throttled),
+                (true, false) => self.record(id, trace::Kind::Unthrottled),
+                _ => {}
+            }
+            if throttled {
+                continue;
+            }
+            let blocked = if !dependency::blockers(&self.tasks, id).is_empty() {
+                Some(task::BlockReason::Dependency)
+            } else if self.executor.blocks() {
+                let pid = self.tasks[id].get_pid();
+                pid.and_then(|pid| self.executor.blocked(pid))
+            } else {
+                None
+            };
+            let task = &mut self.tasks[id];
+            match blocked {
+                Some(reason) => task.set_state(task::State::Blocked(reason)),
+                None => {
+                    if matches!(task.state, task::State::Blocked(_)) {
+                        task.set_state(task::State::Ready);
+                    }
+                    ready.push(id);
+                }
+            }
+        }
+        let ready = match &mut self.server {
+            Some(server) => server.eligible(ready, &self.tasks, self.quanta),
+            None => ready,
+        };
+
+        let behind = self.qos.behind(&ready, &self.tasks, self.quanta);
+        let plan = self.plan_dispatch(&ready);
+        let used: usize = plan
+            .iter()
+            .map(|&(i, _)| self.tasks[i].get_spec().width)
+            .sum::<usize>()
+            + self.busy_slots();
+        self.gangs
+            .record_utilisation(used, self.concurrency.get(), plan.len() < ready.len());
+        let missed = behind
+            .iter()
+            .filter(|&&i| !plan.iter().any(|&(p, _)| p == i))
+            .count();
+        self.qos.record_quantum(self.concurrency.get(), missed);
+
+        plan
+    }
+
+    /// Slots held by tasks that are still inside their slice from an earlier quantum.
+    fn busy_slots(&self) -> usize {
+        self.running
+            .iter()
+            .map(|&id| self.tasks[id].get_spec().width)
+            .sum()
+    }
+
+    fn plan_in_queue_order(&mut self) -> Vec<(Ulid, bool)> {
+        let slots = self.concurrency.get();
+        let mut free = slots.saturating_sub(self.busy_slots());
+        let mut plan = Vec::new();
+
+        for id in self.run_queue.iter() {
+            let width = self.tasks[id].get_spec().width;
+            if width > free {
+                break;
+            }
+            free -= width;
+            plan.push((id, false));
+            if free == 0 {
+                break;
+            }
+        }
+
+        self.gangs
+            .record_utilisation(slots - free, slots, plan.len() < self.run_queue.len());
+        self.qos.record_quantum(slots, 0);
+
+        plan
+    }
+
+    fn dispatch(&mut self, plan: Vec<(Ulid, bool)>) {
+        for (id, backfilled) in plan {
+            self.gangs.dispatched(self.quanta, &self.tasks[id]);
+            self.qos.dispatched(self.quanta, &self.tasks[id]);
+            self.throttle.dispatched(&self.tasks[id], self.quanta);
+            if let Some(server) = &mut self.server {
+                server.dispatched(&self.tasks[id], self.quanta);
+            }
+            debug_assert_eq!(self.tasks[id].state, task::State::Ready);
+            self.record(id, trace::Kind::Dispatched { backfilled });
+            self.starvation.dequeued(id, self.quanta);
+            self.decisions += 1;
+            let task = &mut self.tasks[id];
+            task.waited += self.quanta.saturating_sub(task.enqueued_at);
+            task.runs += 1;
+            crate::say!(
+                "{} {} PID: {} with priority: {}",
+                output::paint(
+                    format!("Dispatcher ({})", self.scheduler.name()),
+                    Style::Dim
+                ),
+                if backfilled {
+                    output::paint("backfilled", Style::Yellow)
+                } else {
+                    output::paint("selected  ", Style::Cyan)
+                },
+                output::paint(task.label(), Style::Bold),
+                task.priority
+            );
+            #[cfg(all(unix, feature = "audit"))]
+            if let Some(audit) = &self.audit {
+                audit.record(Action::Dispatch, task);
+            }
+            let starting = !task.has_started();
+            let status_tx = mpsc::Sender::clone(&self.status_tx);
+            let executor = self.executor.as_mut();
+            let clock = self.clock.as_ref();
+            let ran = match guarded(|| task.run(status_tx, executor, clock)) {
+                Ok(Ok(())) => Ok(()),
+                Ok(Err(err)) => Err(format!("resuming it failed: {}", err)),
+                Err(message) => Err(panicked(&message)),
+            };
+            self.run_queue.remove(id);
+            if let Err(cause) = ran {
+                if starting {
+                    self.unreported.remove(&id);
+                }
+                self.poison(id, &cause);
+                continue;
+            }
+
+            if starting && task.state != task::State::Terminated {
+                self.set_nice(id);
+            }
+            if self.tasks[id].state == task::State::Terminated {
+                self.finish(id);
+            } else {
+                self.running.insert(id);
+                self.slices.insert(id, self.tasks[id].quantum(self.quantum));
+            }
+        }
+    }
+
+    fn plan_dispatch(&mut self, ready: &[Ulid]) -> Vec<(Ulid, bool)> {
+        let slots = self.concurrency.get();
+
+        let (held, queued): (Vec<Ulid>, Vec<Ulid>) = if self.backfill {
+            ready.iter().partition(|&&i| self.tasks[i].has_started())
+        } else {
+            (Vec::new(), ready.to_vec())
+        };
+
+        let ranking: Vec<Ulid> = if self.scheduler.follows_run_queue() {
+            queued
+        } else {
+            let candidates: Vec<&Task> = queued.iter().map(|&i| &self.tasks[i]).collect();
+            self.scheduler
+                .rank(&candidates)
+                .into_iter()
+                .map(|p| queued[p])
+                .collect()
+        };
+        let ranking = self.qos.order(ranking, &self.tasks, self.quanta);
+        if self.explain || self.breaking() {
+            self.explain_ranking(&ranking);
+        }
+        let units = gang::units(&ranking, &self.tasks);
+
+        let mut plan: Vec<(Ulid, bool)> = held.iter().map(|&i| (i, false)).collect();
+
+        let busy = self.busy_slots();
+        if !self.backfill {
+            let mut free = slots.saturating_sub(busy);
+            for unit in units {
+                if self.held_apart(&plan, &unit) {
+                    continue;
+                }
+                let width = gang::width(&unit, &self.tasks);
+                if width > free {
+                    break;
+                }
+                free -= width;
+                plan.extend(unit.into_iter().map(|i| (i, false)));
+            }
+
+            return plan;
+        }
+
+        let running: Vec<backfill::Slot> = held
+            .iter()
+            .map(|&i| backfill::Slot {
+                width: self.tasks[i].get_spec().width,
+                remaining: self.tasks[i].remaining_estimate(),
+            })
+            .collect();
+        let used: usize = running.iter().map(|s| s.width).sum();
+
+        let queue: Vec<backfill::Slot> = units
+            .iter()
+            .map(|unit| backfill::Slot {
+                width: gang::width(unit, &self.tasks),
+                remaining: unit
+                    .iter()
+                    .map(|&i| self.tasks[i].remaining_estimate())
+                    .collect::<Option<Vec<_>>>()
+                    .and_then(|r| r.into_iter().max()),
+            })
+            .collect();
+
+        for start in backfill::plan(&running, &queue, slots.saturating_sub(used + busy)) {
+            if self.held_apart(&plan, &units[start.index]) {
+                continue;
+            }
+            if start.backfilled {
+                self.backfilled_starts += 1;
+            } else {
+                self.in_order_starts += 1;
+            }
+            plan.extend(units[start.index].iter().map(|&i| (i, start.backfilled)));
+        }
+
+        plan
+    }
+
+    fn lock_holders(&self, waiting: &[Ulid]) -> HashMap<Ulid, Ulid> {
+        let mut holders = HashMap::new();
+        if !self.executor.blocks() {
+            return holders;
+        }
+        for &id in waiting {
+            let holder = self.tasks[id]
+                .get_pid()
+                .and_then(|pid| self.executor.lock_holder(pid))
+                .and_then(|pid| self.tasks.iter().find(|task| task.get_pid() == Some(pid)));
+            if let Some(holder) = holder {
+                holders.insert(id, holder.get_id());
+            }
+        }
+        holders
+    }
+
+    fn donate_priorities(&mut self) {
+        let queued: Vec<Ulid> = self.run_queue.iter().collect();
+        let holders = self.lock_holders(&queued);
+        let donations = dependency::donations(&self.tasks, &queued, &holders);
+        let ids = self.tasks.ids().to_vec();
+        for id in ids {
+            let donation = donations.get(&id).copied();
+            let donor = donation.map(|(_, from)| self.tasks[from].label());
+            let task = &mut self.tasks[id];
+            if task.state == task::State::Terminated {
+                task.donated = None;
+                continue;
+            }
+            if task.donated == donation.map(|(priority, _)| priority) {
+                continue;
+            }
+
+            task.donated = donation.map(|(priority, _)| priority);
+            match (task.donated, donor) {
+                (Some(priority), Some(donor)) => crate::say!(
+                    "Donated priority: {} from PID: {} to PID: {} which it waits for",
+                    priority,
+                    donor,
+                    task.label()
+                ),
+                _ => crate::say!(
+                    "PID: {} is back to priority: {}",
+                    task.label(),
+                    task.effective_priority()
+                ),
+            }
+            if self.run_queue.remove(id) {
+                let key = self.tasks[id].effective_priority();
+                self.run_queue.push(id, key, self.tasks.position(id) as u64);
+            }
+        }
+    }
+
+    fn held_apart(&mut self, plan: &[(Ulid, bool)], unit: &[Ulid]) -> bool {
+        let held = unit.iter().any(|&i| {
+            plan.iter()
+                .any(|&(p, _)| affinity::conflicts(&self.tasks[i], &self.tasks[p]))
+        });
+        if held {
+            for &i in unit {
+                self.affinity.held_back(&self.tasks[i]);
+            }
+        }
+        held
+    }
+
+    fn queue_view(&self) -> String {
+        let mut view = format!(
+            "Run queue at quantum {} ({}, {} queued):",
+            self.quanta,
+            self.scheduler.name(),
+            self.run_queue.len()
+        );
+        if self.run_queue.is_empty() {
+            view.push_str(" empty");
+            return view;
+        }
+        let _ = write!(
+            view,
+            "\n  {:>3}  {:<26}  {:<16}  {:<18}  {:>3}  {:>9}  {:>7}",
+            "#", "PID", "NAME", "STATE", "KEY", "EFFECTIVE", "WAITING"
+        );
+        for (position, (key, _, id)) in self.run_queue.entries().enumerate() {
+            let task = &self.tasks[id];
+            let _ = write!(
+                view,
+                "\n  {:>3}  {:<26}  {:<16}  {:<18}  {:>3}  {:>9}  {:>7}",
+                position + 1,
+                task.get_id(),
+                task.name(),
+                task.state.to_string(),
+                key,
+                task.effective_priority(),
+                self.quanta.saturating_sub(task.enqueued_at)
+            );
+        }
+        view
+    }
+
+    fn explain_ranking(&self, ranking: &[Ulid]) {
+        if ranking.is_empty() {
+            return;
+        }
+
+        crate::say!(
+            "Explain quantum {} ({}):\n  {:<26}  {:<16}  {:>8}  {:>5}  {:>7}  {:>9}",
+            self.quanta,
+            self.scheduler.name(),
+            "PID",
+            "NAME",
+            "PRIORITY",
+            "BOOST",
+            "DONATED",
+            "EFFECTIVE"
+        );
+        for &id in ranking {
+            let task = &self.tasks[id];
+            crate::say!(
+                "  {:<26}  {:<16}  {:>8}  {:>+5}  {:>7}  {:>9}",
+                task.get_id(),
+                task.name(),
+                task.priority,
+                task.boost,
+                task.donated
+                    .map_or("-".to_string(), |donated| donated.to_string()),
+                task.effective_priority()
+            );
+        }
+    }
+
+    fn print_backfill_report(&self) {
+        if !self.backfill {
+            return;
+        }
+
+        crate::report!(
+            "------------------------------------------\n\
+             BACKFILL REPORT\n\
+             In-order Starts: {}\n\
+             Backfilled:      {}\n\
+             ------------------------------------------",
+            self.in_order_starts,
+            self.backfilled_starts
+        );
+    }
+
+    fn pause(&mut self, reply: &Reply) {
+        if self.paused_at.is_some() {
+            reply.send("Simulator is already paused");
+            return;
+        }
+
+        for &id in &self.running {
+            let task = &mut self.tasks[id];
+            if task.state != task::State::Running {
+                continue;
+            }
+            match task.freeze(self.executor.as_mut()) {
+                Ok(()) => {
+                    task.set_state(task::State::Suspended);
+                    self.frozen.insert(id);
+                }
+                Err(err) => eprintln!("Failed to freeze PID: {}, it keeps running: {}", id, err),
+            }
+        }
+
+        self.paused_at = Some(self.clock.now());
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.suspend();
+        }
+        reply.send("Simulator paused");
+    }
+
+    fn resume(&mut self, reply: &Reply) {
+        let Some(paused_at) = self.paused_at.take() else {
+            reply.send("Simulator is not paused");
+            return;
+        };
+
+        let paused_for = self.clock.now().saturating_duration_since(paused_at);
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.resume();
+        }
+        for task in self.tasks.iter_mut() {
+            if task.state != task::State::Terminated {
+                task.add_paused_time(paused_for);
+            }
+        }
+        let mut failed = Vec::new();
+        for id in std::mem::take(&mut self.frozen) {
+            let task = &mut self.tasks[id];
+            if task.state == task::State::Suspended && self.running.contains(&id) {
+                let executor = self.executor.as_mut();
+                match guarded(|| task.thaw(executor)) {
+                    Ok(Ok(())) => task.set_state(task::State::Running),
+                    Ok(Err(err)) => failed.push((id, format!("resuming it failed: {}", err))),
+                    Err(message) => failed.push((id, panicked(&message))),
+                }
+            }
+        }
+        for (id, cause) in failed {
+            self.poison(id, &cause);
+        }
+
+        reply.send(format!(
+            "Simulator resumed after {} seconds",
+            paused_for.as_secs_f64()
+        ));
+    }
+
+    fn save_checkpoint(&self, path: &Path, reply: &Reply) {
+        match checkpoint::save(path, &self.tasks, self.clock.as_ref()) {
+            Ok(()) => reply.send(format!("Checkpoint written to {}", path.display())),
+            Err(err) => reply.send(format!(
+                "Failed to write checkpoint {}: {}",
+                path.display(),
+                err
+            )),
+        }
+    }
+
+    fn select(&self, selection: &Selection, reply: &Reply) -> Vec<Ulid> {
+        let selector = match selection {
+            Selection::Task(task) => return self.resolve(task, reply).into_iter().collect(),
+            Selection::Filter(selector) => selector,
+        };
+
+        let selected: Vec<Ulid> = self
+            .tasks
+            .iter()
+            .filter(|task| task.state != task::State::Terminated && selector.matches(task))
+            .map(Task::get_id)
+            .collect();
+        if selected.is_empty() {
+            reply.send(format!("No unfinished task matches: {}", selector));
+        }
+        selected
+    }
+
+    fn resolve(&self, task: &TaskRef, reply: &Reply) -> Option<Ulid> {
+        self.tasks
+            .resolve(&task.0)
+            .map_err(|err| reply.send(err))
+            .ok()
+    }
+
+    fn handle(&mut self, message: Message) {
+        let Message { command, reply } = message;
+
+        match command {
+            Command::Pause => self.pause(&reply),
+            Command::Resume => self.resume(&reply),
+            Command::Checkpoint(path) => self.save_checkpoint(&path, &reply),
+            Command::Submit(spec) => {
+                self.submit(*spec, &reply);
+            }
+            Command::Renice(selection, priority) => {
+                for id in self.select(&selection, &reply) {
+                    self.renice(id, priority, &reply);
+                }
+            }
+            Command::Kill(selection) => {
+                for id in self.select(&selection, &reply) {
+                    self.kill(id, &reply);
+                }
+            }
+            Command::Suspend(selection) => {
+                for id in self.select(&selection, &reply) {
+                    self.suspend(id, &reply);
+                }
+            }
+            Command::ResumeTask(selection) => {
+                for id in self.select(&selection, &reply) {
+                    self.resume_task(id, &reply);
+                }
+            }
+            Command::Step => self.set_debug_mode(debugger::Mode::Step, &reply),
+            Command::Continue => self.set_debug_mode(debugger::Mode::Continue, &reply),
+            Command::Until(task) => {
+                if let Some(id) = self.resolve(&task, &reply) {
+                    self.set_debug_mode(debugger::Mode::Until(id), &reply);
+                }
+            }
+            Command::Inspect(task) => {
+                if let Some(id) = self.resolve(&task, &reply) {
+                    self.inspect(id, &reply);
+                }
+            }
+            Command::Break(breakpoint) => self.set_breakpoint(breakpoint, &reply),
+            Command::Delete(number) => self.delete_breakpoint(number, &reply),
+            Command::Status => reply.send(self.status()),
+            Command::List(selector) => self.list(selector, &reply),
+            Command::Shutdown => {
+                self.shutdown = true;
+                reply.send("Shutting down");
+            }
+            Command::Reload => self.reload(&reply),
+            Command::EndOfInput => self.input_open = false,
+        }
+    }
+
+    fn wait_quantum(&mut self) {
+        let mut remaining = self.slice;
+
+        while !self.shutdown && !remaining.is_zero() {
+            if self.paused_at.is_some() {
+                self.next_message();
+                continue;
+            }
+
+            let started = self.clock.now();
+            let deadline = started + remaining;
+            let message = match self.clock.timeout(deadline) {
+                Some(timeout) => self.control_rx.recv_timeout(timeout).ok(),
+                None => self.control_rx.try_recv().ok(),
+            };
+            match message {
+                Some(message) => self.handle(message),
+                None => self.clock.sleep_until(deadline),
+            }
+            remaining =
+                remaining.saturating_sub(self.clock.now().saturating_duration_since(started));
+        }
+    }
+
+    fn all_terminated(&self) -> bool {
+        self.run_queue.is_empty() && self.running.is_empty() && self.suspended.is_empty()
+    }
+
+    fn idle(&self) -> bool {
+        self.all_terminated()
+            && !self.input_open
+            && !self.deadlines.pending()
+            && self.scenario.as_ref().is_none_or(Scenario::is_done)
+    }
+
+    fn play_scenario(&mut self) {
+        let now = self.elapsed;
+        let Some(steps) = self.scenario.as_mut().map(|s| s.due(now)) else {
+            return;
+        };
+
+        for step in steps {
+            crate::say!(
+                "Scenario at {} seconds: {}",
+                step.at.as_secs_f64(),
+                step.text
+            );
+            let task = |name: &str| self.scenario.as_ref().and_then(|s| s.task(name));
+            match step.action {
+                scenario::Action::Add(name, spec) => {
+                    if let Some(id) = self.submit(*spec, &Reply::Log) {
+                        self.scenario.as_mut().unwrap().added(name, id);
+                    }
+                }
+                scenario::Action::Renice(name, priority) => match task(&name) {
+                    Some(id) => self.renice(id, priority, &Reply::Log),
+                    None => crate::say!("Scenario task {} was never admitted", name),
+                },
+                scenario::Action::Kill(name) => match task(&name) {
+                    Some(id) => self.kill(id, &Reply::Log),
+                    None => crate::say!("Scenario task {} was never admitted", name),
+                },
+                scenario::Action::Control(command) => self.handle(Message {
+                    command,
+                    reply: Reply::Log,
+                }),
+            }
+        }
+    }
+
+    fn release_jobs(&mut self) {
+        for (release, spec) in self.deadlines.due(self.elapsed) {
+            if let Some(id) = self.submit(spec, &Reply::Log) {
+                self.deadlines.released(id, release);
+            }
+        }
+    }
+
+    fn next_message(&mut self) {
+        match self.control_rx.recv() {
+            Ok(message) => self.handle(message),
+            Err(_) if self.paused_at.is_some() => self.resume(&Reply::Stdout),
+            Err(_) => self.shutdown = true,
+        }
+    }
+
+    fn wait_for_work(&mut self) {
+        while !self.shutdown && (self.paused_at.is_some() || (self.daemon && self.idle())) {
+            self.next_message();
+        }
+    }
+
+    fn poll_running(&mut self) {
+        let running: Vec<Ulid> = self.running.iter().copied().collect();
+        for id in running {
+            let left = self
+                .slices
+                .get(&id)
+                .map_or(Duration::ZERO, |left| left.saturating_sub(self.slice));
+            let expired = left.is_zero();
+            if expired {
+                self.slices.remove(&id);
+            } else {
+                self.slices.insert(id, left);
+            }
+
+            let task = &mut self.tasks[id];
+            if task.state == task::State::Waiting {
+                if expired {
+                    self.running.remove(&id);
+                    self.record(id, trace::Kind::Preempted);
+                    self.enqueue(id);
+                }
+                continue;
+            }
+
+            task.cpu_time += self.slice;
+            if !expired {
+                continue;
+            }
+            if let Some(comparison) = &mut self.kernel_comparison {
+                comparison.observe(task, self.executor.as_ref(), self.quanta);
+            }
+
+            let executor = self.executor.as_mut();
+            let status = match guarded(|| task.get_current_state(executor)) {
+                Ok(status) => status,
+                Err(message) => {
+                    self.poison(id, &panicked(&message));
+                    continue;
+                }
+            };
+            task.sample_memory(self.quanta);
+            task.sample_processor(self.quanta);
+            if let Some(syscalls) = &mut self.syscalls {
+                syscalls.observe(task, self.executor.as_ref(), self.quanta);
+            }
+
+            match status {
+                Ok(task::Status::Running) => {
+                    if let Some(interactivity) = &mut self.interactivity {
+                        interactivity.observe(task, self.executor.as_ref(), self.slice);
+                    }
+                    let executor = self.executor.as_mut();
+                    let paused = match guarded(|| task.pause(executor)) {
+                        Ok(paused) => paused,
+                        Err(message) => {
+                            self.poison(id, &panicked(&message));
+                            continue;
+                        }
+                    };
+                    if let Some(preemption) = &mut self.preemption {
+                        preemption.observe(task, self.quanta);
+                    }
+                    if paused.is_err() && task.stop_failures >= task::MAX_STOP_FAILURES {
+                        self.give_up(id);
+                        continue;
+                    }
+                    self.running.remove(&id);
+                    self.record(id, trace::Kind::Preempted);
+                    self.enqueue(id);
+                    continue;
+                }
+                Ok(task::Status::Terminated(exit_code)) => {
+                    task.set_state(task::State::Terminated);
+                    task.exit_code = Some(exit_code);
+                    task.duration += task.elapsed(self.clock.as_ref());
+                    task.print();
+                }
+                Err(err) => {
+                    task.set_state(task::State::Terminated);
+                    task.exit_code = Some(task::ExitCode::Failure);
+                    task.duration += task.elapsed(self.clock.as_ref());
+                    task.print_with_error(&err);
+                }
+            }
+
+            self.running.remove(&id);
+            self.finish(id);
+        }
+    }
+
+    fn poison(&mut self, id: Ulid, cause: &str) {
+        let task = &mut self.tasks[id];
+        crate::say!(
+            "Poisoned PID: {} after {}",
+            task.label(),
+            output::paint(cause, Style::Red)
+        );
+        let executor = self.executor.as_mut();
+        let _ = guarded(|| task.kill(executor));
+        task.set_state(task::State::Terminated);
+        task.exit_code = Some(task::ExitCode::Poisoned);
+        task.duration += task.elapsed(self.clock.as_ref());
+
+        self.running.remove(&id);
+        self.finish(id);
+    }
+
+    fn give_up(&mut self, id: Ulid) {
+        let task = &mut self.tasks[id];
+        crate::say!(
+            "Gave up on PID: {} which ignored {} pause requests in a row",
+            id,
+            task.stop_failures
+        );
+        #[cfg(all(unix, feature = "audit"))]
+        if let Some(audit) = &self.audit {
+            audit.record(Action::Kill, task);
+        }
+        task.kill(self.executor.as_mut());
+        task.set_state(task::State::Terminated);
+        task.exit_code = Some(task::ExitCode::Failure);
+        task.duration += task.elapsed(self.clock.as_ref());
+
+        self.running.remove(&id);
+        self.finish(id);
+    }
+
+    fn stop_unfinished(&mut self) {
+        for task in self.tasks.iter_mut() {
+            if task.state != task::State::Terminated {
+                #[cfg(all(unix, feature = "audit"))]
+                if let (Some(audit), Some(_)) = (&self.audit, task.get_pid()) {
+                    audit.record(Action::Kill, task);
+                }
+                task.kill(self.executor.as_mut());
+            }
+        }
+    }
+
+    fn reject_oversized(&mut self) {
+        let ids = self.tasks.ids().to_vec();
+        for id in ids {
+            let task = &self.tasks[id];
+            let needed = match task.get_spec().gang.as_deref() {
+                Some(gang) => gang::gang_width(gang, &self.tasks),
+                None => task.get_spec().width,
+            };
+            if task.state == task::State::Terminated || needed <= self.concurrency.get() {
+                continue;
+            }
+
+            let task = &mut self.tasks[id];
+            task.set_state(task::State::Terminated);
+            task.exit_code = Some(task::ExitCode::Failure);
+            crate::say!(
+                "Rejected PID: {} which needs {} slots but concurrency is {}",
+                task.get_id(),
+                needed,
+                self.concurrency
+            );
+            if !task.has_started() {
+                self.unreported.remove(&id);
+            }
+            self.run_queue.remove(id);
+            self.starvation.dequeued(id, self.quanta + 1);
+            self.finish(id);
+        }
+    }
+
+    fn print_reports(&self) {
+        self.starvation.print_report(&self.tasks, self.quantum);
+        self.print_backfill_report();
+        self.scheduler.print_report();
+        self.gangs.print_report(self.quantum);
+        self.deadlines.print_report();
+        self.affinity.print_report(&self.tasks);
+        self.qos.print_report();
+        self.throttle.print_report(&self.tasks, self.quantum);
+        if let Some(server) = &self.server {
+            server.print_report();
+        }
+        let window = self.window.quanta(self.quanta);
+        if let Some(comparison) = &self.kernel_comparison {
+            comparison.print_report(&self.tasks, self.quantum, &window);
+        }
+        if let Some(syscalls) = &self.syscalls {
+            syscalls.print_report(&self.tasks, &window);
+        }
+        if let Some(preemption) = &self.preemption {
+            preemption.print_report(&self.tasks, self.quantum, &window);
+        }
+        if self.findings {
+            let findings = findings::analyze(
+                self.events(),
+                &self.tasks,
+                self.quantum,
+                self.starvation.threshold(),
+            );
+            findings::print_report(&findings, &self.tasks);
+        }
+        if let Some(throughput) = &self.throughput {
+            throughput.print_report(self.quanta, self.quantum);
+        }
+        metrics::print_rankings(&metrics::rankings(&self.tasks, self.quantum, |id| {
+            self.affinity.task_migrations(id)
+        }));
+    }
+
+    fn reload(&mut self, reply: &Reply) {
+        let Some(watch) = &mut self.config else {
+            reply.send("No config file to reload");
+            return;
+        };
+        let path = watch.path().display().to_string();
+        let profile = match watch.reload() {
+            Ok(profile) => profile,
+            Err(err) => {
+                reply.send(format!("Failed to reload config {}: {}", path, err));
+                return;
+            }
+        };
+
+        let mut changes = Vec::new();
+        if let Some(quantum) = &profile.quantum {
+            match parse_quantum(quantum) {
+                Ok(quantum) => {
+                    changes.push(format!("quantum {:?} -> {:?}", self.quantum, quantum));
+                    self.quantum = quantum;
+                }
+                Err(err) => changes.push(format!("quantum unchanged ({})", err)),
+            }
+        }
+        if let Some(threshold) = profile.starvation_threshold {
+            changes.push(format!(
+                "starvation-threshold {}ms -> {}ms",
+                self.starvation.threshold().as_millis(),
+                threshold
+            ));
+            self.starvation
+                .set_threshold(Duration::from_millis(threshold));
+        }
+        if let Some(backfill) = profile.backfill {
+            changes.push(format!("backfill {} -> {}", self.backfill, backfill));
+            self.backfill = backfill;
+        }
+        if let Some(enabled) = profile.interactivity_boost {
+            changes.push(format!(
+                "interactivity-boost {} -> {}",
+                self.interactivity.is_some(),
+                enabled
+            ));
+            if enabled != self.interactivity.is_some() {
+                self.interactivity = enabled.then(Interactivity::default);
+            }
+        }
+        if let Some(window) = profile.qos_window {
+            changes.push(format!("qos-window {} -> {}", self.qos.window(), window));
+            self.qos.set_window(window);
+        }
+        if let Some(period) = profile.quota_period {
+            changes.push(format!(
+                "quota-period {} -> {}",
+                self.throttle.period(),
+                period
+            ));
+            self.throttle.set_period(period);
+        }
+        let restart = profile.needs_restart();
+        if !restart.is_empty() {
+            changes.push(format!("{} need a restart", restart.join(", ")));
+        }
+
+        if changes.is_empty() {
+            reply.send(format!("Config reloaded from {}: no changes", path));
+        } else {
+            reply.send(format!(
+                "Config reloaded from {}: {}",
+                path,
+                changes.join(", ")
+            ));
+        }
+    }
+
+    fn start_quantum(&mut self) {
+        if status::take_request() {
+            Reply::Stdout.send(self.status());
+        }
+        if self.config.as_ref().is_some_and(config::Watch::changed) {
+            self.reload(&Reply::Log);
+        }
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.start(self.quanta + 1, self.quantum.div_f64(self.time_scale));
+        }
+        self.play_scenario();
+        self.release_jobs();
+        let started = Instant::now();
+        self.dispatcher();
+        self.dispatch_time += started.elapsed();
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.tasks(status::table(self.snapshots().iter(), self.quantum));
+            watchdog.enter("running the quantum");
+        }
+        self.slices.retain(|id, _| self.running.contains(id));
+        self.slice = self.slices.values().copied().min().unwrap_or(self.quantum);
+        self.starvation
+            .observe(&self.tasks, self.quanta, self.quantum);
+    }
+
+    fn publish_metrics(&self) {
+        let Some(exporter) = &self.metrics else {
+            return;
+        };
+
+        exporter.publish(
+            &metrics::Snapshot {
+                run_queue_length: self.run_queue.len(),
+                concurrency: self.concurrency.get(),
+                quanta: self.quanta,
+                dispatches: self.decisions,
+                dispatch_time: self.dispatch_time,
+                ..metrics::Snapshot::new(&self.tasks)
+            },
+            self.snapshots(),
+        );
+    }
+
+    fn out_of_time(&self) -> bool {
+        self.max_quanta.is_some_and(|max| self.quanta >= max)
+            || self.max_sim_time.is_some_and(|max| self.elapsed >= max)
+    }
+
+    fn abandon_unfinished(&mut self) {
+        let mut incomplete = 0;
+        for task in self.tasks.iter() {
+            if task.state != task::State::Terminated {
+                task.print_incomplete();
+                incomplete += 1;
+            }
+        }
+
+        crate::report!(
+            "Run limit reached after {} quanta ({} seconds simulated), incomplete tasks: {}",
+            self.quanta,
+            self.elapsed.as_secs_f64(),
+            incomplete
+        );
+        self.shutdown = true;
+    }
+
+    fn end_quantum(&mut self) -> bool {
+        self.elapsed += self.slice;
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.enter("polling running tasks");
+        }
+        self.poll_running();
+        for id in self.deadlines.overdue(&self.tasks, self.elapsed) {
+            self.kill(id, &Reply::Log);
+        }
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.finish();
+        }
+        self.check_breakpoints();
+        self.publish_metrics();
+
+        if self.out_of_time() && !self.idle() {
+            self.abandon_unfinished();
+            return false;
+        }
+
+        if self.daemon {
+            self.drain_statuses();
+            false
+        } else {
+            self.idle()
+        }
+    }
+
+    fn stop(&mut self) {
+        self.stop_unfinished();
+        self.print_reports();
+        self.save_trace();
+        self.save_graph();
+        self.save_report();
+        self.save_timeseries();
+        self.save_experiment();
+        self.export_spans();
+        crate::report!("Simulator stopped");
+    }
+
+    fn drain_statuses(&mut self) {
+        while let Ok((id, _)) = self.status_rx.try_recv() {
+            self.unreported.remove(&id);
+        }
+    }
+
+    fn await_statuses(&mut self) {
+        let deadline = Instant::now() + self.status_timeout;
+        while !self.unreported.is_empty() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.status_rx.recv_timeout(remaining) {
+                Ok((id, _)) => {
+                    self.unreported.remove(&id);
+                }
+                Err(_) => {
+                    let missing: Vec<String> = self
+                        .unreported
+                        .iter()
+                        .map(|&id| self.tasks[id].label())
+                        .collect();
+                    crate::report!(
+                        "Gave up after {} seconds waiting for the first status of {} tasks: {}",
+                        self.status_timeout.as_secs_f64(),
+                        missing.len(),
+                        missing.join(", ")
+                    );
+                    return;
+                }
+            }
+        }
+    }
+
+    fn complete(&mut self) {
+        self.await_statuses();
+
+        self.print_reports();
+        self.save_trace();
+        self.save_graph();
+        self.save_report();
+        self.save_timeseries();
+        self.save_experiment();
+        self.export_spans();
+        crate::report!("All tasks completed!");
+    }
+
+    pub fn step(&mut self) -> bool {
+        if self.shutdown || self.idle() {
+            return false;
+        }
+        if self.quanta == 0 {
+            self.reject_oversized();
+        }
+
+        self.start_quantum();
+        self.wait_quantum();
+        !self.end_quantum()
+    }
+
+    pub fn summary(&self) -> RunSummary {
+        RunSummary::new(self.tasks.iter(), self.quanta, self.quantum, self.shutdown)
+    }
+
+    pub fn run(&mut self) -> RunSummary {
+        self.reject_oversized();
+
+        loop {
+            self.wait_for_work();
+
+            if self.shutdown {
+                self.stop();
+                return self.summary();
+            }
+
+            self.start_quantum();
+            self.wait_quantum();
+            if self.end_quantum() {
+                break;
+            }
+        }
+
+        self.complete();
+        self.summary()
+    }
+}
+
+#[cfg(feature = "async")]
+mod asynchronous;
+
+#[cfg(test)]
+mod properties;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::executor::{MockExecutor, Outcome};
+    use std::path::PathBuf;
+
+    fn simulator(clock: &ManualClock) -> (Simulator, mpsc::Sender<Message>) {
+        let (control_tx, control_rx) = mpsc::channel();
+        let simulator = Simulator::new(Vec::new(), control_rx).clock(clock.clone());
+        (simulator, control_tx)
+    }
+
+    fn send(control_tx: &mpsc::Sender<Message>, command: Command) -> mpsc::Receiver<String> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        control_tx
+            .send(Message {
+                command,
+                reply: Reply::Channel(reply_tx),
+            })
+            .unwrap();
+        reply_rx
+    }
+
+    fn run(tasks: Vec<Task>, executor: &MockExecutor) -> Simulator {
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(tasks, control_rx)
+            .clock(ManualClock::new())
+            .executor(executor.clone());
+        simulator.run();
+        simulator
+    }
+
+    fn exit_code(simulator: &Simulator, path: &str) -> Option<task::ExitCode> {
+        simulator
+            .tasks
+            .iter()
+            .find(|t| t.get_path_to_binary() == Path::new(path))
+            .and_then(|t| t.exit_code)
+    }
+
+    #[test]
+    fn priority_policy_starts_tasks_in_priority_order() {
+        let executor = MockExecutor::new();
+        let tasks = vec![
+            Task::new("low", None, 3),
+            Task::new("high", None, 1),
+            Task::new("medium", None, 2),
+        ];
+
+        run(tasks, &executor);
+
+        assert_eq!(
+            executor.spawned(),
+            ["high", "medium", "low"].map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn higher_priority_task_runs_to_completion_first() {
+        let executor = MockExecutor::new()
+            .script("long", 3, Outcome::Exit(task::ExitCode::Success))
+            .script("urgent", 2, Outcome::Exit(task::ExitCode::Success));
+        let tasks = vec![Task::new("long", None, 2), Task::new("urgent", None, 1)];
+
+        let simulator = run(tasks, &executor);
+
+        assert_eq!(executor.spawned(), ["urgent", "long"].map(PathBuf::from));
+        assert_eq!(executor.quanta_used("long"), 3);
+        assert_eq!(executor.quanta_used("urgent"), 2);
+        assert!(simulator.all_terminated());
+    }
+
+    #[test]
+    fn max_quanta_ends_a_run_with_a_task_that_never_finishes() {
+        let executor =
+            MockExecutor::new().script("forever", u32::MAX, Outcome::Exit(task::ExitCode::Success));
+        let tasks = vec![Task::new("forever", None, 1), Task::new("queued", None, 2)];
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(tasks, control_rx)
+            .clock(ManualClock::new())
+            .executor(executor.clone())
+            .max_quanta(Some(5));
+
+        simulator.run();
+
+        assert_eq!(simulator.quanta, 5);
+        assert_eq!(executor.quanta_used("forever"), 5);
+        assert_eq!(executor.spawned(), [PathBuf::from("forever")]);
+        assert!(!simulator.all_terminated());
+    }
+
+    #[test]
+    fn streamed_tasks_keep_the_run_going_until_input_ends() {
+        let executor =
+            MockExecutor::new().script("late", 2, Outcome::Exit(task::ExitCode::Success));
+        let clock = ManualClock::new();
+        let (control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(Vec::new(), control_rx)
+            .clock(clock.clone())
+            .executor(executor.clone())
+            .stream_tasks(true);
+
+        assert!(simulator.step());
+        assert!(simulator.step());
+        assert_eq!(executor.spawned(), Vec::<PathBuf>::new());
+
+        send(
+            &control_tx,
+            Command::Submit(Box::new(task::Spec::new("late", None, 1))),
+        );
+        send(&control_tx, Command::EndOfInput);
+        simulator.run();
+
+        assert_eq!(executor.spawned(), [PathBuf::from("late")]);
+        assert_eq!(exit_code(&simulator, "late"), Some(task::ExitCode::Success));
+        assert!(simulator.quanta > 2);
+    }
+
+    #[test]
+    fn the_dispatch_that_launches_a_task_is_not_charged_cpu_time() {
+        let executor = MockExecutor::new().script("job", 3, Outcome::Exit(task::ExitCode::Success));
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(vec![Task::new("job", None, 1)], control_rx)
+            .clock(ManualClock::new())
+            .executor(executor)
+            .quantum(Duration::from_millis(100))
+            .record_trace();
+        let cpu_time = |simulator: &Simulator| simulator.tasks.iter().next().unwrap().cpu_time;
+
+        simulator.step();
+        assert_eq!(cpu_time(&simulator), Duration::ZERO);
+        simulator.step();
+        assert_eq!(cpu_time(&simulator), Duration::from_millis(100));
+        simulator.run();
+
+        let dispatches = simulator
+            .events()
+            .iter()
+            .filter(|event| matches!(event.kind, trace::Kind::Dispatched { .. }))
+            .count();
+        assert_eq!(dispatches, 3);
+        assert_eq!(cpu_time(&simulator), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn tasks_with_their_own_quantum_run_in_shorter_slices() {
+        let executor = MockExecutor::new()
+            .script("short", 3, Outcome::Exit(task::ExitCode::Success))
+            .script("long", 2, Outcome::Exit(task::ExitCode::Success));
+        let short = Task::from_spec(task::Spec {
+            quantum_ms: Some(20),
+            ..task::Spec::new("short", None, 1)
+        });
+        let long = Task::new("long", None, 2);
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(vec![short, long], control_rx)
+            .clock(ManualClock::new())
+            .executor(executor)
+            .quantum(Duration::from_millis(100));
+        simulator.run();
+
+        let cpu_time = |path: &str| {
+            simulator
+                .tasks
+                .iter()
+                .find(|task| task.get_spec().path_to_binary == Path::new(path))
+                .map(|task| task.cpu_time)
+        };
+        assert_eq!(cpu_time("short"), Some(Duration::from_millis(40)));
+        assert_eq!(cpu_time("long"), Some(Duration::from_millis(100)));
+        assert_eq!(simulator.elapsed, Duration::from_millis(260));
+    }
+
+    #[test]
+    fn a_short_slice_does_not_cut_short_the_task_running_next_to_it() {
+        let executor = MockExecutor::new()
+            .script("short", 6, Outcome::Exit(task::ExitCode::Success))
+            .script("long", 2, Outcome::Exit(task::ExitCode::Success));
+        let short = Task::from_spec(task::Spec {
+            quantum_ms: Some(20),
+            ..task::Spec::new("short", None, 1)
+        });
+        let long = Task::new("long", None, 2);
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(vec![short, long], control_rx)
+            .clock(ManualClock::new())
+            .executor(executor)
+            .concurrency(NonZeroUsize::new(2).unwrap())
+            .quantum(Duration::from_millis(100))
+            .record_trace();
+        simulator.run();
+
+        let task = |path: &str| {
+            simulator
+                .tasks
+                .iter()
+                .find(|task| task.get_spec().path_to_binary == Path::new(path))
+                .unwrap()
+        };
+        let count = |path: &str, kind: fn(&trace::Kind) -> bool| {
+            let id = task(path).get_id();
+            simulator
+                .events()
+                .iter()
+                .filter(|event| event.task == id && kind(&event.kind))
+                .count()
+        };
+        let dispatched = |kind: &trace::Kind| matches!(kind, trace::Kind::Dispatched { .. });
+        let preempted = |kind: &trace::Kind| matches!(kind, trace::Kind::Preempted);
+
+        assert_eq!(count("short", dispatched), 6);
+        assert_eq!(count("long", dispatched), 2);
+        assert_eq!(count("long", preempted), 1);
+        assert_eq!(task("short").cpu_time, Duration::from_millis(100));
+        assert_eq!(task("long").cpu_time, Duration::from_millis(100));
+        assert_eq!(simulator.elapsed, Duration::from_millis(200));
+    }
+
+    #[test]
+    fn missed_deadlines_abort_or_skip_the_next_release() {
+        let executor = MockExecutor::new()
+            .script("periodic", 3, Outcome::Exit(task::ExitCode::Success))
+            .script("doomed", 1, Outcome::Exit(task::ExitCode::Success));
+        let periodic = Task::from_spec(task::Spec {
+            period_ms: Some(200),
+            jobs: 3,
+            on_miss: task::MissPolicy::Skip,
+            ..task::Spec::new("periodic", None, 1)
+        });
+        let doomed = Task::from_spec(task::Spec {
+            deadline_ms: Some(100),
+            on_miss: task::MissPolicy::Abort,
+            ..task::Spec::new("doomed", None, 2)
+        });
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(vec![periodic, doomed], control_rx)
+            .clock(ManualClock::new())
+            .executor(executor.clone())
+            .quantum(Duration::from_millis(100));
+        simulator.run();
+
+        assert_eq!(
+            executor.spawned(),
+            [PathBuf::from("periodic"), PathBuf::from("periodic")]
+        );
+        assert_eq!(
+            exit_code(&simulator, "doomed"),
+            Some(task::ExitCode::Failure)
+        );
+        assert_eq!(simulator.deadlines.missed(), 3);
+        assert_eq!(simulator.deadlines.skipped(), 1);
+        assert_eq!(simulator.elapsed, Duration::from_millis(700));
+    }
+
+    #[test]
+    fn a_sporadic_server_bounds_aperiodic_work_per_period() {
+        let executor = MockExecutor::new()
+            .script("tick", 1, Outcome::Exit(task::ExitCode::Success))
+            .script("background", 4, Outcome::Exit(task::ExitCode::Success));
+        let tick = Task::from_spec(task::Spec {
+            period_ms: Some(400),
+            jobs: 2,
+            ..task::Spec::new("tick", None, 1)
+        });
+        let background = Task::new("background", None, 2);
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(vec![tick, background], control_rx)
+            .clock(ManualClock::new())
+            .executor(executor)
+            .quantum(Duration::from_millis(100))
+            .sporadic_server(Some((1, 4)));
+        simulator.run();
+
+        let server = simulator.server.as_ref().unwrap();
+        assert_eq!((server.served(), server.peak()), (4, 1));
+        assert_eq!(simulator.deadlines.missed(), 0);
+        assert_eq!(simulator.elapsed, Duration::from_millis(1400));
+        assert!(crate::sporadic::parse_server("3/2").is_err());
+        assert!(crate::sporadic::parse_server("2").is_err());
+    }
+
+    #[test]
+    fn waiting_tasks_donate_their_priority_to_the_task_they_wait_for() {
+        let run = |donate: bool| {
+            let executor = MockExecutor::new()
+                .script("low", 3, Outcome::Exit(task::ExitCode::Success))
+                .script("mid", 3, Outcome::Exit(task::ExitCode::Success))
+                .script("high", 1, Outcome::Exit(task::ExitCode::Success));
+            let high = Task::from_spec(task::Spec {
+                after: vec!["low".to_string()],
+                ..task::Spec::new("high", None, 1)
+            });
+            let tasks = vec![Task::new("low", None, 5), Task::new("mid", None, 3), high];
+            let (_control_tx, control_rx) = mpsc::channel();
+            Simulator::new(tasks, control_rx)
+                .clock(ManualClock::new())
+                .executor(executor.clone())
+                .donate_priority(donate)
+                .run();
+            executor.spawned()
+        };
+
+        let order = |names: [&str; 3]| names.map(PathBuf::from).to_vec();
+        assert_eq!(run(false), order(["mid", "low", "high"]));
+        assert_eq!(run(true), order(["low", "high", "mid"]));
+    }
+
+    #[test]
+    fn apart_tasks_never_share_a_quantum_and_near_tasks_follow_their_core() {
+        let executor = MockExecutor::new()
+            .script("a", 2, Outcome::Exit(task::ExitCode::Success))
+            .script("b", 1, Outcome::Exit(task::ExitCode::Success))
+            .script("c", 1, Outcome::Exit(task::ExitCode::Success));
+        let b = Task::from_spec(task::Spec {
+            apart_from: vec!["a".to_string()],
+            ..task::Spec::new("b", None, 2)
+        });
+        let c = Task::from_spec(task::Spec {
+            near: Some("a".to_string()),
+            ..task::Spec::new("c", None, 3)
+        });
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(vec![Task::new("a", None, 1), b, c], control_rx)
+            .clock(ManualClock::new())
+            .executor(executor.clone())
+            .concurrency(NonZeroUsize::new(2).unwrap());
+        simulator.run();
+
+        assert_eq!(
+            executor.spawned(),
+            ["a", "c", "b"].map(PathBuf::from).to_vec()
+        );
+        assert_eq!(simulator.affinity.violations(), (0, 1));
+        assert_eq!(simulator.quanta, 3);
+    }
+
+    #[test]
+    fn tasks_run_slower_after_a_cold_start_with_cache_warmth() {
+        let executor = MockExecutor::new()
+            .script("a", 3, Outcome::Exit(task::ExitCode::Success))
+            .script("b", 3, Outcome::Exit(task::ExitCode::Success))
+            .cache_warmth(50);
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(
+            vec![Task::new("a", None, 1), Task::new("b", None, 2)],
+            control_rx,
+        )
+        .clock(ManualClock::new())
+        .executor(executor.clone())
+        .concurrency(NonZeroUsize::new(2).unwrap());
+        simulator.run();
+
+        assert_eq!(executor.quanta_used("a"), 4);
+        assert_eq!(executor.quanta_used("b"), 4);
+        assert_eq!(executor.warm_quanta(), (6, 2));
+        assert_eq!(simulator.affinity.migrations().0, 0);
+    }
+
+    #[test]
+    fn migrations_between_cores_are_charged_the_migration_cost() {
+        let executor = MockExecutor::new()
+            .script("x", 1, Outcome::Exit(task::ExitCode::Success))
+            .script("y", 3, Outcome::Exit(task::ExitCode::Success))
+            .script("w", 2, Outcome::Exit(task::ExitCode::Success));
+        let scenario = "at 150ms add w --priority 1 w".parse().unwrap();
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(
+            vec![Task::new("y", None, 2), Task::new("x", None, 3)],
+            control_rx,
+        )
+        .clock(ManualClock::new())
+        .executor(executor.clone())
+        .scenario(scenario)
+        .concurrency(NonZeroUsize::new(2).unwrap())
+        .migration_cost(Some(Duration::from_millis(5)));
+        simulator.run();
+
+        let stats = simulator.stats();
+        assert_eq!(stats.migrations, 1);
+        assert_eq!(stats.migration_cost, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn a_panic_while_handling_a_task_poisons_only_that_task() {
+        let executor = MockExecutor::new()
+            .script("panics", 2, Outcome::Panic)
+            .script("fine", 3, Outcome::Exit(task::ExitCode::Success));
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(
+            vec![Task::new("panics", None, 1), Task::new("fine", None, 2)],
+            control_rx,
+        )
+        .clock(ManualClock::new())
+        .executor(executor.clone());
+        let summary = simulator.run();
+
+        assert_eq!(
+            exit_code(&simulator, "panics"),
+            Some(task::ExitCode::Poisoned)
+        );
+        assert_eq!(exit_code(&simulator, "fine"), Some(task::ExitCode::Success));
+        assert_eq!(summary.poisoned(), 1);
+    }
+
+    #[test]
+    fn a_task_that_dies_while_paused_is_poisoned_on_resume() {
+        let executor = MockExecutor::new()
+            .script("dies", 4, Outcome::Exit(task::ExitCode::Success))
+            .script("fine", 3, Outcome::Exit(task::ExitCode::Success));
+        let (control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(
+            vec![Task::new("dies", None, 1), Task::new("fine", None, 2)],
+            control_rx,
+        )
+        .concurrency(NonZeroUsize::new(2).unwrap())
+        .clock(ManualClock::new())
+        .executor(executor.clone());
+
+        simulator.step();
+        simulator.start_quantum();
+        send(&control_tx, Command::Pause);
+        simulator.next_message();
+        executor.vanish("dies");
+        send(&control_tx, Command::Resume);
+        simulator.next_message();
+        assert_eq!(
+            exit_code(&simulator, "dies"),
+            Some(task::ExitCode::Poisoned)
+        );
+
+        simulator.run();
+        assert_eq!(exit_code(&simulator, "fine"), Some(task::ExitCode::Success));
+    }
+
+    #[test]
+    fn the_end_of_run_wait_for_statuses_is_bounded() {
+        let executor = MockExecutor::new().script("a", 2, Outcome::Exit(task::ExitCode::Success));
+        let silent = Task::new("silent", None, 2);
+        let silent_id = silent.get_id();
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(vec![Task::new("a", None, 1)], control_rx)
+            .clock(ManualClock::new())
+            .executor(executor)
+            .status_timeout(Duration::from_millis(20));
+        simulator.tasks.insert(silent);
+        simulator.unreported.insert(silent_id);
+        simulator.run();
+
+        assert_eq!(simulator.unreported, BTreeSet::from([silent_id]));
+    }
+
+    #[test]
+    fn a_scenario_adds_renices_and_kills_tasks_over_time() {
+        let executor = MockExecutor::new()
+            .script("forever", u32::MAX, Outcome::Exit(task::ExitCode::Success))
+            .script("later", 2, Outcome::Exit(task::ExitCode::Success));
+        let scenario = "
+            at 0s add forever --priority 1 forever
+            at 300ms add later --priority 2 later
+            at 450ms renice later 0
+            at 900ms kill forever
+        "
+        .parse()
+        .unwrap();
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(Vec::new(), control_rx)
+            .clock(ManualClock::new())
+            .executor(executor.clone())
+            .scenario(scenario);
+
+        simulator.run();
+
+        assert_eq!(executor.quanta_used("later"), 2);
+        assert_eq!(executor.quanta_used("forever"), 4);
+        assert_eq!(
+            exit_code(&simulator, "forever"),
+            Some(task::ExitCode::Failure)
+        );
+        assert_eq!(
+            exit_code(&simulator, "later"),
+            Some(task::ExitCode::Success)
+        );
+    }
+
+    #[test]
+    fn started_tasks_get_the_nice_value_their_priority_maps_to() {
+        let executor =
+            MockExecutor::new().script("forever", u32::MAX, Outcome::Exit(task::ExitCode::Success));
+        let scenario = "
+            at 0s add forever --priority 1 forever
+            at 300ms renice forever 3
+            at 600ms kill forever
+        "
+        .parse()
+        .unwrap();
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(Vec::new(), control_rx)
+            .clock(ManualClock::new())
+            .executor(executor.clone())
+            .scenario(scenario)
+            .nice(NiceTable::default());
+
+        simulator.run();
+
+        assert_eq!(executor.nice("forever"), Some(-5));
+        assert_eq!(simulator.snapshots()[0].nice, Some(-5));
+        assert!(status::table(simulator.snapshots().iter(), DEFAULT_QUANTUM).contains("NICE"));
+    }
+
+    #[test]
+    fn the_debugger_stops_before_dispatch_until_told_to_go_on() {
+        let executor = MockExecutor::new()
+            .script("first", 2, Outcome::Exit(task::ExitCode::Success))
+            .script("second", 1, Outcome::Exit(task::ExitCode::Success));
+        let first = Task::new("first", None, 1);
+        let second = Task::new("second", None, 2);
+        let second_id = second.get_id();
+        let (control_tx, control_rx) = mpsc::channel();
+        let mut debugged = Simulator::new(vec![first, second], control_rx)
+            .clock(ManualClock::new())
+            .executor(executor.clone())
+            .debug(true);
+
+        let inspected = send(&control_tx, Command::Inspect("first".parse().unwrap()));
+        let stepped = send(&control_tx, Command::Step);
+        let until = send(&control_tx, Command::Until(second_id.into()));
+        debugged.run();
+
+        let inspected = inspected.recv().unwrap();
+        assert!(inspected.contains("State:          READY"), "{}", inspected);
+        assert!(inspected.contains("Process:        not started"));
+        assert_eq!(stepped.recv().unwrap(), "Stepping to the next dispatch");
+        assert_eq!(
+            until.recv().unwrap(),
+            format!("Continuing until PID: {} terminates", second_id)
+        );
+        assert_eq!(
+            debugged.debugger.as_ref().unwrap().mode,
+            debugger::Mode::Step
+        );
+        assert_eq!(
+            exit_code(&debugged, "second"),
+            Some(task::ExitCode::Success)
+        );
+
+        let (mut plain, control_tx) = simulator(&ManualClock::new());
+        let refused = send(&control_tx, Command::Step);
+        plain.next_message();
+        assert_eq!(
+            refused.recv().unwrap(),
+            "Debugging is not enabled, start with --debug"
+        );
+    }
+
+    #[test]
+    fn load_is_sampled_every_quantum() {
+        let executor = MockExecutor::new()
+            .script("wide", 2, Outcome::Exit(task::ExitCode::Success))
+            .script("narrow", 3, Outcome::Exit(task::ExitCode::Success));
+        let tasks = vec![
+            Task::from_spec(task::Spec {
+                width: 2,
+                ..task::Spec::new("wide", None, 1)
+            }),
+            Task::new("narrow", None, 2),
+        ];
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(tasks, control_rx)
+            .clock(ManualClock::new())
+            .executor(executor)
+            .concurrency(NonZeroUsize::new(3).unwrap())
+            .timeseries(Some(PathBuf::from("unused.csv")));
+        while simulator.step() {}
+
+        assert_eq!(
+            simulator.timeseries.as_ref().unwrap().to_csv(),
+            "quantum,seconds,ready,running,utilization,completed\n\
+             1,0.15,0,2,1.000,0\n\
+             2,0.3,0,2,1.000,0\n\
+             3,0.45,0,1,0.333,1\n"
+        );
+    }
+
+    #[test]
+    fn injected_failures_terminate_tasks_with_failure() {
+        let executor = MockExecutor::new()
+            .script("ok", 2, Outcome::Exit(task::ExitCode::Success))
+            .script("exits", 2, Outcome::Exit(task::ExitCode::Failure))
+            .script("crashes", 2, Outcome::PollError(std::io::ErrorKind::Other))
+            .script("missing", 1, Outcome::SpawnError);
+        let tasks = vec![
+            Task::new("ok", None, 1),
+            Task::new("exits", None, 2),
+            Task::new("crashes", None, 3),
+            Task::new("missing", None, 4),
+        ];
+
+        let simulator = run(tasks, &executor);
+
+        assert_eq!(exit_code(&simulator, "ok"), Some(task::ExitCode::Success));
+        for path in ["exits", "crashes", "missing"] {
+            assert_eq!(
+                exit_code(&simulator, path),
+                Some(task::ExitCode::Failure),
+                "{}",
+                path
+            );
+        }
+        assert_eq!(
+            executor.spawned(),
+            ["ok", "exits", "crashes"].map(PathBuf::from)
+        );
+    }
+
+    fn assert_golden(name: &str, simulator: Simulator) {
+        let simulator = simulator.clock(ManualClock::new()).record_trace();
+        let mut simulator = simulator;
+        simulator.run();
+
+        let rendered = simulator.trace.as_ref().unwrap().render(|id| {
+            simulator.tasks[id]
+                .get_path_to_binary()
+                .display()
+                .to_string()
+        });
+        trace::golden::assert_matches(name, &rendered);
+    }
+
+    #[test]
+    fn the_verbose_queue_view_lists_every_queued_task_in_order() {
+        let executor = MockExecutor::new()
+            .script("first", 3, Outcome::Exit(task::ExitCode::Success))
+            .script("second", 1, Outcome::Exit(task::ExitCode::Success))
+            .script("third", 1, Outcome::Exit(task::ExitCode::Success));
+        let tasks = vec![
+            Task::new("first", None, 2),
+            Task::new("second", None, 1),
+            Task::new("third", None, 2),
+        ];
+        let mut simulator = workload(tasks, executor)
+            .clock(ManualClock::new())
+            .verbose(1);
+        simulator.step();
+
+        let view = simulator.queue_view();
+        let rows: Vec<Vec<&str>> = view
+            .lines()
+            .skip(2)
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        assert!(view.starts_with("Run queue at quantum 1 (priority, 2 queued):"));
+        assert_eq!(rows[0][2..], ["first", "READY", "2", "2", "0"]);
+        assert_eq!(rows[1][2..], ["third", "READY", "2", "2", "0"]);
+    }
+
+    #[test]
+    fn seeded_runs_produce_identical_traces() {
+        let run = || {
+            task::seed_ids(11);
+            let tasks = (0..3)
+                .map(|i| Task::new(format!("task-{}", i), None, 2))
+                .collect();
+            let executor = MockExecutor::new()
+                .script("task-0", 2, Outcome::Exit(task::ExitCode::Success))
+                .script("task-1", 1, Outcome::Exit(task::ExitCode::Failure))
+                .script("task-2", 3, Outcome::Exit(task::ExitCode::Success));
+            let mut simulator = workload(tasks, executor)
+                .scheduler(scheduler::build(scheduler::Policy::Random, Some(11)))
+                .clock(ManualClock::new())
+                .record_trace();
+            simulator.run();
+            serde_json::to_string(simulator.events()).unwrap()
+        };
+
+        assert_eq!(run(), run());
+    }
+
+    fn stress(scheduled: bool) -> (Stats, String) {
+        task::seed_ids(5);
+        crate::output::set_quiet(true);
+        let (tasks, executor) = crate::stress::workload(200);
+        let mut simulator = workload(tasks, executor)
+            .concurrency(NonZeroUsize::new(4).unwrap())
+            .explain(scheduled)
+            .clock(ManualClock::new())
+            .record_trace();
+        simulator.run();
+        let trace = serde_json::to_string(simulator.events()).unwrap();
+        (simulator.stats(), trace)
+    }
+
+    #[test]
+    fn a_stress_run_dispatches_every_quantum_of_work_once() {
+        let (stats, _) = stress(false);
+        let work: u64 = (0..200).map(|i| 1 + (i * 7) % 5).sum();
+        assert_eq!(stats.tasks, 200);
+        assert_eq!(stats.decisions, work);
+        assert_eq!(stats.trace_events as u64, 2 * work + 200);
+        assert!(stats.quanta >= work.div_ceil(4));
+    }
+
+    #[test]
+    fn the_queue_order_fast_path_plans_like_the_full_scheduler() {
+        let (fast, fast_trace) = stress(false);
+        let (full, full_trace) = stress(true);
+        assert_eq!(fast_trace, full_trace);
+        assert_eq!((fast.quanta, fast.decisions), (full.quanta, full.decisions));
+    }
+
+    fn workload(tasks: Vec<Task>, executor: MockExecutor) -> Simulator {
+        let (_control_tx, control_rx) = mpsc::channel();
+        Simulator::new(tasks, control_rx).executor(executor)
+    }
+
+    #[test]
+    fn golden_priority_with_failures() {
+        let executor = MockExecutor::new()
+            .script("long", 3, Outcome::Exit(task::ExitCode::Success))
+            .script("urgent", 2, Outcome::Exit(task::ExitCode::Success))
+            .script("exits", 2, Outcome::Exit(task::ExitCode::Failure))
+            .script("missing", 1, Outcome::SpawnError);
+        let tasks = vec![
+            Task::new("long", None, 2),
+            Task::new("urgent", None, 1),
+            Task::new("exits", None, 2),
+            Task::new("missing", None, 3),
+        ];
+
+        assert_golden("priority_with_failures", workload(tasks, executor));
+    }
+
+    #[test]
+    fn golden_random_with_seed() {
+        let tasks = (0..4)
+            .map(|i| Task::new(format!("task-{}", i), None, 3))
+            .collect();
+        let executor = MockExecutor::new()
+            .script("task-0", 2, Outcome::Exit(task::ExitCode::Success))
+            .script("task-1", 3, Outcome::Exit(task::ExitCode::Success))
+            .script("task-2", 1, Outcome::Exit(task::ExitCode::Success))
+            .script("task-3", 2, Outcome::Exit(task::ExitCode::Success));
+
+        let simulator = workload(tasks, executor)
+            .scheduler(scheduler::build(scheduler::Policy::Random, Some(7)));
+        assert_golden("random_with_seed", simulator);
+    }
+
+    #[test]
+    fn golden_backfill() {
+        let spec = |path: &str, priority, width, estimate| task::Spec {
+            width,
+            estimate: Some(Duration::from_millis(estimate)),
+            ..task::Spec::new(path, None, priority)
+        };
+        let tasks = vec![
+            Task::from_spec(spec("running", 1, 3, 600)),
+            Task::from_spec(spec("wide", 2, 4, 300)),
+            Task::from_spec(spec("short", 3, 1, 150)),
+            Task::from_spec(spec("late", 4, 2, 1500)),
+        ];
+        let executor = MockExecutor::new()
+            .script("running", 4, Outcome::Exit(task::ExitCode::Success))
+            .script("wide", 2, Outcome::Exit(task::ExitCode::Success))
+            .script("short", 1, Outcome::Exit(task::ExitCode::Success))
+            .script("late", 3, Outcome::Exit(task::ExitCode::Success));
+
+        let simulator = workload(tasks, executor)
+            .concurrency(NonZeroUsize::new(4).unwrap())
+            .backfill(true);
+        assert_golden("backfill", simulator);
+    }
+
+    #[test]
+    fn wait_quantum_advances_the_clock_by_one_quantum() {
+        let clock = ManualClock::new();
+        let (mut simulator, _control_tx) = simulator(&clock);
+        let start = clock.now();
+
+        simulator.wait_quantum();
+
+        assert_eq!(clock.now() - start, DEFAULT_QUANTUM);
+    }
+
+    #[test]
+    fn messages_are_handled_within_the_quantum() {
+        let clock = ManualClock::new();
+        let (mut simulator, control_tx) = simulator(&clock);
+        let start = clock.now();
+
+        let reply = send(&control_tx, Command::Shutdown);
+        simulator.wait_quantum();
+
+        assert_eq!(reply.recv().unwrap(), "Shutting down");
+        assert!(simulator.shutdown);
+        assert_eq!(clock.now(), start);
+    }
+
+    #[test]
+    fn blocked_and_suspended_tasks_say_why_they_are_not_runnable() {
+        let quantum = Duration::from_millis(10);
+        let executor = MockExecutor::new()
+            .program("reader", &"cpu 10ms; io 20ms".parse().unwrap(), quantum)
+            .program("hog", &"cpu 50ms".parse().unwrap(), quantum);
+        let follower = Task::from_spec(task::Spec {
+            after: vec!["reader".to_string()],
+            ..task::Spec::new("follower", None, 1)
+        });
+        let tasks = vec![
+            Task::new("reader", None, 1),
+            Task::new("hog", None, 2),
+            follower,
+        ];
+        let (control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(tasks, control_rx)
+            .quantum(quantum)
+            .concurrency(NonZeroUsize::new(2).unwrap())
+            .clock(ManualClock::new())
+            .executor(executor);
+        let state = |simulator: &Simulator, name: &str| {
+            let task = simulator.tasks.iter().find(|task| task.name() == name);
+            task.unwrap().state
+        };
+
+        let (mut seen, mut suspended) = (Vec::new(), false);
+        while !simulator.idle() {
+            simulator.start_quantum();
+            if state(&simulator, "hog") == task::State::Running && !suspended {
+                suspended = true;
+                let paused = send(&control_tx, Command::Pause);
+                simulator.next_message();
+                paused.recv().unwrap();
+                assert_eq!(state(&simulator, "hog"), task::State::Suspended);
+                send(&control_tx, Command::Resume);
+                simulator.next_message();
+                assert_eq!(state(&simulator, "hog"), task::State::Running);
+            }
+            simulator.wait_quantum();
+            let ended = simulator.end_quantum();
+            seen.push((state(&simulator, "reader"), state(&simulator, "follower")));
+            if ended {
+                break;
+            }
+        }
+        assert!(suspended);
+        let blocked = task::State::Blocked;
+        assert!(seen.contains(&(
+            blocked(task::BlockReason::Io),
+            blocked(task::BlockReason::Dependency)
+        )));
+        assert_eq!("blocked:io".parse(), Ok(blocked(task::BlockReason::Io)));
+        assert_eq!(state(&simulator, "follower"), task::State::Terminated);
+    }
+
+    #[test]
+    fn a_suspended_task_is_not_dispatched_until_it_is_resumed() {
+        let executor = MockExecutor::new()
+            .script("long", 3, Outcome::Exit(task::ExitCode::Success))
+            .script("short", 2, Outcome::Exit(task::ExitCode::Success));
+        let tasks = vec![Task::new("long", None, 1), Task::new("short", None, 2)];
+        let (control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(tasks, control_rx)
+            .clock(ManualClock::new())
+            .executor(executor.clone())
+            .record_trace();
+        let long = simulator.tasks.resolve("long").unwrap();
+        let command = |line: &str| line.parse::<Command>().unwrap();
+
+        simulator.start_quantum();
+        let suspended = send(&control_tx, command("suspend long"));
+        simulator.next_message();
+        assert_eq!(
+            suspended.recv().unwrap(),
+            format!("Suspended PID: {}", long)
+        );
+        simulator.wait_quantum();
+        simulator.end_quantum();
+        for _ in 0..4 {
+            assert!(simulator.step());
+        }
+        assert_eq!(simulator.tasks[long].state, task::State::Suspended);
+        assert_eq!(executor.quanta_used("long"), 1);
+        assert_eq!(
+            exit_code(&simulator, "short"),
+            Some(task::ExitCode::Success)
+        );
+
+        let refused = send(&control_tx, command("resume short"));
+        simulator.next_message();
+        assert!(refused.recv().unwrap().ends_with("is not suspended"));
+        send(&control_tx, command("resume long"));
+        simulator.next_message();
+        while simulator.step() {}
+        assert_eq!(exit_code(&simulator, "long"), Some(task::ExitCode::Success));
+        assert_eq!(executor.quanta_used("long"), 3);
+        let kinds: Vec<trace::Kind> = simulator
+            .events()
+            .iter()
+            .filter(|event| event.task == long)
+            .map(|event| event.kind)
+            .collect();
+        assert_eq!(
+            kinds[..4],
+            [
+                trace::Kind::Admitted,
+                trace::Kind::Dispatched { backfilled: false },
+                trace::Kind::Suspended,
+                trace::Kind::Resumed,
+            ]
+        );
+    }
+
+    #[test]
+    fn throttling_a_task_is_recorded_in_the_trace() {
+        let executor =
+            MockExecutor::new().script("capped", 4, Outcome::Exit(task::ExitCode::Success));
+        let capped = Task::from_spec(task::Spec {
+            cpu_quota: Some(0.5),
+            ..task::Spec::new("capped", None, 1)
+        });
+        let (_control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(vec![capped], control_rx)
+            .quota_period(4)
+            .clock(ManualClock::new())
+            .executor(executor)
+            .record_trace();
+        simulator.run();
+
+        let events: Vec<(u64, trace::Kind)> = simulator
+            .events()
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event.kind,
+                    trace::Kind::Throttled | trace::Kind::Unthrottled
+                )
+            })
+            .map(|event| (event.quantum, event.kind))
+            .collect();
+        assert_eq!(
+            events,
+            [(3, trace::Kind::Throttled), (4, trace::Kind::Unthrottled)]
+        );
+        assert_eq!(
+            exit_code(&simulator, "capped"),
+            Some(task::ExitCode::Success)
+        );
+    }
+
+    #[test]
+    fn a_task_that_does_not_stop_is_not_reported_as_suspended() {
+        let executor =
+            MockExecutor::new().script("stubborn", 3, Outcome::Exit(task::ExitCode::Success));
+        let (control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(vec![Task::new("stubborn", None, 1)], control_rx)
+            .clock(ManualClock::new())
+            .executor(executor.clone())
+            .record_trace();
+        let id = simulator.tasks.resolve("stubborn").unwrap();
+
+        simulator.step();
+        simulator.start_quantum();
+        executor.vanish("stubborn");
+        let reply = send(&control_tx, "suspend stubborn".parse().unwrap());
+        simulator.next_message();
+        assert!(reply.recv().unwrap().starts_with("Failed to suspend PID:"));
+        assert_eq!(simulator.tasks[id].state, task::State::Running);
+        assert!(simulator.running.contains(&id));
+        assert!(!simulator
+            .events()
+            .iter()
+            .any(|event| event.kind == trace::Kind::Suspended));
+    }
+
+    #[test]
+    fn resuming_the_simulator_only_thaws_the_tasks_its_pause_froze() {
+        let executor = ["kept", "frozen", "stubborn"]
+            .into_iter()
+            .fold(MockExecutor::new(), |executor, name| {
+                executor.script(name, 5, Outcome::Exit(task::ExitCode::Success))
+            });
+        let tasks = ["kept", "frozen", "stubborn"]
+            .into_iter()
+            .map(|name| Task::new(name, None, 1))
+            .collect();
+        let (control_tx, control_rx) = mpsc::channel();
+        let mut simulator = Simulator::new(tasks, control_rx)
+            .clock(ManualClock::new())
+            .executor(executor.clone())
+            .concurrency(NonZeroUsize::new(3).unwrap());
+        let state = |simulator: &Simulator, name: &str| {
+            simulator.tasks[simulator.tasks.resolve(name).unwrap()].state
+        };
+
+        simulator.step();
+        simulator.start_quantum();
+        send(&control_tx, "suspend kept".parse().unwrap());
+        simulator.next_message();
+        executor.vanish("stubborn");
+        let paused = send(&control_tx, Command::Pause);
+        simulator.next_message();
+        assert_eq!(paused.recv().unwrap(), "Simulator paused");
+        assert_eq!(state(&simulator, "frozen"), task::State::Suspended);
+        assert_eq!(state(&simulator, "stubborn"), task::State::Running);
+
+        send(&control_tx, Command::Resume);
+        simulator.next_message();
+        assert_eq!(state(&simulator, "kept"), task::State::Suspended);
+        assert_eq!(state(&simulator, "frozen"), task::State::Running);
+        assert_eq!(state(&simulator, "stubborn"), task::State::Running);
+        assert!(simulator
+            .suspended
+            .contains(&simulator.tasks.resolve("kept").unwrap()));
+    }
+
+    #[test]
+    fn pause_duration_comes_from_the_clock() {
+        let clock = ManualClock::new();
+        let (mut simulator, control_tx) = simulator(&clock);
+
+        let paused = send(&control_tx, Command::Pause);
+        simulator.next_message();
+        assert_eq!(paused.recv().unwrap(), "Simulator paused");
+
+        clock.advance(Duration::from_secs(5));
+
+        let resumed = send(&control_tx, Command::Resume);
+        simulator.next_message();
+        assert_eq!(resumed.recv().unwrap(), "Simulator resumed after 5 seconds");
+    }
+
+    #[test]
+    fn task_durations_follow_the_simulator_clock_and_leave_out_pauses() {
+        let clock = ManualClock::new();
+        let (control_tx, control_rx) = mpsc::channel();
+        let mut simulator =
+            Simulator::new(vec![Task::new("job", None, 1)], control_rx).clock(clock.clone());
+        let elapsed = |simulator: &Simulator| {
+            let task = simulator.tasks.iter().next().unwrap();
+            task.elapsed(simulator.clock.as_ref())
+        };
+
+        clock.advance(Duration::from_secs(3));
+        send(&control_tx, Command::Pause);
+        simulator.next_message();
+        clock.advance(Duration::from_secs(60));
+        send(&control_tx, Command::Resume);
+        simulator.next_message();
+        clock.advance(Duration::from_secs(2));
+
+        assert_eq!(elapsed(&simulator), 5.0);
+    }
+}
@@ -0,0 +1,198 @@
+use super::*;
+use crate::clock::ManualClock;
+use crate::executor::{MockExecutor, Outcome};
+use crate::trace::Kind;
+use proptest::prelude::*;
+use std::collections::HashMap;
+use std::io;
+
+#[derive(Debug, Clone)]
+struct Job {
+    priority: u8,
+    width: usize,
+    quanta: u32,
+    outcome: Outcome,
+    estimate: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct Workload {
+    jobs: Vec<Job>,
+    concurrency: usize,
+    seed: Option<u64>,
+    backfill: bool,
+}
+
+fn outcome() -> impl Strategy<Value = Outcome> {
+    prop_oneof![
+        4 => Just(Outcome::Exit(task::ExitCode::Success)),
+        2 => Just(Outcome::Exit(task::ExitCode::Failure)),
+        1 => Just(Outcome::PollError(io::ErrorKind::Other)),
+        1 => Just(Outcome::SpawnError),
+    ]
+}
+
+fn job() -> impl Strategy<Value = Job> {
+    (
+        0u8..8,
+        1usize..=3,
+        1u32..6,
+        outcome(),
+        proptest::option::of(1u64..1000),
+    )
+        .prop_map(|(priority, width, quanta, outcome, estimate)| Job {
+            priority,
+            width,
+            quanta,
+            outcome,
+            estimate,
+        })
+}
+
+fn workload() -> impl Strategy<Value = Workload> {
+    (
+        proptest::collection::vec(job(), 1..12),
+        1usize..=4,
+        proptest::option::of(any::<u64>()),
+        any::<bool>(),
+    )
+        .prop_map(|(jobs, concurrency, seed, backfill)| Workload {
+            jobs,
+            concurrency,
+            seed,
+            backfill,
+        })
+}
+
+fn run(workload: &Workload) -> (Simulator, MockExecutor) {
+    let mut executor = MockExecutor::new();
+    let mut tasks = Vec::new();
+
+    for (i, job) in workload.jobs.iter().enumerate() {
+        let path = format!("job-{}", i);
+        executor = executor.script(path.as_str(), job.quanta, job.outcome);
+        tasks.push(Task::from_spec(task::Spec {
+            width: job.width,
+            estimate: job.estimate.map(Duration::from_millis),
+            ..task::Spec::new(path, None, job.priority)
+        }));
+    }
+
+    let policy = match workload.seed {
+        Some(_) => scheduler::Policy::Random,
+        None => scheduler::Policy::Priority,
+    };
+
+    let (_control_tx, control_rx) = mpsc::channel();
+    let mut simulator = Simulator::new(tasks, control_rx)
+        .executor(executor.clone())
+        .clock(ManualClock::new())
+        .scheduler(scheduler::build(policy, workload.seed))
+        .concurrency(NonZeroUsize::new(workload.concurrency).unwrap())
+        .backfill(workload.backfill)
+        .record_trace();
+    simulator.run();
+
+    (simulator, executor)
+}
+
+fn events_of(simulator: &Simulator, id: Ulid) -> Vec<Kind> {
+    simulator
+        .trace
+        .as_ref()
+        .unwrap()
+        .events()
+        .iter()
+        .filter(|e| e.task == id)
+        .map(|e| e.kind)
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn terminated_tasks_never_transition_again(workload in workload()) {
+        let (simulator, _) = run(&workload);
+
+        for task in simulator.tasks.iter() {
+            prop_assert_eq!(task.state, task::State::Terminated);
+
+            let events = events_of(&simulator, task.get_id());
+            let terminated = events
+                .iter()
+                .position(|k| matches!(k, Kind::Terminated { .. }));
+            prop_assert_eq!(terminated, Some(events.len() - 1), "{:?}", events);
+        }
+    }
+
+    #[test]
+    fn every_dispatched_task_was_ready(workload in workload()) {
+        let (simulator, _) = run(&workload);
+
+        for task in simulator.tasks.iter() {
+            let events = events_of(&simulator, task.get_id());
+            prop_assert_eq!(events[0], Kind::Admitted);
+
+            for pair in events.windows(2) {
+                match pair[1] {
+                    Kind::Dispatched { .. } => prop_assert!(
+                        matches!(
+                            pair[0],
+                            Kind::Admitted | Kind::Preempted | Kind::Resumed | Kind::Unthrottled
+                        ),
+                        "{:?}",
+                        events
+                    ),
+                    Kind::Preempted => prop_assert!(
+                        matches!(pair[0], Kind::Dispatched { .. }),
+                        "{:?}",
+                        events
+                    ),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cpu_time_is_the_sum_of_running_quanta(workload in workload()) {
+        let (simulator, executor) = run(&workload);
+        let quantum = DEFAULT_QUANTUM;
+
+        for (i, task) in simulator.tasks.iter().enumerate() {
+            let job = &workload.jobs[i];
+            let dispatches = events_of(&simulator, task.get_id())
+                .iter()
+                .filter(|k| matches!(k, Kind::Dispatched { .. }))
+                .count() as u32;
+
+            // The first dispatch only launches the process, which is stopped straight away.
+            prop_assert_eq!(task.cpu_time, quantum * dispatches.saturating_sub(1));
+
+            if job.width > workload.concurrency {
+                prop_assert_eq!(dispatches, 0);
+            } else if job.outcome == Outcome::SpawnError {
+                prop_assert_eq!(dispatches, 1);
+            } else {
+                prop_assert_eq!(dispatches, job.quanta);
+                prop_assert_eq!(executor.quanta_used(task.get_path_to_binary()), job.quanta);
+            }
+        }
+    }
+
+    #[test]
+    fn dispatched_width_never_exceeds_concurrency(workload in workload()) {
+        let (simulator, _) = run(&workload);
+        let mut used: HashMap<u64, usize> = HashMap::new();
+
+        for event in simulator.trace.as_ref().unwrap().events() {
+            if let Kind::Dispatched { .. } = event.kind {
+                *used.entry(event.quantum).or_default() +=
+                    simulator.tasks[event.task].get_spec().width;
+            }
+        }
+
+        for (quantum, width) in used {
+            prop_assert!(width <= workload.concurrency, "quantum {} used {}", quantum, width);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::registry::Registry;
+use crate::task::Task;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use ulid::Ulid;
+
+pub const DEFAULT_THRESHOLD_MS: u64 = 1000;
+
+#[derive(Default)]
+struct Wait {
+    since: Option<u64>,
+    longest: u64,
+    warned: bool,
+    episodes: usize,
+}
+
+pub struct StarvationDetector {
+    threshold: Duration,
+    waits: HashMap<Ulid, Wait>,
+    queue: VecDeque<(u64, Ulid)>,
+    observed: u64,
+}
+
+impl StarvationDetector {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            waits: HashMap::new(),
+            queue: VecDeque::new(),
+            observed: 0,
+        }
+    }
+
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    pub fn set_threshold(&mut self, threshold: Duration) {
+        self.threshold = threshold;
+    }
+
+    pub fn enqueued(&mut self, id: Ulid, quantum: u64) {
+        self.waits.entry(id).or_default().since = Some(quantum);
+        self.queue.push_back((quantum, id));
+    }
+
+    pub fn dequeued(&mut self, id: Ulid, quantum: u64) {
+        let wait = self.waits.entry(id).or_default();
+        if let Some(since) = wait.since.take() {
+            wait.longest = wait.longest.max(quantum.saturating_sub(since));
+        }
+        wait.warned = false;
+    }
+
+    pub fn observe(&mut self, tasks: &Registry, quantum: u64, length: Duration) {
+        self.observed = quantum;
+
+        while let Some(&(since, id)) = self.queue.front() {
+            let waited = (quantum + 1).saturating_sub(since);
+            if length * waited as u32 <= self.threshold {
+                break;
+            }
+            self.queue.pop_front();
+
+            let wait = self.waits.entry(id).or_default();
+            if wait.since != Some(since) || wait.warned {
+                continue;
+            }
+
+            wait.warned = true;
+            wait.episodes += 1;
+            crate::say!(
+                "Starvation warning: PID: {} with priority: {} has waited {} seconds without CPU",
+                tasks[id].label(),
+                tasks[id].priority,
+                (length * waited as u32).as_secs_f64()
+            );
+        }
+    }
+
+    fn longest(&self, wait: &Wait) -> u64 {
+        let current = wait
+            .since
+            .map_or(0, |since| (self.observed + 1).saturating_sub(since));
+        wait.longest.max(current)
+    }
+
+    pub fn print_report(&self, tasks: &Registry, length: Duration) {
+        let starved: Vec<(&Task, &Wait)> = tasks
+            .iter()
+            .filter_map(|t| self.waits.get(&t.get_id()).map(|w| (t, w)))
+            .filter(|(_, w)| w.episodes > 0)
+            .collect();
+
+        crate::report!(
+            "------------------------------------------\n\
+             STARVATION REPORT\n\
+             Threshold:      {} seconds",
+            self.threshold.as_secs_f64()
+        );
+
+        if starved.is_empty() {
+            crate::report!("No task exceeded the threshold");
+        }
+
+        for (task, wait) in starved {
+            crate::report!(
+                "PID:            {}\n\
+                 Priority:       {}\n\
+                 Longest Wait:   {} seconds\n\
+                 Episodes:       {}",
+                task.label(),
+                task.priority,
+                (length * self.longest(wait) as u32).as_secs_f64(),
+                wait.episodes
+            );
+        }
+
+        crate::report!("------------------------------------------");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUANTUM: Duration = Duration::from_millis(150);
+
+    fn registry() -> (Registry, Ulid) {
+        let mut tasks = Registry::default();
+        let id = tasks.insert(Task::new("waiter", None, 5));
+        (tasks, id)
+    }
+
+    #[test]
+    fn warns_once_the_wait_exceeds_the_threshold() {
+        let (tasks, id) = registry();
+        let mut detector = StarvationDetector::new(Duration::from_millis(300));
+
+        detector.enqueued(id, 1);
+        detector.observe(&tasks, 1, QUANTUM);
+        detector.observe(&tasks, 2, QUANTUM);
+        assert_eq!(detector.waits[&id].episodes, 0);
+
+        detector.observe(&tasks, 3, QUANTUM);
+        detector.observe(&tasks, 4, QUANTUM);
+        assert_eq!(detector.waits[&id].episodes, 1);
+
+        detector.dequeued(id, 5);
+        assert_eq!(detector.waits[&id].longest, 4);
+    }
+
+    #[test]
+    fn a_new_wait_starts_a_new_episode() {
+        let (tasks, id) = registry();
+        let mut detector = StarvationDetector::new(Duration::from_millis(150));
+
+        detector.enqueued(id, 1);
+        detector.observe(&tasks, 1, QUANTUM);
+        detector.observe(&tasks, 2, QUANTUM);
+        detector.dequeued(id, 3);
+
+        detector.enqueued(id, 4);
+        detector.dequeued(id, 4);
+        detector.observe(&tasks, 4, QUANTUM);
+        detector.observe(&tasks, 5, QUANTUM);
+        assert_eq!(detector.waits[&id].episodes, 1);
+
+        detector.enqueued(id, 6);
+        detector.observe(&tasks, 6, QUANTUM);
+        detector.observe(&tasks, 7, QUANTUM);
+        assert_eq!(detector.waits[&id].episodes, 2);
+        assert_eq!(detector.longest(&detector.waits[&id]), 2);
+    }
+}
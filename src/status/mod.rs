@@ -0,0 +1,115 @@
+use crate::task::TaskSnapshot;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(all(unix, feature = "process"))]
+extern "C" fn request_dump(_signal: i32) {
+    REQUESTED.store(true, Ordering::Relaxed);
+}
+
+#[cfg(all(unix, feature = "process"))]
+pub fn dump_on_sigusr1() {
+    use nix::sys::signal::{self, SigHandler, Signal};
+
+    let _ = unsafe { signal::signal(Signal::SIGUSR1, SigHandler::Handler(request_dump)) };
+}
+
+pub fn take_request() -> bool {
+    REQUESTED.swap(false, Ordering::Relaxed)
+}
+
+pub fn table<'a>(tasks: impl Iterator<Item = &'a TaskSnapshot>, length: Duration) -> String {
+    let tasks: Vec<&TaskSnapshot> = tasks.collect();
+    let niced = tasks.iter().any(|task| task.nice.is_some());
+    let nice = |value: String| {
+        if niced {
+            format!("  {:>4}", value)
+        } else {
+            String::new()
+        }
+    };
+    let mut table = format!(
+        "{:<26}  {:<20}  {:>7}  {:<18}  {:>3}{}  {:>9}  {:>9}  {:>4}\n",
+        "ID",
+        "NAME",
+        "PID",
+        "STATE",
+        "PRI",
+        nice("NICE".to_string()),
+        "CPU",
+        "WAITED",
+        "RUNS"
+    );
+    for task in tasks {
+        let name = match task.name.char_indices().nth(20) {
+            Some((end, _)) => format!("{}~", &task.name[..end - 1]),
+            None => task.name.clone(),
+        };
+        let _ = writeln!(
+            table,
+            "{:<26}  {:<20}  {:>7}  {:<18}  {:>3}{}  {:>8.2}s  {:>8.2}s  {:>4}",
+            task.id,
+            name,
+            task.pid.map_or("-".to_string(), |pid| pid.to_string()),
+            task.state.to_string(),
+            task.effective_priority,
+            nice(task.nice.map_or("-".to_string(), |nice| nice.to_string())),
+            task.cpu_time.as_secs_f64(),
+            (length * task.waited as u32).as_secs_f64(),
+            task.runs
+        );
+    }
+    table.pop();
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::Registry;
+    use crate::task::{BlockReason, Spec, State, Task};
+    use std::thread;
+
+    #[test]
+    fn tasks_are_listed_one_per_aligned_row() {
+        let mut tasks = Registry::default();
+        let done = tasks.insert(Task::new("/bin/echo", None, 2));
+        tasks[done].set_state(State::Terminated);
+        tasks[done].cpu_time = Duration::from_millis(300);
+        tasks[done].waited = 2;
+        tasks[done].runs = 2;
+        let queued = tasks.insert(Task::from_spec(Spec {
+            name: Some("nightly-report-generator".to_string()),
+            ..Spec::new("/usr/local/bin/report", None, 5)
+        }));
+        tasks[queued].set_state(State::Blocked(BlockReason::Dependency));
+        tasks[queued].enqueued_at = 1;
+
+        let snapshots: Vec<TaskSnapshot> = tasks.iter().map(|task| task.snapshot(5)).collect();
+        let table = thread::spawn(move || table(snapshots.iter(), Duration::from_millis(100)))
+            .join()
+            .unwrap();
+        let rows: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            rows[0],
+            "ID                          NAME                      PID  STATE               PRI        CPU     WAITED  RUNS"
+        );
+        assert_eq!(
+            rows[1],
+            format!(
+                "{}  echo                        -  TERMINATED            2      0.30s      0.20s     2",
+                done
+            )
+        );
+        assert_eq!(
+            rows[2],
+            format!(
+                "{}  nightly-report-gene~        -  BLOCKED:DEPENDENCY    5      0.00s      0.40s     0",
+                queued
+            )
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use crate::task::{self, BlockReason, ExitCode, MemorySample, Spec, State, Task};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_DB_PATH: &str = "psched.db";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS tasks (
+        id              TEXT PRIMARY KEY,
+        path_to_binary  TEXT NOT NULL,
+        args            TEXT,
+        priority        INTEGER NOT NULL,
+        state           TEXT NOT NULL,
+        exit_code       INTEGER,
+        duration        REAL NOT NULL,
+        submitted_at    REAL NOT NULL,
+        finished_at     REAL
+    );
+";
+
+const COLUMNS: &[(&str, &str)] = &[
+    ("spec", "TEXT"),
+    ("peak_resident_kib", "INTEGER"),
+    ("memory", "TEXT"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Record {
+    pub id: String,
+    pub name: String,
+    pub path_to_binary: String,
+    pub args: Option<Vec<String>>,
+    pub priority: u8,
+    pub state: String,
+    pub exit_code: Option<i64>,
+    pub duration: f64,
+    pub submitted_at: f64,
+    pub finished_at: Option<f64>,
+    pub peak_resident_kib: Option<i64>,
+    pub memory: Vec<MemorySample>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+fn unix_seconds(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+fn state_to_sql(state: State) -> &'static str {
+    match state {
+        State::New => "NEW",
+        State::Ready => "READY",
+        State::Running => "RUNNING",
+        State::Waiting => "WAITING",
+        State::Blocked(BlockReason::Io) => "BLOCKED:IO",
+        State::Blocked(BlockReason::Resource) => "BLOCKED:RESOURCE",
+        State::Blocked(BlockReason::Dependency) => "BLOCKED:DEPENDENCY",
+        State::Suspended => "SUSPENDED",
+        State::Terminated => "TERMINATED",
+    }
+}
+
+fn exit_code_to_sql(exit_code: Option<ExitCode>) -> Option<i64> {
+    exit_code.map(|e| match e {
+        ExitCode::Success => 0,
+        ExitCode::Failure => 1,
+        ExitCode::Poisoned => 2,
+    })
+}
+
+fn migrate(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    let mut statement = conn.prepare("SELECT name FROM pragma_table_info('tasks')")?;
+    let existing = statement
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (name, kind) in COLUMNS {
+        if !existing.iter().any(|column| column == name) {
+            conn.execute_batch(&format!("ALTER TABLE tasks ADD COLUMN {} {}", name, kind))?;
+        }
+    }
+
+    Ok(())
+}
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        migrate(&conn)?;
+        Ok(Self { conn })
+    }
+
+    pub fn insert(&self, task: &Task) -> Result<(), Box<dyn Error>> {
+        let args = task.get_args().map(serde_json::to_string).transpose()?;
+        let spec = serde_json::to_string(task.get_spec())?;
+
+        self.conn.execute(
+            "INSERT INTO tasks (id, path_to_binary, args, priority, state, exit_code, duration, submitted_at, spec)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                task.get_id().to_string(),
+                task.get_path_to_binary().to_string_lossy(),
+                args,
+                task.priority,
+                state_to_sql(task.state),
+                exit_code_to_sql(task.exit_code),
+                task.duration,
+                unix_seconds(task.get_date_time_created()),
+                spec,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_outcome(&self, task: &Task) -> Result<(), Box<dyn Error>> {
+        let memory = serde_json::to_string(&task.memory)?;
+
+        self.conn.execute(
+            "UPDATE tasks SET state = ?2, exit_code = ?3, duration = ?4, finished_at = ?5,
+             peak_resident_kib = ?6, memory = ?7 WHERE id = ?1",
+            params![
+                task.get_id().to_string(),
+                state_to_sql(task.state),
+                exit_code_to_sql(task.exit_code),
+                task.duration,
+                unix_seconds(SystemTime::now()),
+                task.peak_resident_kib().map(|kib| kib as i64),
+                memory,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn load_unfinished(&self) -> Result<Vec<Task>, Box<dyn Error>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, path_to_binary, args, priority, submitted_at, spec FROM tasks
+             WHERE state != 'TERMINATED' ORDER BY submitted_at, id",
+        )?;
+
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, u8>(3)?,
+                row.get::<_, f64>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        })?;
+
+        let mut tasks = Vec::new();
+        for row in rows {
+            let (id, path_to_binary, args, priority, submitted_at, spec) = row?;
+            let spec = match spec {
+                Some(spec) => serde_json::from_str(&spec)?,
+                None => {
+                    let args = args.map(|a| serde_json::from_str(&a)).transpose()?;
+                    task::Spec::new(path_to_binary, args, priority)
+                }
+            };
+            let elapsed = (unix_seconds(SystemTime::now()) - submitted_at).max(0.0);
+
+            tasks.push(Task::from_checkpoint(task::Checkpoint {
+                id: id.parse()?,
+                spec,
+                state: State::New,
+                exit_code: None,
+                duration: 0.0,
+                elapsed,
+                cpu_time: Duration::ZERO,
+            }));
+        }
+
+        Ok(tasks)
+    }
+
+    pub fn records_since(&self, since: f64) -> Result<Vec<Record>, Box<dyn Error>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, path_to_binary, args, priority, state, exit_code, duration, submitted_at, finished_at,
+             peak_resident_kib, memory, spec FROM tasks WHERE submitted_at >= ?1 ORDER BY submitted_at, id",
+        )?;
+
+        let rows = statement.query_map([since], |row| {
+            Ok((
+                Record {
+                    id: row.get(0)?,
+                    name: String::new(),
+                    path_to_binary: row.get(1)?,
+                    args: None,
+                    priority: row.get(3)?,
+                    state: row.get(4)?,
+                    exit_code: row.get(5)?,
+                    duration: row.get(6)?,
+                    submitted_at: row.get(7)?,
+                    finished_at: row.get(8)?,
+                    peak_resident_kib: row.get(9)?,
+                    memory: Vec::new(),
+                    metadata: BTreeMap::new(),
+                },
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(10)?,
+                row.get::<_, Option<String>>(11)?,
+            ))
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            let (mut record, args, memory, spec) = row?;
+            record.args = args.map(|a| serde_json::from_str(&a)).transpose()?;
+            let spec = match spec {
+                Some(spec) => serde_json::from_str(&spec)?,
+                None => Spec::new(&record.path_to_binary, None, record.priority),
+            };
+            record.name = spec.name();
+            record.metadata = spec.metadata;
+            if let Some(memory) = memory {
+                record.memory = serde_json::from_str(&memory)?;
+            }
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::executor::{MockExecutor, Outcome};
+use crate::procfs;
+use crate::simulator::Stats;
+use crate::task::{ExitCode, Task};
+use std::time::Duration;
+
+const PRIORITIES: usize = 8;
+const MAX_QUANTA: usize = 5;
+
+pub fn workload(n: usize) -> (Vec<Task>, MockExecutor) {
+    let mut executor = MockExecutor::new();
+    let mut tasks = Vec::with_capacity(n);
+
+    for i in 0..n {
+        let path = format!("stress-{}", i);
+        let quanta = 1 + (i * 7) % MAX_QUANTA;
+        let outcome = if i % 97 == 0 {
+            ExitCode::Failure
+        } else {
+            ExitCode::Success
+        };
+
+        executor = executor.script(path.as_str(), quanta as u32, Outcome::Exit(outcome));
+        tasks.push(Task::new(path, None, ((i * 13) % PRIORITIES) as u8));
+    }
+
+    (tasks, executor)
+}
+
+pub fn print_report(stats: &Stats, wall: Duration) {
+    let per_decision = if stats.decisions == 0 {
+        Duration::ZERO
+    } else {
+        stats.dispatch_time / stats.decisions as u32
+    };
+    let memory = match procfs::memory() {
+        Some(memory) => format!(
+            "{:.1} MiB resident, {:.1} MiB peak",
+            memory.resident_kib as f64 / 1024.0,
+            memory.peak_kib as f64 / 1024.0
+        ),
+        None => "unavailable".to_string(),
+    };
+
+    println!(
+        "------------------------------------------\n\
+         STRESS REPORT\n\
+         Tasks:          {}\n\
+         Quanta:         {}\n\
+         Decisions:      {}\n\
+         Trace Events:   {}\n\
+         Migrations:     {} ({} seconds of migration cost)\n\
+         Wall Time:      {} seconds\n\
+         Dispatch Time:  {} seconds\n\
+         Per Decision:   {:.3} µs\n\
+         Memory:         {}\n\
+         ------------------------------------------",
+        stats.tasks,
+        stats.quanta,
+        stats.decisions,
+        stats.trace_events,
+        stats.migrations,
+        stats.migration_cost.as_secs_f64(),
+        wall.as_secs_f64(),
+        stats.dispatch_time.as_secs_f64(),
+        per_decision.as_secs_f64() * 1e6,
+        memory
+    );
+}
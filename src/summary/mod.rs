@@ -0,0 +1,175 @@
+use crate::task::{ExitCode, State, Task};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use ulid::Ulid;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskResult {
+    pub id: Ulid,
+    pub name: String,
+    pub priority: u8,
+    pub state: State,
+    pub exit_code: Option<ExitCode>,
+    pub duration: f64,
+    pub cpu_time: Duration,
+    pub waited: Duration,
+    pub runs: u32,
+}
+
+impl TaskResult {
+    pub fn from_task(task: &Task, quantum: Duration) -> Self {
+        Self {
+            id: task.get_id(),
+            name: task.name(),
+            priority: task.priority,
+            state: task.state,
+            exit_code: task.exit_code,
+            duration: task.duration,
+            cpu_time: task.cpu_time,
+            waited: quantum.mul_f64(task.waited as f64),
+            runs: task.runs,
+        }
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(ExitCode::Success)
+    }
+
+    pub fn finished(&self) -> bool {
+        self.state == State::Terminated
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub stopped: bool,
+    pub quanta: u64,
+    pub elapsed: Duration,
+    pub tasks: Vec<TaskResult>,
+}
+
+impl RunSummary {
+    pub fn new<'a>(
+        tasks: impl Iterator<Item = &'a Task>,
+        quanta: u64,
+        quantum: Duration,
+        stopped: bool,
+    ) -> Self {
+        Self {
+            stopped,
+            quanta,
+            elapsed: quantum.mul_f64(quanta as f64),
+            tasks: tasks
+                .map(|task| TaskResult::from_task(task, quantum))
+                .collect(),
+        }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, TaskResult> {
+        self.tasks.iter()
+    }
+
+    pub fn get(&self, id: Ulid) -> Option<&TaskResult> {
+        self.tasks.iter().find(|result| result.id == id)
+    }
+
+    pub fn succeeded(&self) -> usize {
+        self.iter().filter(|result| result.succeeded()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.iter()
+            .filter(|result| result.exit_code == Some(ExitCode::Failure))
+            .count()
+    }
+
+    pub fn poisoned(&self) -> usize {
+        self.iter()
+            .filter(|result| result.exit_code == Some(ExitCode::Poisoned))
+            .count()
+    }
+
+    pub fn unfinished(&self) -> usize {
+        self.iter().filter(|result| !result.finished()).count()
+    }
+
+    pub fn cpu_time(&self) -> Duration {
+        self.iter().map(|result| result.cpu_time).sum()
+    }
+
+    pub fn mean_wait(&self) -> Duration {
+        match self.tasks.len() {
+            0 => Duration::ZERO,
+            len => self.iter().map(|result| result.waited).sum::<Duration>() / len as u32,
+        }
+    }
+
+    pub fn mean_duration(&self) -> f64 {
+        let finished: Vec<f64> = self
+            .iter()
+            .filter(|result| result.finished())
+            .map(|result| result.duration)
+            .collect();
+        match finished.len() {
+            0 => 0.0,
+            len => finished.iter().sum::<f64>() / len as f64,
+        }
+    }
+}
+
+impl IntoIterator for RunSummary {
+    type Item = TaskResult;
+    type IntoIter = std::vec::IntoIter<TaskResult>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tasks.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a RunSummary {
+    type Item = &'a TaskResult;
+    type IntoIter = std::slice::Iter<'a, TaskResult>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tasks.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clock::ManualClock;
+    use crate::executor::{MockExecutor, Outcome};
+    use crate::simulator::{Simulator, DEFAULT_QUANTUM};
+    use crate::task::{ExitCode, State, Task};
+    use std::sync::mpsc;
+
+    #[test]
+    fn a_run_returns_one_result_per_task_with_aggregates() {
+        let executor = MockExecutor::new()
+            .script("short", 1, Outcome::Exit(ExitCode::Success))
+            .script("broken", 2, Outcome::Exit(ExitCode::Failure));
+        let tasks = vec![Task::new("short", None, 1), Task::new("broken", None, 2)];
+        let short = tasks[0].get_id();
+        let (_control_tx, control_rx) = mpsc::channel();
+        let summary = Simulator::new(tasks, control_rx)
+            .clock(ManualClock::new())
+            .executor(executor)
+            .run();
+
+        assert!(!summary.stopped);
+        assert_eq!(summary.quanta, 3);
+        assert_eq!(summary.elapsed, DEFAULT_QUANTUM * 3);
+        assert_eq!((summary.succeeded(), summary.failed()), (1, 1));
+        assert_eq!(summary.unfinished(), 0);
+
+        let result = summary.get(short).unwrap();
+        assert_eq!(result.name, "short");
+        assert_eq!(result.state, State::Terminated);
+        assert!(result.succeeded());
+        assert_eq!(result.runs, 1);
+        assert_eq!(summary.mean_wait(), DEFAULT_QUANTUM / 2);
+
+        let names: Vec<String> = summary.into_iter().map(|result| result.name).collect();
+        assert_eq!(names, ["short", "broken"]);
+    }
+}
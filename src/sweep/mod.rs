@@ -0,0 +1,441 @@
+use crate::burst::Program;
+use crate::clock::{self, ManualClock};
+use crate::control::{self, DEFAULT_PRIORITY};
+use crate::executor::MockExecutor;
+use crate::scenario::Scenario;
+use crate::scheduler::{self, Policy};
+use crate::simulator::{self, Simulator};
+use crate::trace::Kind;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JobFile {
+    name: String,
+    burst: String,
+    #[serde(default)]
+    arrival: Option<String>,
+    #[serde(default)]
+    priority: Option<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkloadFile {
+    #[serde(rename = "task", default)]
+    tasks: Vec<JobFile>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+    pub name: String,
+    pub arrival: Duration,
+    pub burst: Program,
+    pub priority: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workload {
+    pub jobs: Vec<Job>,
+}
+
+impl std::str::FromStr for Workload {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let file: WorkloadFile = toml::from_str(s).map_err(|e| e.to_string())?;
+        if file.tasks.is_empty() {
+            return Err("no [[task]] entries".to_string());
+        }
+        let jobs = file
+            .tasks
+            .into_iter()
+            .map(|job| {
+                if job.name.is_empty() || job.name.contains(char::is_whitespace) {
+                    return Err(format!("invalid task name: {:?}", job.name));
+                }
+                let duration = |value: &str| {
+                    clock::parse_duration(value).map_err(|e| format!("task {}: {}", job.name, e))
+                };
+                let burst = match clock::parse_duration(job.burst.trim()) {
+                    Ok(duration) => Program::cpu(duration),
+                    Err(_) => job
+                        .burst
+                        .parse()
+                        .map_err(|e| format!("task {}: {}", job.name, e))?,
+                };
+                Ok(Job {
+                    burst,
+                    arrival: job
+                        .arrival
+                        .as_deref()
+                        .map_or(Ok(Duration::ZERO), duration)?,
+                    priority: job.priority.unwrap_or(DEFAULT_PRIORITY),
+                    name: job.name,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { jobs })
+    }
+}
+
+impl Workload {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        text.parse()
+    }
+
+    fn build(&self, quantum: Duration) -> (Scenario, MockExecutor) {
+        let mut script = String::new();
+        for job in &self.jobs {
+            script.push_str(&format!(
+                "at {}ns add {} --priority {} --burst {} -- {}\n",
+                job.arrival.as_nanos(),
+                job.name,
+                job.priority,
+                control::quote_words(&[job.burst.to_string()]),
+                job.name
+            ));
+        }
+
+        let scenario = script.parse().expect("sweep scenarios are well formed");
+        (scenario, MockExecutor::new().quantum(quantum))
+    }
+}
+
+pub fn parse_quanta(value: &str) -> Result<Vec<Duration>, String> {
+    let Some((range, step)) = value.split_once("..") else {
+        return value
+            .split(',')
+            .map(|quantum| simulator::parse_quantum(quantum.trim()))
+            .collect();
+    };
+    let start = simulator::parse_quantum(range.trim())?;
+    let (end, step) = match step.split_once("step") {
+        Some((end, step)) => (end, simulator::parse_quantum(step.trim())?),
+        None => (step, start),
+    };
+    let end = simulator::parse_quantum(end.trim())?;
+    if end < start {
+        return Err(format!("The range ends before it starts: {}", value));
+    }
+
+    let mut quanta = Vec::new();
+    let mut quantum = start;
+    while quantum <= end {
+        quanta.push(quantum);
+        quantum += step;
+    }
+    Ok(quanta)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub policy: Policy,
+    pub quantum: Duration,
+    pub tasks: usize,
+    pub completed: usize,
+    pub quanta: u64,
+    pub mean_wait: Duration,
+    pub mean_turnaround: Duration,
+    pub dispatches: u64,
+}
+
+impl Row {
+    pub const HEADER: &'static str = "policy,quantum_ms,tasks,completed,quanta,makespan_s,\
+                                      mean_wait_s,mean_turnaround_s,dispatches";
+
+    pub fn to_csv(&self) -> String {
+        let name = self
+            .policy
+            .to_possible_value()
+            .expect("policies are not skipped");
+        format!(
+            "{},{},{},{},{},{:.6},{:.6},{:.6},{}",
+            name.get_name(),
+            self.quantum.as_secs_f64() * 1000.0,
+            self.tasks,
+            self.completed,
+            self.quanta,
+            self.quantum.as_secs_f64() * self.quanta as f64,
+            self.mean_wait.as_secs_f64(),
+            self.mean_turnaround.as_secs_f64(),
+            self.dispatches
+        )
+    }
+}
+
+pub fn simulate(workload: &Workload, policy: Policy, quantum: Duration, seed: u64) -> Row {
+    let (scenario, executor) = workload.build(quantum);
+    let (_control_tx, control_rx) = mpsc::channel();
+    let mut simulator = Simulator::new(Vec::new(), control_rx)
+        .quantum(quantum)
+        .scheduler(scheduler::build(policy, Some(seed)))
+        .executor(executor)
+        .clock(ManualClock::new())
+        .scenario(scenario)
+        .record_trace();
+    let summary = simulator.run();
+
+    let mut admitted = HashMap::new();
+    let mut turnarounds = Vec::new();
+    for event in simulator.events() {
+        match event.kind {
+            Kind::Admitted => {
+                admitted.insert(event.task, event.quantum);
+            }
+            Kind::Terminated { .. } => {
+                if let Some(start) = admitted.get(&event.task) {
+                    turnarounds.push(quantum * (event.quantum - start) as u32);
+                }
+            }
+            Kind::Dispatched { .. }
+            | Kind::Preempted
+            | Kind::Suspended
+            | Kind::Resumed
+            | Kind::Throttled
+            | Kind::Unthrottled => {}
+        }
+    }
+    let mean_turnaround = match turnarounds.len() {
+        0 => Duration::ZERO,
+        len => turnarounds.iter().sum::<Duration>() / len as u32,
+    };
+
+    Row {
+        policy,
+        quantum,
+        tasks: summary.tasks.len(),
+        completed: summary.iter().filter(|result| result.finished()).count(),
+        quanta: summary.quanta,
+        mean_wait: summary.mean_wait(),
+        mean_turnaround,
+        dispatches: summary.iter().map(|result| u64::from(result.runs)).sum(),
+    }
+}
+
+pub fn sweep(
+    workload: &Workload,
+    policies: &[Policy],
+    quanta: &[Duration],
+    seed: u64,
+    repeat: usize,
+) -> Vec<Row> {
+    policies
+        .iter()
+        .flat_map(|&policy| quanta.iter().map(move |&quantum| (policy, quantum)))
+        .flat_map(|(policy, quantum)| {
+            (0..repeat as u64)
+                .map(move |run| simulate(workload, policy, quantum, seed.wrapping_add(run)))
+        })
+        .collect()
+}
+
+fn t95(df: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+    match df {
+        0 => 0.0,
+        1..=30 => TABLE[df - 1],
+        31..=40 => 2.021,
+        41..=60 => 2.000,
+        61..=120 => 1.980,
+        _ => 1.960,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub stddev: f64,
+    pub ci95: f64,
+}
+
+impl Estimate {
+    pub fn of(samples: &[f64]) -> Self {
+        let n = samples.len();
+        let mean = samples.iter().sum::<f64>() / n.max(1) as f64;
+        let variance = match n {
+            0 | 1 => 0.0,
+            n => samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64,
+        };
+        let stddev = variance.sqrt();
+        Self {
+            mean,
+            stddev,
+            ci95: t95(n.saturating_sub(1)) * stddev / (n.max(1) as f64).sqrt(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub policy: Policy,
+    pub quantum: Duration,
+    pub runs: usize,
+    pub tasks: usize,
+    pub completed: Estimate,
+    pub makespan: Estimate,
+    pub mean_wait: Estimate,
+    pub mean_turnaround: Estimate,
+    pub dispatches: Estimate,
+}
+
+impl Summary {
+    pub fn of(runs: &[Row]) -> Self {
+        let estimate =
+            |metric: fn(&Row) -> f64| Estimate::of(&runs.iter().map(metric).collect::<Vec<_>>());
+        Self {
+            policy: runs[0].policy,
+            quantum: runs[0].quantum,
+            runs: runs.len(),
+            tasks: runs[0].tasks,
+            completed: estimate(|row| row.completed as f64),
+            makespan: estimate(|row| row.quantum.as_secs_f64() * row.quanta as f64),
+            mean_wait: estimate(|row| row.mean_wait.as_secs_f64()),
+            mean_turnaround: estimate(|row| row.mean_turnaround.as_secs_f64()),
+            dispatches: estimate(|row| row.dispatches as f64),
+        }
+    }
+
+    pub fn header() -> String {
+        let mut header = "policy,quantum_ms,runs,tasks".to_string();
+        for metric in [
+            "completed",
+            "makespan_s",
+            "mean_wait_s",
+            "mean_turnaround_s",
+            "dispatches",
+        ] {
+            header.push_str(&format!(",{0}_mean,{0}_stddev,{0}_ci95", metric));
+        }
+        header
+    }
+
+    pub fn to_csv(&self) -> String {
+        let name = self
+            .policy
+            .to_possible_value()
+            .expect("policies are not skipped");
+        let mut line = format!(
+            "{},{},{},{}",
+            name.get_name(),
+            self.quantum.as_secs_f64() * 1000.0,
+            self.runs,
+            self.tasks
+        );
+        for estimate in [
+            self.completed,
+            self.makespan,
+            self.mean_wait,
+            self.mean_turnaround,
+            self.dispatches,
+        ] {
+            line.push_str(&format!(
+                ",{:.6},{:.6},{:.6}",
+                estimate.mean, estimate.stddev, estimate.ci95
+            ));
+        }
+        line
+    }
+}
+
+pub fn summary_csv(rows: &[Row], repeat: usize) -> String {
+    let mut csv = format!("{}\n", Summary::header());
+    for runs in rows.chunks(repeat.max(1)) {
+        csv.push_str(&Summary::of(runs).to_csv());
+        csv.push('\n');
+    }
+    csv
+}
+
+pub fn csv(rows: &[Row]) -> String {
+    let mut csv = format!("{}\n", Row::HEADER);
+    for row in rows {
+        csv.push_str(&row.to_csv());
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKLOAD: &str = r#"
+[[task]]
+name = "long"
+burst = "200ms"
+priority = 1
+
+[[task]]
+name = "short"
+burst = "20ms"
+arrival = "10ms"
+"#;
+
+    #[test]
+    fn the_product_of_policies_and_quanta_becomes_one_row_each() {
+        crate::output::set_quiet(true);
+        let quanta = parse_quanta("10ms..50ms step 20ms").unwrap();
+        assert_eq!(quanta, [10, 30, 50].map(Duration::from_millis).to_vec());
+        assert_eq!(parse_quanta("5ms, 1s").unwrap()[1], Duration::from_secs(1));
+        assert!(parse_quanta("20ms..10ms").is_err());
+
+        let workload: Workload = WORKLOAD.parse().unwrap();
+        let rows = sweep(
+            &workload,
+            &[Policy::Priority, Policy::Random],
+            &quanta,
+            1,
+            1,
+        );
+        assert_eq!(rows.len(), 6);
+        assert!(rows.iter().all(|row| row.completed == 2));
+        assert_eq!(rows[0].dispatches, 22);
+        assert!(rows[2].dispatches < rows[0].dispatches);
+
+        let csv = csv(&rows);
+        assert!(csv.starts_with("policy,quantum_ms,"));
+        assert_eq!(
+            csv.lines().nth(1).unwrap().split(',').next(),
+            Some("priority")
+        );
+        assert_eq!(csv.lines().nth(4).unwrap().split(',').nth(1), Some("10"));
+    }
+
+    #[test]
+    fn repeated_runs_are_summarized_with_a_confidence_interval() {
+        let estimate = Estimate::of(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert_eq!(estimate.mean, 5.0);
+        assert!((estimate.stddev - 2.138).abs() < 1e-3);
+        assert!((estimate.ci95 - 2.365 * estimate.stddev / 8f64.sqrt()).abs() < 1e-9);
+
+        crate::output::set_quiet(true);
+        let workload: Workload = WORKLOAD.parse().unwrap();
+        let quanta = [Duration::from_millis(10)];
+        let rows = sweep(
+            &workload,
+            &[Policy::Priority, Policy::Random],
+            &quanta,
+            7,
+            5,
+        );
+        assert_eq!(rows.len(), 10);
+        let csv = summary_csv(&rows, 5);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains(",mean_wait_s_mean,mean_wait_s_stddev,mean_wait_s_ci95,"));
+        let priority = Summary::of(&rows[..5]);
+        assert_eq!((priority.runs, priority.mean_wait.stddev), (5, 0.0));
+        assert!(lines[1].starts_with("priority,10,5,2,"));
+    }
+}
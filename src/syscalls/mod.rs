@@ -0,0 +1,76 @@
+use crate::executor::Executor;
+use crate::registry::Registry;
+use crate::task::Task;
+use crate::window;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use ulid::Ulid;
+
+struct Count {
+    quanta: u64,
+    total: u64,
+}
+
+fn count(totals: &[(u64, u64)], window: &RangeInclusive<u64>) -> Option<Count> {
+    let (before, inside) = window::split(totals, window);
+    let (quanta, last) = inside.fold((0, before), |(quanta, _), total| (quanta + 1, total));
+
+    (quanta > 0).then(|| Count {
+        quanta,
+        total: last.saturating_sub(before),
+    })
+}
+
+#[derive(Default)]
+pub struct SyscallCounter {
+    totals: HashMap<Ulid, Vec<(u64, u64)>>,
+}
+
+impl SyscallCounter {
+    pub fn observe(&mut self, task: &Task, executor: &dyn Executor, quantum: u64) {
+        let Some(total) = task.get_pid().and_then(|pid| executor.syscalls(pid)) else {
+            return;
+        };
+
+        self.totals
+            .entry(task.get_id())
+            .or_default()
+            .push((quantum, total));
+    }
+
+    pub fn print_report(&self, tasks: &Registry, window: &RangeInclusive<u64>) {
+        crate::report!(
+            "------------------------------------------\n\
+             SYSCALL REPORT\n\
+             Measured:       {}",
+            window::describe(window)
+        );
+
+        let mut counted = 0;
+        for task in tasks.iter() {
+            let Some(count) = self
+                .totals
+                .get(&task.get_id())
+                .and_then(|totals| count(totals, window))
+            else {
+                continue;
+            };
+            counted += 1;
+
+            crate::report!(
+                "PID:            {}\n\
+                 Syscalls:       {}\n\
+                 Per Quantum:    {:.1}",
+                task.label(),
+                count.total,
+                count.total as f64 / count.quanta as f64
+            );
+        }
+
+        if counted == 0 {
+            crate::report!("No syscalls were counted");
+        }
+
+        crate::report!("------------------------------------------");
+    }
+}
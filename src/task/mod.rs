@@ -1,21 +1,32 @@
-use nix::unistd::Pid;
+use crate::clock::{Clock, Instant, SystemTime, UNIX_EPOCH};
+use crate::executor::{Executor, Pid};
+use crate::output::{self, Style};
+use crate::procfs::ProcessStats;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::error::Error;
-use std::ffi::OsStr;
-use std::process::Command;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
-use std::time::SystemTime;
+use std::time::Duration;
 use ulid::Ulid;
 
+pub const MAX_STOP_FAILURES: u32 = 3;
+
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum Status {
     Running,
     Terminated(ExitCode),
 }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum ExitCode {
     Success,
     Failure,
+    Poisoned,
 }
 
 impl std::fmt::Display for ExitCode {
@@ -23,16 +34,36 @@ impl std::fmt::Display for ExitCode {
         match self {
             ExitCode::Success => write!(f, "0 (SUCCESS)"),
             ExitCode::Failure => write!(f, "1 (FAILURE)"),
+            ExitCode::Poisoned => write!(f, "- (POISONED)"),
         }
     }
 }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub enum BlockReason {
+    Io,
+    Resource,
+    Dependency,
+}
+
+impl std::fmt::Display for BlockReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockReason::Io => write!(f, "IO"),
+            BlockReason::Resource => write!(f, "RESOURCE"),
+            BlockReason::Dependency => write!(f, "DEPENDENCY"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum State {
     New,
     Ready,
     Running,
     Waiting,
+    Blocked(BlockReason),
+    Suspended,
     Terminated,
 }
 
@@ -43,39 +74,569 @@ impl std::fmt::Display for State {
             State::Ready => write!(f, "READY"),
             State::Running => write!(f, "RUNNING"),
             State::Waiting => write!(f, "WAITING"),
+            State::Blocked(reason) => write!(f, "BLOCKED:{}", reason),
+            State::Suspended => write!(f, "SUSPENDED"),
             State::Terminated => write!(f, "TERMINATED"),
         }
     }
 }
 
-pub struct Task<'a> {
+impl std::str::FromStr for State {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "NEW" => Ok(State::New),
+            "READY" => Ok(State::Ready),
+            "RUNNING" => Ok(State::Running),
+            "WAITING" => Ok(State::Waiting),
+            "BLOCKED:IO" => Ok(State::Blocked(BlockReason::Io)),
+            "BLOCKED:RESOURCE" => Ok(State::Blocked(BlockReason::Resource)),
+            "BLOCKED:DEPENDENCY" => Ok(State::Blocked(BlockReason::Dependency)),
+            "SUSPENDED" => Ok(State::Suspended),
+            "TERMINATED" => Ok(State::Terminated),
+            _ => Err(format!(
+                "Unknown state: {} (expected new, ready, running, waiting, blocked:io, \
+                 blocked:resource, blocked:dependency, suspended or terminated)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize)]
+pub enum Qos {
+    Guaranteed,
+    #[default]
+    Burstable,
+    BestEffort,
+}
+
+impl std::fmt::Display for Qos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Qos::Guaranteed => write!(f, "guaranteed"),
+            Qos::Burstable => write!(f, "burstable"),
+            Qos::BestEffort => write!(f, "best-effort"),
+        }
+    }
+}
+
+impl std::str::FromStr for Qos {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "guaranteed" => Ok(Qos::Guaranteed),
+            "burstable" => Ok(Qos::Burstable),
+            "best-effort" => Ok(Qos::BestEffort),
+            _ => Err(format!(
+                "Unknown QoS tier: {} (expected guaranteed, burstable or best-effort)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize)]
+pub enum MissPolicy {
+    Abort,
+    #[default]
+    Late,
+    Skip,
+}
+
+impl std::fmt::Display for MissPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MissPolicy::Abort => write!(f, "abort"),
+            MissPolicy::Late => write!(f, "late"),
+            MissPolicy::Skip => write!(f, "skip"),
+        }
+    }
+}
+
+impl std::str::FromStr for MissPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "abort" => Ok(MissPolicy::Abort),
+            "late" => Ok(MissPolicy::Late),
+            "skip" => Ok(MissPolicy::Skip),
+            _ => Err(format!(
+                "Unknown deadline miss policy: {} (expected abort, late or skip)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub enum Runtime {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl Runtime {
+    pub fn program(self) -> &'static str {
+        match self {
+            Runtime::Docker => "docker",
+            Runtime::Podman => "podman",
+        }
+    }
+}
+
+impl std::str::FromStr for Runtime {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "docker" => Ok(Runtime::Docker),
+            "podman" => Ok(Runtime::Podman),
+            _ => Err(format!(
+                "Unknown container runtime: {} (expected docker or podman)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Container {
+    pub image: String,
+    #[serde(default)]
+    pub runtime: Runtime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<f64>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub struct Sandbox {
+    #[serde(default)]
+    pub mount: bool,
+    #[serde(default)]
+    pub pid: bool,
+    #[serde(default)]
+    pub network: bool,
+    #[serde(default)]
+    pub seccomp: bool,
+}
+
+impl std::str::FromStr for Sandbox {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut sandbox = Sandbox::default();
+        for part in s.split(',') {
+            match part.trim() {
+                "mount" => sandbox.mount = true,
+                "pid" => sandbox.pid = true,
+                "net" => sandbox.network = true,
+                "seccomp" => sandbox.seccomp = true,
+                "all" => {
+                    sandbox = Sandbox {
+                        mount: true,
+                        pid: true,
+                        network: true,
+                        seccomp: true,
+                    }
+                }
+                other => {
+                    return Err(format!(
+                        "Unknown sandbox option: {} (expected mount, pid, net, seccomp or all)",
+                        other
+                    ))
+                }
+            }
+        }
+        Ok(sandbox)
+    }
+}
+
+impl std::fmt::Display for Sandbox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts = [
+            (self.mount, "mount"),
+            (self.pid, "pid"),
+            (self.network, "net"),
+            (self.seccomp, "seccomp"),
+        ];
+        let parts: Vec<&str> = parts
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, name)| *name)
+            .collect();
+        write!(f, "{}", parts.join(","))
+    }
+}
+
+thread_local! {
+    static SEEDED_IDS: RefCell<Option<(u64, ChaCha8Rng)>> = const { RefCell::new(None) };
+}
+
+pub fn seed_ids(seed: u64) {
+    SEEDED_IDS.with(|ids| *ids.borrow_mut() = Some((0, ChaCha8Rng::seed_from_u64(seed))));
+}
+
+pub fn new_id() -> Ulid {
+    let seeded = SEEDED_IDS.with(|ids| {
+        ids.borrow_mut().as_mut().map(|(count, rng)| {
+            *count += 1;
+            Ulid::from_parts(*count, rng.gen())
+        })
+    });
+    if let Some(id) = seeded {
+        return id;
+    }
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64);
+    Ulid::from_parts(millis, rand::random())
+}
+
+fn is_default_qos(qos: &Qos) -> bool {
+    *qos == Qos::default()
+}
+
+fn is_default_miss_policy(policy: &MissPolicy) -> bool {
+    *policy == MissPolicy::default()
+}
+
+fn default_jobs() -> u32 {
+    1
+}
+
+fn is_default_jobs(jobs: &u32) -> bool {
+    *jobs == 1
+}
+
+fn default_width() -> usize {
+    1
+}
+
+fn is_default_width(width: &usize) -> bool {
+    *width == 1
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Spec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub path_to_binary: PathBuf,
+    pub args: Option<Vec<String>>,
+    pub priority: u8,
+    #[serde(default = "default_width", skip_serializing_if = "is_default_width")]
+    pub width: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantum_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "is_default_miss_policy")]
+    pub on_miss: MissPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub period_ms: Option<u64>,
+    #[serde(default = "default_jobs", skip_serializing_if = "is_default_jobs")]
+    pub jobs: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gang: Option<String>,
+    #[serde(default, skip_serializing_if = "is_default_qos")]
+    pub qos: Qos,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub share: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_quota: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<Container>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_as: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<Sandbox>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub apart_from: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub near: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Spec {
+    pub fn new(
+        path_to_binary: impl Into<PathBuf>,
+        args: Option<Vec<String>>,
+        priority: u8,
+    ) -> Self {
+        Self {
+            name: None,
+            path_to_binary: path_to_binary.into(),
+            args,
+            priority,
+            width: default_width(),
+            estimate: None,
+            quantum_ms: None,
+            deadline_ms: None,
+            on_miss: MissPolicy::default(),
+            period_ms: None,
+            jobs: default_jobs(),
+            gang: None,
+            qos: Qos::default(),
+            share: None,
+            cpu_quota: None,
+            container: None,
+            host: None,
+            memory: None,
+            owner: None,
+            run_as: None,
+            sandbox: None,
+            after: Vec::new(),
+            apart_from: Vec::new(),
+            near: None,
+            burst: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    pub fn arguments(&self) -> Vec<String> {
+        let mut arguments = self.args.clone().unwrap_or_default();
+        arguments.extend(self.burst.clone());
+        arguments
+    }
+
+    pub fn name(&self) -> String {
+        match (&self.name, self.path_to_binary.file_name()) {
+            (Some(name), _) => name.clone(),
+            (None, Some(file)) => file.to_string_lossy().into_owned(),
+            (None, None) => self.path_to_binary.display().to_string(),
+        }
+    }
+
+    pub fn account(&self) -> &str {
+        self.run_as
+            .as_deref()
+            .or(self.owner.as_deref())
+            .unwrap_or("local")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: Ulid,
+    #[serde(flatten)]
+    pub spec: Spec,
+    pub state: State,
+    pub exit_code: Option<ExitCode>,
+    pub duration: f64,
+    pub elapsed: f64,
+    #[serde(default)]
+    pub cpu_time: Duration,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct TaskSnapshot {
+    pub id: Ulid,
+    pub name: String,
+    pub pid: Option<u32>,
+    pub state: State,
+    pub priority: u8,
+    pub effective_priority: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+    pub exit_code: Option<ExitCode>,
+    pub duration: f64,
+    pub cpu_time: Duration,
+    pub waited: u64,
+    pub runs: u32,
+    pub spec: Spec,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct MemorySample {
+    pub quantum: u64,
+    pub resident_kib: u64,
+}
+
+pub struct Task {
     pub state: State,
     pub duration: f64,
     pub priority: u8,
     pub exit_code: Option<ExitCode>,
+    pub cpu_time: Duration,
+    pub boost: i8,
+    pub donated: Option<u8>,
+    pub enqueued_at: u64,
+    pub kernel: Option<ProcessStats>,
+    pub stop_failures: u32,
+    pub stop_latency: Option<Duration>,
+    pub memory: Vec<MemorySample>,
+    pub processors: Vec<(u64, u32)>,
+    pub nice: Option<i32>,
+    pub waited: u64,
+    pub runs: u32,
+    pub preemptions: u32,
 
     id: Ulid,
     pid: Option<Pid>,
-    path_to_binary: &'a OsStr,
-    args: Option<Vec<&'a str>>,
+    spec: Spec,
     created: SystemTime,
+    started: Option<Instant>,
+    carried: Duration,
+    paused: Duration,
 }
 
-impl<'a> Task<'a> {
-    pub fn new(path_to_binary: &'a OsStr, args: Option<Vec<&'a str>>, priority: u8) -> Self {
+impl Task {
+    pub fn new(
+        path_to_binary: impl Into<PathBuf>,
+        args: Option<Vec<String>>,
+        priority: u8,
+    ) -> Self {
+        Self::from_spec(Spec::new(path_to_binary, args, priority))
+    }
+
+    pub fn from_spec(spec: Spec) -> Self {
         Self {
-            id: Ulid::new(),
+            id: new_id(),
             pid: None,
-            path_to_binary,
-            args,
             duration: 0.0,
             state: State::New,
-            priority,
+            priority: spec.priority,
             exit_code: None,
+            cpu_time: Duration::ZERO,
+            boost: 0,
+            donated: None,
+            enqueued_at: 0,
+            kernel: None,
+            stop_failures: 0,
+            stop_latency: None,
+            memory: Vec::new(),
+            processors: Vec::new(),
+            nice: None,
+            waited: 0,
+            runs: 0,
+            preemptions: 0,
+            spec,
             created: SystemTime::now(),
+            started: None,
+            carried: Duration::ZERO,
+            paused: Duration::ZERO,
+        }
+    }
+
+    pub fn adopt(pid: Pid, spec: Spec) -> Self {
+        Self {
+            pid: Some(pid),
+            ..Self::from_spec(spec)
+        }
+    }
+
+    pub fn from_checkpoint(checkpoint: Checkpoint) -> Self {
+        let carried = Duration::try_from_secs_f64(checkpoint.elapsed).unwrap_or_default();
+        let now = SystemTime::now();
+        let created = now.checked_sub(carried).unwrap_or(now);
+
+        let state = match checkpoint.state {
+            State::Terminated => State::Terminated,
+            _ => State::New,
+        };
+
+        Self {
+            id: checkpoint.id,
+            pid: None,
+            duration: checkpoint.duration,
+            state,
+            priority: checkpoint.spec.priority,
+            exit_code: checkpoint.exit_code,
+            cpu_time: checkpoint.cpu_time,
+            boost: 0,
+            donated: None,
+            enqueued_at: 0,
+            kernel: None,
+            stop_failures: 0,
+            stop_latency: None,
+            memory: Vec::new(),
+            processors: Vec::new(),
+            nice: None,
+            waited: 0,
+            runs: 0,
+            preemptions: 0,
+            spec: checkpoint.spec,
+            created,
+            started: None,
+            carried,
+            paused: Duration::ZERO,
+        }
+    }
+
+    pub fn checkpoint(&self, clock: &dyn Clock) -> Checkpoint {
+        let elapsed = match self.state {
+            State::Terminated => 0.0,
+            _ => self.elapsed(clock),
+        };
+
+        Checkpoint {
+            id: self.id,
+            spec: Spec {
+                priority: self.priority,
+                ..self.spec.clone()
+            },
+            state: self.state,
+            exit_code: self.exit_code,
+            duration: self.duration,
+            elapsed,
+            cpu_time: self.cpu_time,
         }
     }
 
+    pub fn snapshot(&self, quantum: u64) -> TaskSnapshot {
+        let waited = match self.state {
+            State::Ready | State::Waiting | State::Blocked(_) if self.enqueued_at <= quantum => {
+                self.waited + quantum - self.enqueued_at
+            }
+            _ => self.waited,
+        };
+
+        TaskSnapshot {
+            id: self.id,
+            name: self.name(),
+            pid: self.pid.map(Pid::as_raw),
+            state: self.state,
+            priority: self.priority,
+            effective_priority: self.effective_priority(),
+            nice: self.nice,
+            exit_code: self.exit_code,
+            duration: self.duration,
+            cpu_time: self.cpu_time,
+            waited,
+            runs: self.runs,
+            spec: self.spec.clone(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.spec.name()
+    }
+
+    pub fn is_called(&self, reference: &str) -> bool {
+        self.name() == reference || self.id.to_string().eq_ignore_ascii_case(reference)
+    }
+
+    pub fn label(&self) -> String {
+        format!("{} ({})", self.id, self.name())
+    }
+
     pub fn get_id(&self) -> Ulid {
         self.id
     }
@@ -84,168 +645,358 @@ impl<'a> Task<'a> {
         self.created
     }
 
-    pub fn run(&mut self, tx: mpsc::Sender<Status>) {
-        if self.pid.is_none() {
-            self.state = State::Running;
+    pub fn get_spec(&self) -> &Spec {
+        &self.spec
+    }
+
+    pub fn get_path_to_binary(&self) -> &Path {
+        &self.spec.path_to_binary
+    }
 
-            let mut command = Command::new(&self.path_to_binary);
+    pub fn get_args(&self) -> Option<&[String]> {
+        self.spec.args.as_deref()
+    }
 
-            if let Some(arguments) = &self.args {
-                command.args(arguments);
-            }
+    pub fn set_state(&mut self, state: State) {
+        debug_assert!(
+            self.state != State::Terminated,
+            "PID: {} moved from TERMINATED to {}",
+            self.id,
+            state
+        );
+        self.state = state;
+    }
+
+    pub fn get_pid(&self) -> Option<Pid> {
+        self.pid
+    }
+
+    pub fn boosted_priority(&self) -> u8 {
+        (i16::from(self.priority) - i16::from(self.boost)).clamp(0, i16::from(u8::MAX)) as u8
+    }
+
+    pub fn effective_priority(&self) -> u8 {
+        let boosted = self.boosted_priority();
+        self.donated.map_or(boosted, |donated| donated.min(boosted))
+    }
+
+    pub fn has_started(&self) -> bool {
+        self.pid.is_some()
+    }
+
+    pub fn remaining_estimate(&self) -> Option<Duration> {
+        self.spec
+            .estimate
+            .map(|estimate| estimate.saturating_sub(self.cpu_time))
+    }
+
+    pub fn quantum(&self, default: Duration) -> Duration {
+        self.spec.quantum_ms.map_or(default, Duration::from_millis)
+    }
+
+    pub fn start_clock(&mut self, now: Instant) {
+        self.started.get_or_insert(now);
+    }
+
+    pub fn restart_clock(&mut self, now: Instant) {
+        self.started = Some(now);
+    }
 
-            let mut child = match command.spawn() {
-                Ok(child) => child,
+    pub fn elapsed(&self, clock: &dyn Clock) -> f64 {
+        let since = self.started.map_or(Duration::ZERO, |started| {
+            clock.now().saturating_duration_since(started)
+        });
+        (self.carried + since)
+            .saturating_sub(self.paused)
+            .as_secs_f64()
+    }
+
+    pub fn add_paused_time(&mut self, paused: Duration) {
+        self.paused += paused;
+    }
+
+    pub fn run(
+        &mut self,
+        tx: mpsc::Sender<(Ulid, Status)>,
+        executor: &mut dyn Executor,
+        clock: &dyn Clock,
+    ) -> io::Result<()> {
+        if self.pid.is_none() {
+            self.set_state(State::Running);
+
+            let pid = match executor.spawn(self.id, &self.spec) {
+                Ok(pid) => pid,
                 Err(err) => {
                     self.exit_code = Some(ExitCode::Failure);
-                    self.state = State::Terminated;
-                    let now = SystemTime::now();
-                    self.duration += now.duration_since(self.created).unwrap().as_secs_f64();
+                    self.set_state(State::Terminated);
+                    self.duration += self.elapsed(clock);
 
                     self.print_with_error(&err);
 
-                    tx.send(Status::Terminated(ExitCode::Failure)).unwrap();
-                    return;
+                    tx.send((self.id, Status::Terminated(ExitCode::Failure)))
+                        .unwrap();
+                    return Ok(());
                 }
             };
 
-            self.pid = Some(Pid::from_raw(child.id() as i32));
+            self.pid = Some(pid);
+            self.sample_kernel_stats(pid, executor);
 
-            match child.try_wait() {
-                Ok(Some(exit_status)) => {
-                    if exit_status.success() {
-                        self.exit_code = Some(ExitCode::Success);
-                        tx.send(Status::Terminated(ExitCode::Success)).unwrap();
-                    } else {
-                        self.exit_code = Some(ExitCode::Failure);
-                        tx.send(Status::Terminated(ExitCode::Failure)).unwrap();
-                    }
+            match executor.poll(pid) {
+                Ok(Status::Terminated(exit_code)) => {
+                    self.exit_code = Some(exit_code);
+                    tx.send((self.id, Status::Terminated(exit_code))).unwrap();
 
-                    self.state = State::Terminated;
-                    let now = SystemTime::now();
-                    self.duration += now.duration_since(self.created).unwrap().as_secs_f64();
+                    self.set_state(State::Terminated);
+                    self.duration += self.elapsed(clock);
                     self.print();
-                    return;
                 }
-                Ok(None) => {
-                    self.state = State::Running;
+                Ok(Status::Running) => {
+                    self.set_state(State::Running);
                     self.print();
-                    tx.send(Status::Running).unwrap();
-                    self.pause();
-                    return;
+                    tx.send((self.id, Status::Running)).unwrap();
+                    // The process is stopped as soon as it is launched, so this dispatch is not
+                    // charged CPU time. A failed stop is counted in stop_failures and retried at
+                    // the next preemption.
+                    let _ = self.pause(executor);
                 }
                 Err(err) => {
-                    let now = SystemTime::now();
-                    self.duration += now.duration_since(self.created).unwrap().as_secs_f64();
+                    self.duration += self.elapsed(clock);
                     self.exit_code = Some(ExitCode::Failure);
-                    self.state = State::Terminated;
+                    self.set_state(State::Terminated);
                     self.print_with_error(&err);
 
-                    tx.send(Status::Terminated(ExitCode::Failure)).unwrap();
-                    return;
+                    tx.send((self.id, Status::Terminated(ExitCode::Failure)))
+                        .unwrap();
                 }
             }
+            Ok(())
         } else {
-            self.resume();
+            self.resume(executor)
         }
     }
 
-    pub fn pause(&mut self) {
+    pub fn pause(&mut self, executor: &mut dyn Executor) -> io::Result<()> {
         if let Some(pid) = self.pid {
-            nix::sys::signal::kill(pid, nix::sys::signal::Signal::SIGSTOP).unwrap();
-
-            self.state = State::Waiting;
-            println!(
-                "------------------------------------------\n\
-                 PAUSED\n\
-                 PID:            {}\n\
-                 State:          {}\n\
-                 ------------------------------------------",
-                self.id, self.state,
+            let started = Instant::now();
+            let stopped = executor.stop(pid);
+            self.stop_latency = stopped.is_ok().then(|| started.elapsed());
+
+            self.set_state(State::Waiting);
+            if let Err(err) = stopped {
+                self.stop_failures += 1;
+                crate::say!(
+                    "{}",
+                    output::boxed(
+                        Some("PAUSE FAILED"),
+                        &[
+                            ("PID", self.id.to_string()),
+                            (
+                                "Failures",
+                                format!("{} of {}", self.stop_failures, MAX_STOP_FAILURES)
+                            ),
+                            ("Error", output::paint(&err, Style::Red)),
+                        ],
+                    )
+                );
+                return Err(err);
+            }
+
+            self.stop_failures = 0;
+            crate::say!(
+                "{}",
+                output::boxed(
+                    Some("PAUSED"),
+                    &[("PID", self.id.to_string()), ("State", self.styled_state()),],
+                )
             );
         }
+        Ok(())
     }
 
-    pub fn resume(&mut self) {
+    pub fn resume(&mut self, executor: &mut dyn Executor) -> io::Result<()> {
         if let Some(pid) = self.pid {
-            nix::sys::signal::kill(pid, nix::sys::signal::Signal::SIGCONT).unwrap();
-
-            self.state = State::Running;
-            println!(
-                "------------------------------------------\n\
-                 RESUMED\n\
-                 PID:            {}\n\
-                 State:          {}\n\
-                 ------------------------------------------",
-                self.id, self.state,
+            executor.cont(pid)?;
+
+            self.set_state(State::Running);
+            crate::say!(
+                "{}",
+                output::boxed(
+                    Some("RESUMED"),
+                    &[("PID", self.id.to_string()), ("State", self.styled_state()),],
+                )
             );
         }
+        Ok(())
     }
 
-    pub fn print(&self) {
-        if self.state == State::Ready
-            || self.state == State::Running
-            || self.state == State::Waiting
+    pub fn freeze(&self, executor: &mut dyn Executor) -> io::Result<()> {
+        match self.pid {
+            Some(pid) => executor.stop(pid),
+            None => Ok(()),
+        }
+    }
+
+    pub fn thaw(&self, executor: &mut dyn Executor) -> io::Result<()> {
+        match self.pid {
+            Some(pid) => executor.cont(pid),
+            None => Ok(()),
+        }
+    }
+
+    pub fn kill(&mut self, executor: &mut dyn Executor) {
+        if let Some(pid) = self.pid.take() {
+            executor.kill(pid);
+        }
+    }
+
+    fn sample_kernel_stats(&mut self, pid: Pid, executor: &dyn Executor) {
+        if let Some(stats) = executor.process_stats(pid) {
+            let resident_kib = stats
+                .resident_kib
+                .or(self.kernel.and_then(|k| k.resident_kib));
+            self.kernel = Some(ProcessStats {
+                resident_kib,
+                ..stats
+            });
+        }
+    }
+
+    pub fn sample_memory(&mut self, quantum: u64) {
+        let Some(resident_kib) = self.kernel.and_then(|k| k.resident_kib) else {
+            return;
+        };
+        if self
+            .memory
+            .last()
+            .is_some_and(|last| last.resident_kib == resident_kib)
         {
-            println!(
-                "------------------------------------------\n\
-                 PID:            {}\n\
-                 State:          {}\n\
-                 ------------------------------------------",
-                self.id, self.state,
-            );
             return;
         }
+        self.memory.push(MemorySample {
+            quantum,
+            resident_kib,
+        });
+    }
+
+    pub fn sample_processor(&mut self, quantum: u64) {
+        let Some(processor) = self.kernel.and_then(|k| k.processor) else {
+            return;
+        };
+        if self
+            .processors
+            .last()
+            .is_none_or(|&(_, last)| last != processor)
+        {
+            self.processors.push((quantum, processor));
+        }
+    }
+
+    pub fn peak_resident_kib(&self) -> Option<u64> {
+        self.memory.iter().map(|sample| sample.resident_kib).max()
+    }
+
+    fn kernel_report(&self) -> Vec<(&'static str, String)> {
+        let Some(kernel) = &self.kernel else {
+            return Vec::new();
+        };
+
+        let resident = match (kernel.resident_kib, self.peak_resident_kib()) {
+            (Some(kib), Some(peak)) => format!("{} KiB (peak {} KiB)", kib, peak),
+            (Some(kib), None) => format!("{} KiB", kib),
+            _ => "-".to_string(),
+        };
+        vec![
+            ("Kernel State", kernel.state.to_string()),
+            (
+                "Kernel CPU",
+                format!("{} seconds", kernel.cpu_time.as_secs_f64()),
+            ),
+            ("Resident", resident),
+            (
+                "Switches",
+                format!(
+                    "{} voluntary, {} involuntary",
+                    kernel.voluntary_switches, kernel.involuntary_switches
+                ),
+            ),
+        ]
+    }
+
+    fn styled_state(&self) -> String {
+        let style = match (self.state, self.exit_code) {
+            (State::Terminated, Some(ExitCode::Success)) => Style::Green,
+            (State::Terminated, _) => Style::Red,
+            (State::Running, _) => Style::Cyan,
+            _ => Style::Yellow,
+        };
+        output::paint(self.state, style)
+    }
+
+    fn styled_exit_code(&self) -> String {
+        match self.exit_code {
+            Some(ExitCode::Success) => output::paint(ExitCode::Success, Style::Green),
+            Some(exit_code) => output::paint(exit_code, Style::Red),
+            None => "-".to_string(),
+        }
+    }
 
-        let exit_code_str = self
-            .exit_code
-            .as_ref()
-            .map_or("-".to_string(), |e| e.to_string());
+    pub fn print(&self) {
+        let mut fields = vec![
+            ("PID", self.id.to_string()),
+            ("Name", self.name()),
+            ("State", self.styled_state()),
+        ];
+        if self.state == State::Terminated || self.state == State::New {
+            fields.push(("Exit Code", self.styled_exit_code()));
+            fields.push(("Duration", format!("{} seconds", self.duration)));
+        }
+        fields.extend(self.kernel_report());
+        crate::say!("{}", output::boxed(None, &fields));
+    }
 
-        println!(
-            "------------------------------------------\n\
-             PID:            {}\n\
-             State:          {}\n\
-             Exit Code:      {}\n\
-             Duration:       {} seconds\n\
-             ------------------------------------------",
-            self.id, self.state, exit_code_str, self.duration,
+    pub fn print_incomplete(&self) {
+        crate::say!(
+            "{}",
+            output::boxed(
+                Some("INCOMPLETE"),
+                &[
+                    ("PID", self.id.to_string()),
+                    ("State", self.styled_state()),
+                    (
+                        "CPU Time",
+                        format!("{} seconds", self.cpu_time.as_secs_f64())
+                    ),
+                ],
+            )
         );
     }
 
     pub fn print_with_error(&self, err: &dyn Error) {
-        let exit_code_str = self
-            .exit_code
-            .as_ref()
-            .map_or("-".to_string(), |e| e.to_string());
-
-        println!(
-            "------------------------------------------\n\
-             PID:            {}\n\
-             State:          {}\n\
-             Exit Code:      {}\n\
-             Error Message:  {}\n\
-             ------------------------------------------",
-            self.id, self.state, exit_code_str, err
+        crate::say!(
+            "{}",
+            output::boxed(
+                None,
+                &[
+                    ("PID", self.id.to_string()),
+                    ("State", self.styled_state()),
+                    ("Exit Code", self.styled_exit_code()),
+                    ("Error Message", output::paint(err, Style::Red)),
+                ],
+            )
         );
     }
 
-    pub fn get_current_state(&self) -> Result<Status, nix::errno::Errno> {
-        if let Some(pid) = self.pid {
-            match nix::sys::wait::waitpid(pid, Some(nix::sys::wait::WaitPidFlag::WNOHANG)) {
-                Ok(nix::sys::wait::WaitStatus::StillAlive) => Ok(Status::Running),
-                Ok(nix::sys::wait::WaitStatus::Exited(_, exit_code)) => {
-                    if exit_code == 0 {
-                        return Ok(Status::Terminated(ExitCode::Success));
-                    } else {
-                        return Ok(Status::Terminated(ExitCode::Failure));
-                    }
-                }
-                Ok(_) => Ok(Status::Terminated(ExitCode::Failure)),
-                Err(err) => Err(err),
+    pub fn get_current_state(&mut self, executor: &mut dyn Executor) -> io::Result<Status> {
+        match self.pid {
+            Some(pid) => {
+                self.sample_kernel_stats(pid, executor);
+                executor.poll(pid)
             }
-        } else {
-            return Err(nix::errno::Errno::ESRCH);
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "task has not been started",
+            )),
         }
     }
 }
@@ -0,0 +1,171 @@
+use crate::registry::Registry;
+use crate::task::Task;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use ulid::Ulid;
+
+pub const DEFAULT_PERIOD: u64 = 10;
+
+pub fn parse_quota(value: &str) -> Result<f64, String> {
+    let quota = match value.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f64>().map(|p| p / 100.0),
+        None => value.parse::<f64>(),
+    }
+    .map_err(|_| format!("Invalid CPU quota: {}", value))?;
+
+    if quota > 0.0 && quota <= 1.0 {
+        Ok(quota)
+    } else {
+        Err(format!(
+            "Invalid CPU quota: {} (expected 0% < quota <= 100%)",
+            value
+        ))
+    }
+}
+
+#[derive(Default)]
+struct Usage {
+    period: u64,
+    used: u64,
+    throttled_since: Option<u64>,
+}
+
+pub struct Throttle {
+    period: u64,
+    usage: HashMap<Ulid, Usage>,
+    intervals: BTreeMap<Ulid, Vec<(u64, u64)>>,
+}
+
+impl Throttle {
+    pub fn new(period: u64) -> Self {
+        Self {
+            period: period.max(1),
+            usage: HashMap::new(),
+            intervals: BTreeMap::new(),
+        }
+    }
+
+    pub fn period(&self) -> u64 {
+        self.period
+    }
+
+    pub fn set_period(&mut self, period: u64) {
+        self.period = period.max(1);
+    }
+
+    pub fn throttled(&self, id: Ulid) -> bool {
+        self.usage
+            .get(&id)
+            .is_some_and(|usage| usage.throttled_since.is_some())
+    }
+
+    pub fn is_throttled(&mut self, task: &Task, quantum: u64) -> bool {
+        let Some(quota) = task.get_spec().cpu_quota else {
+            return false;
+        };
+
+        let period = quantum / self.period;
+        let usage = self.usage.entry(task.get_id()).or_default();
+
+        if usage.period != period {
+            if let Some(since) = usage.throttled_since.take() {
+                self.intervals
+                    .entry(task.get_id())
+                    .or_default()
+                    .push((since, quantum));
+                crate::say!(
+                    "Unthrottled PID: {} at the start of period {}",
+                    task.label(),
+                    period
+                );
+            }
+            usage.period = period;
+            usage.used = 0;
+        }
+
+        let budget = quota * self.period as f64;
+        if (usage.used as f64) < budget {
+            return false;
+        }
+
+        if usage.throttled_since.is_none() {
+            usage.throttled_since = Some(quantum);
+            crate::say!(
+                "Throttled PID: {} after {} of {} quanta in period {}",
+                task.label(),
+                usage.used,
+                self.period,
+                period
+            );
+        }
+
+        true
+    }
+
+    pub fn dispatched(&mut self, task: &Task, quantum: u64) {
+        if task.get_spec().cpu_quota.is_none() {
+            return;
+        }
+
+        let usage = self.usage.entry(task.get_id()).or_default();
+        usage.period = quantum / self.period;
+        usage.used += 1;
+    }
+
+    pub fn finish(&mut self, task: &Task, quantum: u64) {
+        if let Some(since) = self
+            .usage
+            .get_mut(&task.get_id())
+            .and_then(|u| u.throttled_since.take())
+        {
+            self.intervals
+                .entry(task.get_id())
+                .or_default()
+                .push((since, quantum));
+        }
+    }
+
+    pub fn print_report(&self, tasks: &Registry, quantum: Duration) {
+        let throttled: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| t.get_spec().cpu_quota.is_some())
+            .collect();
+
+        if throttled.is_empty() {
+            return;
+        }
+
+        let seconds = |q: u64| (quantum * q as u32).as_secs_f64();
+
+        crate::report!(
+            "------------------------------------------\n\
+             THROTTLE REPORT\n\
+             Period:         {} quanta",
+            self.period
+        );
+
+        for task in throttled {
+            let intervals = self
+                .intervals
+                .get(&task.get_id())
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let total: u64 = intervals.iter().map(|(start, end)| end - start).sum();
+
+            crate::report!(
+                "PID:            {}\n\
+                 CPU Quota:      {}%\n\
+                 Throttled:      {} seconds",
+                task.label(),
+                task.get_spec().cpu_quota.unwrap_or_default() * 100.0,
+                seconds(total)
+            );
+
+            for (start, end) in intervals {
+                crate::report!("  {} .. {} seconds", seconds(*start), seconds(*end));
+            }
+        }
+
+        crate::report!("------------------------------------------");
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use ulid::Ulid;
+
+const MAX_BUCKETS: u64 = 40;
+const HISTOGRAM_BINS: u64 = 10;
+const BAR_WIDTH: u64 = 30;
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Default)]
+pub struct Throughput {
+    admitted: HashMap<Ulid, u64>,
+    completions: Vec<(u64, u64)>,
+}
+
+pub fn sparkline(values: &[u64]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    values
+        .iter()
+        .map(|&value| match value {
+            0 => ' ',
+            _ => SPARKS[((value * SPARKS.len() as u64 - 1) / max) as usize],
+        })
+        .collect()
+}
+
+impl Throughput {
+    pub fn admitted(&mut self, id: Ulid, quantum: u64) {
+        self.admitted.entry(id).or_insert(quantum);
+    }
+
+    pub fn completed(&mut self, id: Ulid, quantum: u64) {
+        let admitted = self.admitted.get(&id).copied().unwrap_or(quantum);
+        self.completions.push((quantum, quantum - admitted));
+    }
+
+    pub fn buckets(&self, last: u64) -> (u64, Vec<u64>) {
+        let width = last.div_ceil(MAX_BUCKETS).max(1);
+        let mut buckets = vec![0; last.div_ceil(width) as usize];
+        for &(quantum, _) in &self.completions {
+            let bucket = (quantum.saturating_sub(1) / width) as usize;
+            if let Some(count) = buckets.get_mut(bucket) {
+                *count += 1;
+            }
+        }
+        (width, buckets)
+    }
+
+    pub fn histogram(&self) -> (u64, Vec<u64>) {
+        let longest = self.completions.iter().map(|&(_, t)| t).max().unwrap_or(0);
+        let width = (longest + 1).div_ceil(HISTOGRAM_BINS).max(1);
+        let mut bins = vec![0; (longest / width + 1) as usize];
+        for &(_, turnaround) in &self.completions {
+            bins[(turnaround / width) as usize] += 1;
+        }
+        (width, bins)
+    }
+
+    pub fn print_report(&self, last: u64, quantum: Duration) {
+        crate::report!("------------------------------------------\nTHROUGHPUT REPORT");
+        if self.completions.is_empty() {
+            crate::report!("No tasks completed\n------------------------------------------");
+            return;
+        }
+
+        let (width, buckets) = self.buckets(last);
+        let seconds = (quantum * last as u32).as_secs_f64();
+        crate::report!(
+            "Completed:      {} tasks in {} quanta ({:.2} per second)\n\
+             Per {} Quanta:  |{}| peak {}",
+            self.completions.len(),
+            last,
+            self.completions.len() as f64 / seconds,
+            width,
+            sparkline(&buckets),
+            buckets.iter().max().unwrap_or(&0)
+        );
+
+        let (width, bins) = self.histogram();
+        let most = bins.iter().copied().max().unwrap_or(1);
+        crate::report!("Turnaround (quanta):");
+        for (bin, &count) in bins.iter().enumerate() {
+            let low = bin as u64 * width;
+            let bar = "█".repeat((count * BAR_WIDTH).div_ceil(most) as usize);
+            crate::report!("  {:>5}-{:<5}  {:<30} {}", low, low + width - 1, bar, count);
+        }
+        crate::report!("------------------------------------------");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completions_are_bucketed_over_time_and_by_turnaround() {
+        let mut throughput = Throughput::default();
+        let ids: Vec<Ulid> = (0..4).map(|_| Ulid::new()).collect();
+        for &id in &ids {
+            throughput.admitted(id, 0);
+        }
+        throughput.completed(ids[0], 1);
+        throughput.completed(ids[1], 2);
+        throughput.completed(ids[2], 2);
+        throughput.completed(ids[3], 90);
+
+        let (width, buckets) = throughput.buckets(90);
+        assert_eq!(width, 3);
+        assert_eq!(buckets.len(), 30);
+        assert_eq!((buckets[0], buckets[29]), (3, 1));
+        assert_eq!(buckets.iter().sum::<u64>(), 4);
+
+        assert_eq!(
+            throughput.histogram(),
+            (10, vec![3, 0, 0, 0, 0, 0, 0, 0, 0, 1])
+        );
+        assert_eq!(sparkline(&[0, 1, 4, 8]), " ▁▄█");
+    }
+}
@@ -0,0 +1,137 @@
+use crate::procfs::{self, CpuTimes};
+use serde::Serialize;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize)]
+pub struct Sample {
+    pub quantum: u64,
+    pub seconds: f64,
+    pub ready: usize,
+    pub running: usize,
+    pub utilization: f64,
+    pub completed: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_cpu: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_memory: Option<f64>,
+}
+
+#[derive(Default)]
+pub struct TimeSeries {
+    samples: Vec<Sample>,
+    completed: usize,
+    host: bool,
+    last_cpu: Option<CpuTimes>,
+}
+
+fn share(value: Option<f64>) -> String {
+    value
+        .map(|value| format!("{:.3}", value))
+        .unwrap_or_default()
+}
+
+impl TimeSeries {
+    pub fn with_host(mut self) -> Self {
+        self.host = true;
+        self.last_cpu = procfs::cpu_times();
+        self
+    }
+
+    fn sample_host(&mut self) -> (Option<f64>, Option<f64>) {
+        let now = procfs::cpu_times();
+        let cpu = match (self.last_cpu, now) {
+            (Some(last), Some(now)) if now.total > last.total => {
+                Some((now.busy - last.busy) as f64 / (now.total - last.total) as f64)
+            }
+            _ => None,
+        };
+        if cpu.is_some() || self.last_cpu.is_none() {
+            self.last_cpu = now;
+        }
+        let memory = procfs::host_memory()
+            .filter(|memory| memory.total_kib > 0)
+            .map(|memory| {
+                memory.total_kib.saturating_sub(memory.available_kib) as f64
+                    / memory.total_kib as f64
+            });
+        (cpu, memory)
+    }
+
+    pub fn record(
+        &mut self,
+        quantum: u64,
+        length: Duration,
+        ready: usize,
+        running: usize,
+        utilization: f64,
+    ) {
+        let (host_cpu, host_memory) = if self.host {
+            self.sample_host()
+        } else {
+            (None, None)
+        };
+        self.samples.push(Sample {
+            quantum,
+            seconds: (length * quantum as u32).as_secs_f64(),
+            ready,
+            running,
+            utilization,
+            completed: self.completed,
+            host_cpu,
+            host_memory,
+        });
+    }
+
+    pub fn completed(&mut self) {
+        self.completed += 1;
+    }
+
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("quantum,seconds,ready,running,utilization,completed");
+        csv.push_str(if self.host {
+            ",host_cpu,host_memory\n"
+        } else {
+            "\n"
+        });
+        for sample in &self.samples {
+            let _ = write!(
+                csv,
+                "{},{},{},{},{:.3},{}",
+                sample.quantum,
+                sample.seconds,
+                sample.ready,
+                sample.running,
+                sample.utilization,
+                sample.completed
+            );
+            if self.host {
+                let _ = write!(
+                    csv,
+                    ",{},{}",
+                    share(sample.host_cpu),
+                    share(sample.host_memory)
+                );
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let contents = if path.extension().is_some_and(|ext| ext == "csv") {
+            self.to_csv()
+        } else {
+            serde_json::to_string_pretty(&self.samples)?
+        };
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
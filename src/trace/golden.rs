@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+fn path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{}.trace", name))
+}
+
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    let mut common = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            out += &format!("  {}\n", expected[i]);
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || common[i + 1][j] >= common[i][j + 1])
+        {
+            out += &format!("- {}\n", expected[i]);
+            i += 1;
+        } else {
+            out += &format!("+ {}\n", actual[j]);
+            j += 1;
+        }
+    }
+    out
+}
+
+pub fn assert_matches(name: &str, actual: &str) {
+    let path = path(name);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!(
+            "Missing golden trace {} ({}); rerun with UPDATE_GOLDEN=1 to create it",
+            path.display(),
+            err
+        )
+    });
+
+    if expected != actual {
+        panic!(
+            "Trace differs from golden {} (- expected, + actual; rerun with UPDATE_GOLDEN=1 to accept):\n{}",
+            path.display(),
+            diff(&expected, actual)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        let expected = "a\nb\nc\n";
+        let actual = "a\nx\nc\nd\n";
+
+        assert_eq!(diff(expected, actual), "  a\n- b\n+ x\n  c\n+ d\n");
+    }
+}
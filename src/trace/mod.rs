@@ -0,0 +1,147 @@
+use crate::task::ExitCode;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+use ulid::Ulid;
+#[cfg(test)]
+pub mod golden;
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Kind {
+    Admitted,
+    Dispatched { backfilled: bool },
+    Preempted,
+    Suspended,
+    Resumed,
+    Throttled,
+    Unthrottled,
+    Terminated { exit_code: Option<ExitCode> },
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Kind::Admitted => "admitted",
+            Kind::Dispatched { backfilled: false } => "dispatched",
+            Kind::Dispatched { backfilled: true } => "backfilled",
+            Kind::Preempted => "preempted",
+            Kind::Suspended => "suspended",
+            Kind::Resumed => "resumed",
+            Kind::Throttled => "throttled",
+            Kind::Unthrottled => "unthrottled",
+            Kind::Terminated {
+                exit_code: Some(ExitCode::Success),
+            } => "succeeded",
+            Kind::Terminated { .. } => "failed",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize)]
+pub struct Event {
+    pub quantum: u64,
+    pub task: Ulid,
+    #[serde(flatten)]
+    pub kind: Kind,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, clap::ValueEnum)]
+pub enum EventFormat {
+    Ndjson,
+}
+
+impl Event {
+    pub fn to_ndjson(&self, length: Duration, metadata: &BTreeMap<String, String>) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(object) = value.as_object_mut() {
+            if let Some(kind) = object.remove("event") {
+                object.insert("type".to_string(), kind);
+            }
+            object.insert(
+                "timestamp".to_string(),
+                (length * self.quantum as u32).as_secs_f64().into(),
+            );
+            if !metadata.is_empty() {
+                object.insert("metadata".to_string(), serde_json::json!(metadata));
+            }
+        }
+        value.to_string()
+    }
+}
+
+#[derive(Default)]
+pub struct Trace {
+    events: Vec<Event>,
+}
+
+impl Trace {
+    pub fn record(&mut self, quantum: u64, task: Ulid, kind: Kind) {
+        self.events.push(Event {
+            quantum,
+            task,
+            kind,
+        });
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        for event in &self.events {
+            serde_json::to_writer(&mut writer, event)?;
+            writeln!(writer)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub fn render(&self, name: impl Fn(Ulid) -> String) -> String {
+        self.events
+            .iter()
+            .map(|e| format!("{:>4}  {:<10}  {}\n", e.quantum, e.kind, name(e.task)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_stream_as_one_json_object_per_line() {
+        let task = Ulid::nil();
+        let event = Event {
+            quantum: 4,
+            task,
+            kind: Kind::Terminated {
+                exit_code: Some(ExitCode::Success),
+            },
+        };
+        assert_eq!(
+            event.to_ndjson(
+                Duration::from_millis(150),
+                &BTreeMap::from([("run".to_string(), "baseline".to_string())])
+            ),
+            format!(
+                "{{\"exit_code\":\"Success\",\"metadata\":{{\"run\":\"baseline\"}},\"quantum\":4,\"task\":\"{}\",\"timestamp\":0.6,\"type\":\"terminated\"}}",
+                task
+            )
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const CHECK_INTERVAL: Duration = Duration::from_millis(20);
+
+struct Beat {
+    quantum: u64,
+    phase: Option<&'static str>,
+    since: Instant,
+    limit: Duration,
+    suspended: bool,
+    reported: bool,
+    tasks: String,
+    stalls: u64,
+}
+
+impl Beat {
+    fn stalled(&self) -> Option<Duration> {
+        let stalled_for = self.since.elapsed();
+        (self.phase.is_some() && !self.suspended && !self.reported && stalled_for > self.limit)
+            .then_some(stalled_for)
+    }
+
+    fn dump(&self, stalled_for: Duration) {
+        crate::report!(
+            "------------------------------------------\n\
+             WATCHDOG\n\
+             Quantum:        {}\n\
+             Stuck In:       {}\n\
+             Stalled For:    {:.2} seconds (limit {} seconds)\n\
+             {}\n\
+             ------------------------------------------",
+            self.quantum,
+            self.phase.unwrap_or("-"),
+            stalled_for.as_secs_f64(),
+            self.limit.as_secs_f64(),
+            self.tasks
+        );
+    }
+}
+
+pub struct Watchdog {
+    multiple: u32,
+    beat: Arc<Mutex<Beat>>,
+}
+
+impl Watchdog {
+    pub fn spawn(multiple: u32) -> Self {
+        let beat = Arc::new(Mutex::new(Beat {
+            quantum: 0,
+            phase: None,
+            since: Instant::now(),
+            limit: Duration::ZERO,
+            suspended: false,
+            reported: false,
+            tasks: String::new(),
+            stalls: 0,
+        }));
+
+        let shared = Arc::clone(&beat);
+        thread::spawn(move || {
+            while Arc::strong_count(&shared) > 1 {
+                thread::sleep(CHECK_INTERVAL);
+                let mut beat = shared.lock().unwrap();
+                if let Some(stalled_for) = beat.stalled() {
+                    beat.reported = true;
+                    beat.stalls += 1;
+                    beat.dump(stalled_for);
+                }
+            }
+        });
+
+        Self { multiple, beat }
+    }
+
+    pub fn start(&self, quantum: u64, length: Duration) {
+        let mut beat = self.beat.lock().unwrap();
+        beat.quantum = quantum;
+        beat.phase = Some("dispatching");
+        beat.since = Instant::now();
+        beat.limit = length * self.multiple;
+        beat.reported = false;
+    }
+
+    pub fn enter(&self, phase: &'static str) {
+        self.beat.lock().unwrap().phase = Some(phase);
+    }
+
+    pub fn tasks(&self, table: String) {
+        self.beat.lock().unwrap().tasks = table;
+    }
+
+    pub fn finish(&self) {
+        self.beat.lock().unwrap().phase = None;
+    }
+
+    pub fn suspend(&self) {
+        self.beat.lock().unwrap().suspended = true;
+    }
+
+    pub fn resume(&self) {
+        let mut beat = self.beat.lock().unwrap();
+        beat.suspended = false;
+        beat.since = Instant::now();
+    }
+
+    pub fn stalls(&self) -> u64 {
+        self.beat.lock().unwrap().stalls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_quantum_that_overruns_the_limit_is_reported_once() {
+        let watchdog = Watchdog::spawn(2);
+        watchdog.start(1, Duration::from_millis(10));
+        watchdog.finish();
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(watchdog.stalls(), 0);
+
+        watchdog.start(2, Duration::from_millis(10));
+        watchdog.suspend();
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(watchdog.stalls(), 0);
+
+        watchdog.resume();
+        watchdog.enter("polling running tasks");
+        thread::sleep(Duration::from_millis(200));
+        assert_eq!(watchdog.stalls(), 1);
+    }
+}
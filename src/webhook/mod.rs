@@ -0,0 +1,108 @@
+use crate::otel;
+use crate::summary::TaskResult;
+use serde_json::json;
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+
+const DEFAULT_PATH: &str = "/";
+
+pub fn payload(result: &TaskResult) -> String {
+    let event = if result.succeeded() {
+        "task.succeeded"
+    } else {
+        "task.failed"
+    };
+    json!({ "event": event, "task": result }).to_string()
+}
+
+pub struct Webhooks {
+    tx: Option<mpsc::Sender<String>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Webhooks {
+    pub fn spawn(urls: Vec<String>) -> Self {
+        let (tx, rx) = mpsc::channel::<String>();
+        let worker = thread::spawn(move || {
+            for body in rx {
+                for url in &urls {
+                    if let Err(err) = otel::post(url, DEFAULT_PATH, &body) {
+                        eprintln!("Webhook {} failed: {}", url, err);
+                    }
+                }
+            }
+        });
+
+        Self {
+            tx: Some(tx),
+            worker: Some(worker),
+        }
+    }
+
+    pub fn notify(&self, result: &TaskResult) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(payload(result));
+        }
+    }
+}
+
+impl Drop for Webhooks {
+    fn drop(&mut self) {
+        self.tx.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{ExitCode, State, Task};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    #[test]
+    fn each_finished_task_is_posted_to_the_webhook() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks/psched", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.strip_prefix("Content-Length: ") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                request.push_str(&line);
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+                .unwrap();
+            (request, String::from_utf8(body).unwrap())
+        });
+
+        let mut task = Task::new("backup", None, 1);
+        task.set_state(State::Terminated);
+        task.exit_code = Some(ExitCode::Failure);
+        let webhooks = Webhooks::spawn(vec![url]);
+        webhooks.notify(&TaskResult::from_task(&task, Duration::from_millis(100)));
+        drop(webhooks);
+
+        let (request, body) = server.join().unwrap();
+        assert!(request.starts_with("POST /hooks/psched HTTP/1.1\r\n"));
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["event"], "task.failed");
+        assert_eq!(body["task"]["name"], "backup");
+    }
+}
@@ -0,0 +1,61 @@
+use clap::Args;
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Default, Clone, Copy, Args)]
+pub struct Window {
+    #[arg(long, value_name = "QUANTA", default_value_t = 0)]
+    pub warm_up: u64,
+
+    #[arg(long, value_name = "QUANTA", default_value_t = 0)]
+    pub cool_down: u64,
+}
+
+impl Window {
+    pub fn quanta(&self, last: u64) -> RangeInclusive<u64> {
+        self.warm_up + 1..=last.saturating_sub(self.cool_down)
+    }
+}
+
+pub fn describe(window: &RangeInclusive<u64>) -> String {
+    if window.is_empty() {
+        return "no quanta".to_string();
+    }
+    format!("quanta {} to {}", window.start(), window.end())
+}
+
+pub fn split<'a, T: Copy + Default>(
+    samples: &'a [(u64, T)],
+    window: &RangeInclusive<u64>,
+) -> (T, impl Iterator<Item = T> + 'a) {
+    let before = samples
+        .iter()
+        .rev()
+        .find(|(quantum, _)| quantum < window.start())
+        .map_or(T::default(), |&(_, value)| value);
+    let window = window.clone();
+    let inside = samples
+        .iter()
+        .filter(move |(quantum, _)| window.contains(quantum))
+        .map(|&(_, value)| value);
+    (before, inside)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_window_drops_warm_up_and_cool_down_quanta() {
+        let window = Window {
+            warm_up: 2,
+            cool_down: 3,
+        };
+        assert_eq!(window.quanta(10), 3..=7);
+        assert!(window.quanta(4).is_empty());
+
+        let samples = [(1, 5), (2, 8), (3, 9), (7, 20), (8, 30)];
+        let (before, inside) = split(&samples, &window.quanta(10));
+        assert_eq!(before, 8);
+        assert_eq!(inside.collect::<Vec<_>>(), [9, 20]);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::burst::{Phase, Program};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const BLOCK: usize = 64 * 1024;
+const POLL: Duration = Duration::from_millis(1);
+
+#[cfg(all(unix, feature = "process"))]
+fn cpu_time() -> Option<Duration> {
+    nix::time::clock_gettime(nix::time::ClockId::CLOCK_PROCESS_CPUTIME_ID)
+        .ok()
+        .map(Duration::from)
+}
+
+#[cfg(not(all(unix, feature = "process")))]
+fn cpu_time() -> Option<Duration> {
+    None
+}
+
+fn burn(duration: Duration) {
+    let (start, wall) = (cpu_time(), Instant::now());
+    let mut state = 1u64;
+    loop {
+        for _ in 0..10_000 {
+            state = std::hint::black_box(state.wrapping_mul(6_364_136_223_846_793_005) + 1);
+        }
+        let spent = match (start, cpu_time()) {
+            (Some(start), Some(now)) => now.saturating_sub(start),
+            _ => wall.elapsed(),
+        };
+        if spent >= duration {
+            break;
+        }
+    }
+}
+
+fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn churn(path: &Path, duration: Duration) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    let (block, mut buffer) = ([0x5a; BLOCK], vec![0; BLOCK]);
+    let started = Instant::now();
+    while started.elapsed() < duration {
+        file.write_all(&block)?;
+        file.sync_data()?;
+        file.rewind()?;
+        file.read_exact(&mut buffer)?;
+        file.rewind()?;
+    }
+    Ok(())
+}
+
+pub struct Worker {
+    scratch: PathBuf,
+    held: HashSet<String>,
+}
+
+impl Worker {
+    pub fn new(scratch: impl Into<PathBuf>) -> Self {
+        Self {
+            scratch: scratch.into(),
+            held: HashSet::new(),
+        }
+    }
+
+    fn lock_path(&self, lock: &str) -> PathBuf {
+        self.scratch
+            .join(format!("psched-{}.lock", file_name(lock)))
+    }
+
+    fn io(&self, device: &str, duration: Duration) -> io::Result<()> {
+        let path = self.scratch.join(format!(
+            "psched-worker-{}-{}.io",
+            std::process::id(),
+            file_name(device)
+        ));
+        let result = churn(&path, duration);
+        fs::remove_file(&path)?;
+        result
+    }
+
+    fn acquire(&mut self, lock: &str) -> io::Result<()> {
+        let path = self.lock_path(lock);
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{}", std::process::id())?;
+                    self.held.insert(lock.to_string());
+                    return Ok(());
+                }
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => thread::sleep(POLL),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn release(&mut self, lock: &str) -> io::Result<()> {
+        self.held.remove(lock);
+        fs::remove_file(self.lock_path(lock))
+    }
+
+    pub fn phase(&mut self, phase: &Phase) -> io::Result<()> {
+        match phase {
+            Phase::Cpu(duration) => {
+                burn(*duration);
+                Ok(())
+            }
+            Phase::Io { device, duration } => self.io(device, *duration),
+            Phase::Sleep(duration) => {
+                thread::sleep(*duration);
+                Ok(())
+            }
+            Phase::Acquire(lock) => self.acquire(lock),
+            Phase::Release(lock) => self.release(lock),
+        }
+    }
+
+    pub fn run(&mut self, program: &Program) -> io::Result<()> {
+        program
+            .phases
+            .iter()
+            .try_for_each(|phase| self.phase(phase))
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        for lock in std::mem::take(&mut self.held) {
+            let _ = fs::remove_file(self.lock_path(&lock));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_take_their_duration_and_clean_up_after_themselves() {
+        let scratch = std::env::temp_dir().join(format!("psched-{}", ulid::Ulid::new()));
+        fs::create_dir(&scratch).unwrap();
+        let program: Program = "cpu 20ms; io disk 10ms; sleep 10ms; acquire a/b; cpu 1ms"
+            .parse()
+            .unwrap();
+
+        let started = Instant::now();
+        let mut worker = Worker::new(&scratch);
+        worker.run(&program).unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(40));
+        assert!(worker.lock_path("a/b").exists());
+        drop(worker);
+        assert_eq!(fs::read_dir(&scratch).unwrap().count(), 0);
+        fs::remove_dir(scratch).unwrap();
+    }
+}